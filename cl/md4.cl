@@ -0,0 +1,168 @@
+#define F(x, y, z)	bitselect((z), (y), (x))
+#define G(x, y, z)	bitselect((x), (y), (x) ^ (z))
+#define H(x, y, z)	(((x) ^ (y)) ^ (z))
+
+#define STEP(f, a, b, c, d, x, t, s)	  \
+  (a) += f((b), (c), (d)) + (x) + (t); \
+  (a) = rotate((a), (uint)(s))
+
+union Hash {
+  unsigned char bytes[16];
+  unsigned int ints[4];
+  unsigned long longs[2];
+  uint4 vector;
+};
+typedef union Hash Hash;
+
+inline void md4(unsigned int * hash, const unsigned int * input) {
+  hash[0] = 0x67452301;
+  hash[1] = 0xefcdab89;
+  hash[2] = 0x98badcfe;
+  hash[3] = 0x10325476;
+
+  /* Round 1 */
+  STEP(F, hash[0], hash[1], hash[2], hash[3], input[0], 0, 3);
+  STEP(F, hash[3], hash[0], hash[1], hash[2], input[1], 0, 7);
+  STEP(F, hash[2], hash[3], hash[0], hash[1], input[2], 0, 11);
+  STEP(F, hash[1], hash[2], hash[3], hash[0], input[3], 0, 19);
+  STEP(F, hash[0], hash[1], hash[2], hash[3], input[4], 0, 3);
+  STEP(F, hash[3], hash[0], hash[1], hash[2], input[5], 0, 7);
+  STEP(F, hash[2], hash[3], hash[0], hash[1], input[6], 0, 11);
+  STEP(F, hash[1], hash[2], hash[3], hash[0], input[7], 0, 19);
+  STEP(F, hash[0], hash[1], hash[2], hash[3], input[8], 0, 3);
+  STEP(F, hash[3], hash[0], hash[1], hash[2], input[9], 0, 7);
+  STEP(F, hash[2], hash[3], hash[0], hash[1], input[10], 0, 11);
+  STEP(F, hash[1], hash[2], hash[3], hash[0], input[11], 0, 19);
+  STEP(F, hash[0], hash[1], hash[2], hash[3], input[12], 0, 3);
+  STEP(F, hash[3], hash[0], hash[1], hash[2], input[13], 0, 7);
+  STEP(F, hash[2], hash[3], hash[0], hash[1], input[14], 0, 11);
+  STEP(F, hash[1], hash[2], hash[3], hash[0], input[15], 0, 19);
+
+  /* Round 2 */
+  STEP(G, hash[0], hash[1], hash[2], hash[3], input[0], 0x5a827999, 3);
+  STEP(G, hash[3], hash[0], hash[1], hash[2], input[4], 0x5a827999, 5);
+  STEP(G, hash[2], hash[3], hash[0], hash[1], input[8], 0x5a827999, 9);
+  STEP(G, hash[1], hash[2], hash[3], hash[0], input[12], 0x5a827999, 13);
+  STEP(G, hash[0], hash[1], hash[2], hash[3], input[1], 0x5a827999, 3);
+  STEP(G, hash[3], hash[0], hash[1], hash[2], input[5], 0x5a827999, 5);
+  STEP(G, hash[2], hash[3], hash[0], hash[1], input[9], 0x5a827999, 9);
+  STEP(G, hash[1], hash[2], hash[3], hash[0], input[13], 0x5a827999, 13);
+  STEP(G, hash[0], hash[1], hash[2], hash[3], input[2], 0x5a827999, 3);
+  STEP(G, hash[3], hash[0], hash[1], hash[2], input[6], 0x5a827999, 5);
+  STEP(G, hash[2], hash[3], hash[0], hash[1], input[10], 0x5a827999, 9);
+  STEP(G, hash[1], hash[2], hash[3], hash[0], input[14], 0x5a827999, 13);
+  STEP(G, hash[0], hash[1], hash[2], hash[3], input[3], 0x5a827999, 3);
+  STEP(G, hash[3], hash[0], hash[1], hash[2], input[7], 0x5a827999, 5);
+  STEP(G, hash[2], hash[3], hash[0], hash[1], input[11], 0x5a827999, 9);
+  STEP(G, hash[1], hash[2], hash[3], hash[0], input[15], 0x5a827999, 13);
+
+  /* Round 3 */
+  STEP(H, hash[0], hash[1], hash[2], hash[3], input[0], 0x6ed9eba1, 3);
+  STEP(H, hash[3], hash[0], hash[1], hash[2], input[8], 0x6ed9eba1, 9);
+  STEP(H, hash[2], hash[3], hash[0], hash[1], input[4], 0x6ed9eba1, 11);
+  STEP(H, hash[1], hash[2], hash[3], hash[0], input[12], 0x6ed9eba1, 15);
+  STEP(H, hash[0], hash[1], hash[2], hash[3], input[2], 0x6ed9eba1, 3);
+  STEP(H, hash[3], hash[0], hash[1], hash[2], input[10], 0x6ed9eba1, 9);
+  STEP(H, hash[2], hash[3], hash[0], hash[1], input[6], 0x6ed9eba1, 11);
+  STEP(H, hash[1], hash[2], hash[3], hash[0], input[14], 0x6ed9eba1, 15);
+  STEP(H, hash[0], hash[1], hash[2], hash[3], input[1], 0x6ed9eba1, 3);
+  STEP(H, hash[3], hash[0], hash[1], hash[2], input[9], 0x6ed9eba1, 9);
+  STEP(H, hash[2], hash[3], hash[0], hash[1], input[5], 0x6ed9eba1, 11);
+  STEP(H, hash[1], hash[2], hash[3], hash[0], input[13], 0x6ed9eba1, 15);
+  STEP(H, hash[0], hash[1], hash[2], hash[3], input[3], 0x6ed9eba1, 3);
+  STEP(H, hash[3], hash[0], hash[1], hash[2], input[11], 0x6ed9eba1, 9);
+  STEP(H, hash[2], hash[3], hash[0], hash[1], input[7], 0x6ed9eba1, 11);
+  STEP(H, hash[1], hash[2], hash[3], hash[0], input[15], 0x6ed9eba1, 15);
+
+  hash[0] += 0x67452301;
+  hash[1] += 0xefcdab89;
+  hash[2] += 0x98badcfe;
+  hash[3] += 0x10325476;
+}
+
+//______________________________________________________________________________
+// Find the hash for a limited number of targets
+//
+// Defines:
+// CONST_BEGIN {:d} # The index of where the variable part begins
+// CONST_END {:d} # The index past of where the variable part ends
+// CONST_CHECKSUM {:d} # If defined, the algorithm of the check digits ending the variable part
+// CONST_CHECKSUM_BASE {:d} # The constant part of the checksum sum
+// CONST_CHECKSUM_LENGTH {:d} # The number of check digits
+// CONST_LENGTH {:d} # The length of the payload (salt + value + salt, depending on its position)
+// CONST_LENGTH_ON_CPU {:d} # Positions the iterations are substituting
+// CONST_MASK {:d} # If defined, the variable part is enumerated from the mask tables
+// CONST_TARGET_COUNT {:d} # The number of items in the targets array
+// CONST_UTF16LE {:d} # If defined, every character is encoded in two bytes, as UTF-16LE
+// CONST_VALUE_LENGTH {:d} # The size of the buffer of the value, a block of 64 bytes
+// CONST_WORDLIST {:d} # If defined, the variable part is read from a batch of words
+//
+// targets: Target hashes
+// output: Matched values
+// words: Batch of words, only with CONST_WORDLIST
+//______________________________________________________________________________
+__kernel void crack(constant Hash * targets,
+    global unsigned int * output,
+#ifdef CONST_WORDLIST
+    global const unsigned char * words,
+#endif
+    private const unsigned int prefix) {
+  unsigned int index = get_global_id(0);
+
+  // Buffer for the hash
+  Hash hash;
+
+  // Zero initialize
+  Value value = {};
+
+#ifdef CONST_WORDLIST
+  load(words, index, &value);
+#else
+  prepare(index, prefix, &value);
+#endif
+
+  // %%PREFIX%%
+
+  // %%TRANSFORM%%
+
+  // %%SUFFIX%%
+
+  // Inject size
+  value.longs[7] = CONST_LENGTH << 3;
+
+  // Inject padding
+  value.bytes[CONST_LENGTH] = 0x80;
+
+  // Actually cracking
+  md4(hash.ints, value.ints);
+
+#if CONST_TARGET_COUNT < 32
+#pragma unroll
+  for (int i = 0; i < CONST_TARGET_COUNT; i++) {
+    if (hash.longs[0] == targets[i].longs[0] && hash.longs[1] == targets[i].longs[1]) {
+      output[i << 1] = index;
+      output[(i << 1) + 1] = prefix;
+      return;
+    }
+  }
+#else
+  unsigned int i = 0;
+  while (i < CONST_TARGET_COUNT) {
+    if (hash.longs[1] == targets[i].longs[1]) {
+      if (hash.longs[0] == targets[i].longs[0]) {
+        output[i << 1] = index;
+        output[(i << 1) + 1] = prefix;
+        return;
+      } else {
+        i = hash.longs[0] < targets[i].longs[0]
+          ? (i << 1) + 1
+          : (i << 1) + 2;
+      }
+    } else {
+      i = hash.longs[1] < targets[i].longs[1]
+        ? (i << 1) + 1
+        : (i << 1) + 2;
+    }
+  }
+#endif //#if CONST_TARGET_COUNT
+}
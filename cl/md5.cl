@@ -17,11 +17,14 @@ union Hash {
 };
 typedef union Hash Hash;
 
-inline void md5(unsigned int * hash, const unsigned int * input) {
-  hash[0] = 0x67452301;
-  hash[1] = 0xefcdab89;
-  hash[2] = 0x98badcfe;
-  hash[3] = 0x10325476;
+// Hashes a block from the state left by the previous blocks
+inline void md5_from(unsigned int * hash,
+    const unsigned int * state,
+    const unsigned int * input) {
+  hash[0] = state[0];
+  hash[1] = state[1];
+  hash[2] = state[2];
+  hash[3] = state[3];
 
   /* Round 1 */
   STEP(F, hash[0], hash[1], hash[2], hash[3], input[0], 0xd76aa478, 7);
@@ -95,10 +98,15 @@ inline void md5(unsigned int * hash, const unsigned int * input) {
   STEP(I, hash[2], hash[3], hash[0], hash[1], input[2], 0x2ad7d2bb, 15);
   STEP(I, hash[1], hash[2], hash[3], hash[0], input[9], 0xeb86d391, 21);
 
-  hash[0] += 0x67452301;
-  hash[1] += 0xefcdab89;
-  hash[2] += 0x98badcfe;
-  hash[3] += 0x10325476;
+  hash[0] += state[0];
+  hash[1] += state[1];
+  hash[2] += state[2];
+  hash[3] += state[3];
+}
+
+inline void md5(unsigned int * hash, const unsigned int * input) {
+  const unsigned int state[4] = {0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476};
+  md5_from(hash, state, input);
 }
 
 //______________________________________________________________________________
@@ -107,16 +115,30 @@ inline void md5(unsigned int * hash, const unsigned int * input) {
 // Defines:
 // CONST_BEGIN {:d} # The index of where the variable part begins
 // CONST_END {:d} # The index past of where the variable part ends
-// CONST_BASE64_BEGIN {:d} # The index where to base64 encode from (salt length)
-// CONST_LENGTH {:d} # The length of the payload (salt + value)
-// CONST_LENGTH_ON_CPU {:d} # Decimal places the iterations are substituting
+// CONST_CHECKSUM {:d} # If defined, the algorithm of the check digits ending the variable part
+// CONST_CHECKSUM_BASE {:d} # The constant part of the checksum sum
+// CONST_CHAIN_HEX {:d} # If defined, the rounds of the chain hash the hex digits of the previous hash
+// CONST_CHAIN_ROUNDS {:d} # If defined, the number of hashes of the chain, each hashing the previous one
+// CONST_CHECKSUM_LENGTH {:d} # The number of check digits
+// CONST_HMAC_INNER {...} # If defined, the state once the inner padded key of the HMAC is hashed
+// CONST_HMAC_OUTER {...} # The state once the outer padded key of the HMAC is hashed
+// CONST_LENGTH {:d} # The length of the payload (salt + value + salt, depending on its position)
+// CONST_LENGTH_ON_CPU {:d} # Positions the iterations are substituting
+// CONST_MASK {:d} # If defined, the variable part is enumerated from the mask tables
 // CONST_TARGET_COUNT {:d} # The number of items in the targets array
+// CONST_UTF16LE {:d} # If defined, every character is encoded in two bytes, as UTF-16LE
+// CONST_VALUE_LENGTH {:d} # The size of the buffer of the value, a block of 64 bytes
+// CONST_WORDLIST {:d} # If defined, the variable part is read from a batch of words
 //
 // targets: Target hashes
 // output: Matched values
+// words: Batch of words, only with CONST_WORDLIST
 //______________________________________________________________________________
 __kernel void crack(constant Hash * targets,
     global unsigned int * output,
+#ifdef CONST_WORDLIST
+    global const unsigned char * words,
+#endif
     private const unsigned int prefix) {
   unsigned int index = get_global_id(0);
 
@@ -126,19 +148,38 @@ __kernel void crack(constant Hash * targets,
   // Zero initialize
   Value value = {};
 
-#ifdef CONST_LENGTH_ON_CPU
-  prepare(index, prefix, &value);
+#ifdef CONST_WORDLIST
+  load(words, index, &value);
 #else
-  prepare(index, &value);
+  prepare(index, prefix, &value);
 #endif
 
   // %%PREFIX%%
 
-#ifdef CONST_BASE64_BEGIN
-  // %%XOR%%
-  to_base64(&value);
-#endif
+  // %%TRANSFORM%%
+
+  // %%SUFFIX%%
 
+#ifdef CONST_HMAC_INNER
+  // Inject size, the value following the inner padded key
+  value.longs[7] = (CONST_LENGTH + 64) << 3;
+
+  // Inject padding
+  value.bytes[CONST_LENGTH] = 0x80;
+
+  // Inner hash, from the inner padded key
+  const unsigned int inner[4] = CONST_HMAC_INNER;
+  md5_from(hash.ints, inner, value.ints);
+
+  // Outer hash of the inner hash, from the outer padded key
+  Value outer = {};
+  outer.longs[0] = hash.longs[0];
+  outer.longs[1] = hash.longs[1];
+  outer.bytes[16] = 0x80;
+  outer.longs[7] = (64 + 16) << 3;
+  const unsigned int outer_state[4] = CONST_HMAC_OUTER;
+  md5_from(hash.ints, outer_state, outer.ints);
+#else
   // Inject size
   value.longs[7] = CONST_LENGTH << 3;
 
@@ -147,6 +188,18 @@ __kernel void crack(constant Hash * targets,
 
   // Actually cracking
   md5(hash.ints, value.ints);
+#endif
+
+#ifdef CONST_CHAIN_ROUNDS
+  // Every extra round hashes the hash of the previous one
+  for (unsigned int round = 1; round < CONST_CHAIN_ROUNDS; round++) {
+    Value chained = {};
+    const unsigned int length = chain_value(hash.bytes, 16, &chained);
+    chained.longs[7] = length << 3;
+    chained.bytes[length] = 0x80;
+    md5(hash.ints, chained.ints);
+  }
+#endif
 
 #if CONST_TARGET_COUNT < 32
 #pragma unroll
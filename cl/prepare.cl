@@ -1,19 +1,206 @@
 union Value {
-  unsigned char bytes[64];
-  unsigned int ints[16];
-  unsigned long longs[8];
+  unsigned char bytes[CONST_VALUE_LENGTH];
+  unsigned int ints[CONST_VALUE_LENGTH / 4];
+  unsigned long longs[CONST_VALUE_LENGTH / 8];
 };
 typedef union Value Value;
 
+/*
+ * The positions of the skeleton are given in characters. With UTF-16LE,
+ * every character takes two bytes, the second of which is left at zero
+ */
+#ifdef CONST_UTF16LE
+#define CHAR_INDEX(index) ((index) << 1)
+#else
+#define CHAR_INDEX(index) (index)
+#endif
+
+#ifdef CONST_CHAIN_ROUNDS
+/*
+ * Every extra round of a chain hashes the hash of the previous round. It is
+ * written at the start of the value as lowercase hex digits with
+ * CONST_CHAIN_HEX, or as its bytes otherwise. Returns the length written
+ */
+inline unsigned int chain_value(const unsigned char * hash,
+    const unsigned int length,
+    Value * value) {
+#ifdef CONST_CHAIN_HEX
+  for (unsigned int i = 0; i < length; i++) {
+    unsigned char high = hash[i] >> 4;
+    unsigned char low = hash[i] & 0xf;
+    value->bytes[i << 1] = high < 10 ? '0' + high : 'a' - 10 + high;
+    value->bytes[(i << 1) + 1] = low < 10 ? '0' + low : 'a' - 10 + low;
+  }
+  return length << 1;
+#else
+  for (unsigned int i = 0; i < length; i++) {
+    value->bytes[i] = hash[i];
+  }
+  return length;
+#endif
+}
+#endif
+
 /*
  * The skeleton is composed of: [SUFFIX + variable + LENGTH]
- * The size of the skeleton is a multiple of 512 bits (64 bytes)
+ * The size of the skeleton is a block of the algorithm, CONST_VALUE_LENGTH
+ * bytes (64 bytes, or 128 bytes for SHA-384 and SHA-512)
  * The SUFFIX is arbitrary
  * The LENGTH is the length of (SUFFIX + variable) in a 64 bit format
  * If LENGTH happens to be larger than 64 bits, only the lower 64 bits
  * are considered
  */
+#ifdef CONST_CHECKSUM
+/*
+ * The last CONST_CHECKSUM_LENGTH bytes of the variable part are check
+ * digits derived from the other ones. `checksum_weights` gives the weight
+ * of each position, while CONST_CHECKSUM_BASE is the constant sum of the
+ * digits of the prefix and of the literals. CONST_CHECKSUM selects the
+ * algorithm:
+ * 1: Luhn
+ * 2: mod 11 with 10 written as X
+ * 3: mod 11 with 10 and 11 written as 0
+ * 4: ISO 7064 MOD 97-10
+ */
+#define CONST_VARIABLE_END (CONST_END - CONST_CHECKSUM_LENGTH)
+inline void checksum(Value * skeleton) {
+  unsigned int sum = CONST_CHECKSUM_BASE;
+  unsigned int digit;
+
+#pragma unroll
+  for (char index = CONST_BEGIN; index < CONST_VARIABLE_END; index++) {
+    // Literals have a weight of 0
+    digit = (skeleton->bytes[CHAR_INDEX(index)] - '0') * checksum_weights[index - CONST_BEGIN];
+#if CONST_CHECKSUM == 1
+    // The digits of doubled values are summed
+    digit -= digit > 9 ? 9 : 0;
+#endif
+    sum += digit;
+  }
+
+#if CONST_CHECKSUM == 1
+  skeleton->bytes[CHAR_INDEX(CONST_VARIABLE_END)] = '0' + (10 - sum % 10) % 10;
+#elif CONST_CHECKSUM == 2
+  sum = (11 - sum % 11) % 11;
+  skeleton->bytes[CHAR_INDEX(CONST_VARIABLE_END)] = sum == 10 ? 'X' : '0' + sum;
+#elif CONST_CHECKSUM == 3
+  sum = 11 - sum % 11;
+  skeleton->bytes[CHAR_INDEX(CONST_VARIABLE_END)] = sum >= 10 ? '0' : '0' + sum;
+#else
+  sum = 98 - sum % 97;
+  skeleton->bytes[CHAR_INDEX(CONST_VARIABLE_END)] = '0' + sum / 10;
+  skeleton->bytes[CHAR_INDEX(CONST_VARIABLE_END + 1)] = '0' + sum % 10;
+#endif
+}
+#else
+#define CONST_VARIABLE_END CONST_END
+#endif
+
+#ifdef CONST_DATE_LENGTH
+/*
+ * The first CONST_DATE_LENGTH bytes of the variable part are a date, given
+ * by its index in the range of days. CONST_DATE_FIRST is the number of days
+ * from 0000-03-01 to the first day of the range, and CONST_DATE_YEAR,
+ * CONST_DATE_MONTH and CONST_DATE_DAY are the offsets of the fields. The
+ * year has CONST_DATE_YEAR_LENGTH digits (2 or 4)
+ */
+#define CONST_VARIABLE_BEGIN (CONST_BEGIN + CONST_DATE_LENGTH)
+inline void date(unsigned int day, Value * skeleton) {
+  // Civil date from the number of days, with years starting in March
+  unsigned int days = day + CONST_DATE_FIRST;
+  unsigned int era = days / 146097;
+  unsigned int day_of_era = days - era * 146097;
+  unsigned int year_of_era = (day_of_era - day_of_era / 1460
+      + day_of_era / 36524 - day_of_era / 146096) / 365;
+  unsigned int day_of_year = day_of_era
+    - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  unsigned int month = (5 * day_of_year + 2) / 153;
+
+  day = day_of_year - (153 * month + 2) / 5 + 1;
+  month = month < 10 ? month + 3 : month - 9;
+  unsigned int year = year_of_era + era * 400 + (month <= 2);
+
+#if CONST_DATE_YEAR_LENGTH == 4
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_YEAR)] = '0' + year / 1000;
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_YEAR + 1)] = '0' + year / 100 % 10;
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_YEAR + 2)] = '0' + year / 10 % 10;
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_YEAR + 3)] = '0' + year % 10;
+#else
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_YEAR)] = '0' + year / 10 % 10;
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_YEAR + 1)] = '0' + year % 10;
+#endif
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_MONTH)] = '0' + month / 10;
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_MONTH + 1)] = '0' + month % 10;
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_DAY)] = '0' + day / 10;
+  skeleton->bytes[CHAR_INDEX(CONST_BEGIN + CONST_DATE_DAY + 1)] = '0' + day % 10;
+}
+#else
+#define CONST_VARIABLE_BEGIN CONST_BEGIN
+#endif
+
+#if defined(CONST_MASK)
+/*
+ * Each position of the variable part has its own charset, given by the
+ * mask tables: `mask_charsets` holds all charsets concatenated, while
+ * `mask_offsets` and `mask_radixes` give the start and size of the charset
+ * of each position. Literals are charsets of size one
+ */
+#ifdef CONST_LENGTH_ON_CPU
+#define CONST_MASK_SPLIT (CONST_VARIABLE_BEGIN + CONST_LENGTH_ON_CPU)
+#else
+#define CONST_MASK_SPLIT CONST_VARIABLE_BEGIN
+#endif
+inline void prepare(unsigned int value,
+    unsigned int iteration,
+    Value * skeleton) {
+
+  unsigned int next;
+  unsigned char position;
+
+  // Filling the "variable" part of the skeleton
+#pragma unroll
+  for (char index = CONST_VARIABLE_END - 1; index >= CONST_MASK_SPLIT; index--) {
+    position = index - CONST_VARIABLE_BEGIN;
+
+    // Next position
+    next = value / mask_radixes[position];
+
+    // Convert the remainder to a character of the charset
+    skeleton->bytes[CHAR_INDEX(index)] =
+      mask_charsets[mask_offsets[position] + value - next * mask_radixes[position]];
+
+    // Move one position
+    value = next;
+  }
+
 #ifdef CONST_LENGTH_ON_CPU
+  // Filling the iteration part of the skeleton
+#pragma unroll
+  for (char index = CONST_MASK_SPLIT - 1; index >= CONST_VARIABLE_BEGIN; index--) {
+    position = index - CONST_VARIABLE_BEGIN;
+
+    // Next position
+    next = iteration / mask_radixes[position];
+
+    // Convert the remainder to a character of the charset
+    skeleton->bytes[CHAR_INDEX(index)] =
+      mask_charsets[mask_offsets[position] + iteration - next * mask_radixes[position]];
+
+    // Move one position
+    iteration = next;
+  }
+#endif
+
+#ifdef CONST_DATE_LENGTH
+  // The dates vary the slowest, after the mask on the GPU or on the CPU
+  date(value + iteration, skeleton);
+#endif
+
+#ifdef CONST_CHECKSUM
+  checksum(skeleton);
+#endif
+}
+#elif defined(CONST_LENGTH_ON_CPU)
 inline void prepare(unsigned int value,
     unsigned int iteration,
     Value * skeleton) {
@@ -22,15 +209,15 @@ inline void prepare(unsigned int value,
 
   // Filling the "variable" part of the skeleton
 #pragma unroll
-  for (char index = CONST_END - 1;
-      index >= CONST_BEGIN + CONST_LENGTH_ON_CPU;
+  for (char index = CONST_VARIABLE_END - 1;
+      index >= CONST_VARIABLE_BEGIN + CONST_LENGTH_ON_CPU;
       index--)
   {
     // Next decimal place
     next = value / 10;
 
     // Convert numbers to char
-    skeleton->bytes[index] = (value - next * 10) + 48;
+    skeleton->bytes[CHAR_INDEX(index)] = (value - next * 10) + 48;
 
     // Move one decimal place
     value = next;
@@ -38,36 +225,73 @@ inline void prepare(unsigned int value,
 
   // Filling the iteration part of the skeleton
 #pragma unroll
-  for (char index = CONST_BEGIN + CONST_LENGTH_ON_CPU - 1;
-      index >= CONST_BEGIN;
+  for (char index = CONST_VARIABLE_BEGIN + CONST_LENGTH_ON_CPU - 1;
+      index >= CONST_VARIABLE_BEGIN;
       index--)
   {
     // Next decimal place
     next = iteration / 10;
 
     // Convert numbers to char
-    skeleton->bytes[index] = (iteration - next * 10) + 48;
+    skeleton->bytes[CHAR_INDEX(index)] = (iteration - next * 10) + 48;
 
     // Move one decimal place
     iteration = next;
   }
+
+#ifdef CONST_DATE_LENGTH
+  // The dates vary the slowest, after the mask on the GPU or on the CPU
+  date(value + iteration, skeleton);
+#endif
+
+#ifdef CONST_CHECKSUM
+  checksum(skeleton);
+#endif
 }
 #else
-inline void prepare(unsigned int value, Value * skeleton) {
+inline void prepare(unsigned int value,
+    unsigned int iteration,
+    Value * skeleton) {
 
   unsigned int next;
 
   // Filling the "variable" part of the skeleton
 #pragma unroll
-  for (char index = CONST_END - 1; index >= CONST_BEGIN; index--) {
+  for (char index = CONST_VARIABLE_END - 1; index >= CONST_VARIABLE_BEGIN; index--) {
     // Next decimal place
     next = value / 10;
 
     // Convert numbers to char
-    skeleton->bytes[index] = (value - next * 10) + 48;
+    skeleton->bytes[CHAR_INDEX(index)] = (value - next * 10) + 48;
 
     // Move one decimal place
     value = next;
   }
+
+#ifdef CONST_DATE_LENGTH
+  // The dates vary the slowest, after the mask on the GPU or on the CPU
+  date(value + iteration, skeleton);
+#endif
+
+#ifdef CONST_CHECKSUM
+  checksum(skeleton);
+#endif
+}
+#endif
+
+#ifdef CONST_WORDLIST
+/*
+ * The variable part is copied from a batch of words, all of the same length
+ */
+inline void load(global const unsigned char * words,
+    unsigned int word_index,
+    Value * skeleton) {
+
+  global const unsigned char * word = words + word_index * (CONST_END - CONST_BEGIN);
+
+#pragma unroll
+  for (char index = CONST_BEGIN; index < CONST_END; index++) {
+    skeleton->bytes[CHAR_INDEX(index)] = word[index - CONST_BEGIN];
+  }
 }
 #endif
@@ -0,0 +1,241 @@
+union Hash {
+  unsigned char bytes[20];
+  unsigned int ints[5];
+};
+typedef union Hash Hash;
+
+#define F1(x, y, z) (bitselect(z,y,x))
+#define F0(x, y, z) (bitselect (x, y, ((x) ^ (z))))
+#define F2(x, y, z) ((x) ^ (y) ^ (z))
+#define mod(x, y) ((x)-((x)/(y)*(y)))
+#define rotl32(a, n) rotate ((a), (n))
+
+#define SWAP(val) ((val & 0xff) << 24) | ((val & 0xff00) << 8) | ((val & 0xff0000) >> 8) | ((val & 0xff000000) >> 24);
+
+#define SHA1_STEP(f, a, b, c, d, e, x, K) \
+{                                         \
+  e += K;                                 \
+  e += x;                                 \
+  e += f(b, c, d);                        \
+  e += rotl32(a, 5u);                     \
+  b = rotl32(b, 30u);                     \
+}
+
+#define SHA1_EXPAND(i) \
+  (W[(i) & 15] = rotl32(W[((i) - 3) & 15] ^ W[((i) - 8) & 15] ^ W[((i) - 14) & 15] ^ W[(i) & 15], 1u))
+
+static void sha1_process(const unsigned int * input, unsigned int * digest) {
+  unsigned int a = digest[0];
+  unsigned int b = digest[1];
+  unsigned int c = digest[2];
+  unsigned int d = digest[3];
+  unsigned int e = digest[4];
+
+  unsigned int W[16];
+  for (int i = 0; i < 16; i++) {
+    W[i] = input[i];
+  }
+
+#define ROUND_STEP(f, K, x0, x1, x2, x3, x4) \
+  {                                          \
+    SHA1_STEP(f, a, b, c, d, e, x0, K);      \
+    SHA1_STEP(f, e, a, b, c, d, x1, K);      \
+    SHA1_STEP(f, d, e, a, b, c, x2, K);      \
+    SHA1_STEP(f, c, d, e, a, b, x3, K);      \
+    SHA1_STEP(f, b, c, d, e, a, x4, K);      \
+  }
+
+  ROUND_STEP(F1, 0x5a827999u, W[0], W[1], W[2], W[3], W[4]);
+  ROUND_STEP(F1, 0x5a827999u, W[5], W[6], W[7], W[8], W[9]);
+  ROUND_STEP(F1, 0x5a827999u, W[10], W[11], W[12], W[13], W[14]);
+  ROUND_STEP(F1, 0x5a827999u, W[15], SHA1_EXPAND(16), SHA1_EXPAND(17), SHA1_EXPAND(18), SHA1_EXPAND(19));
+
+#define ROUND_EXPAND(f, i, K)                                                    \
+  ROUND_STEP(f, K, SHA1_EXPAND(i), SHA1_EXPAND((i) + 1), SHA1_EXPAND((i) + 2), \
+      SHA1_EXPAND((i) + 3), SHA1_EXPAND((i) + 4))
+
+  ROUND_EXPAND(F2, 20, 0x6ed9eba1u);
+  ROUND_EXPAND(F2, 25, 0x6ed9eba1u);
+  ROUND_EXPAND(F2, 30, 0x6ed9eba1u);
+  ROUND_EXPAND(F2, 35, 0x6ed9eba1u);
+
+  ROUND_EXPAND(F0, 40, 0x8f1bbcdcu);
+  ROUND_EXPAND(F0, 45, 0x8f1bbcdcu);
+  ROUND_EXPAND(F0, 50, 0x8f1bbcdcu);
+  ROUND_EXPAND(F0, 55, 0x8f1bbcdcu);
+
+  ROUND_EXPAND(F2, 60, 0xca62c1d6u);
+  ROUND_EXPAND(F2, 65, 0xca62c1d6u);
+  ROUND_EXPAND(F2, 70, 0xca62c1d6u);
+  ROUND_EXPAND(F2, 75, 0xca62c1d6u);
+
+#undef ROUND_STEP
+#undef ROUND_EXPAND
+
+  digest[0] += a;
+  digest[1] += b;
+  digest[2] += c;
+  digest[3] += d;
+  digest[4] += e;
+}
+
+/* The main hashing function */
+static void sha1(unsigned int * hash, const unsigned int * input) {
+  int input_len = CONST_LENGTH / 4;
+  if (mod(CONST_LENGTH, 4)) {
+    input_len++;
+  }
+
+  unsigned int W[0x10] = {0};
+  int loops = input_len;
+  int current_loop = 0;
+  unsigned int State[5] = {0};
+  State[0] = 0x67452301;
+  State[1] = 0xefcdab89;
+  State[2] = 0x98badcfe;
+  State[3] = 0x10325476;
+  State[4] = 0xc3d2e1f0;
+
+  while (loops > 0) {
+    for (int m = 0; m < 16; m++) {
+      W[m] = 0x0;
+    }
+
+    for (int m = 0; loops != 0 && m < 16; m++) {
+      W[m] ^= SWAP(input[m + (current_loop * 16)]);
+      loops--;
+    }
+
+    if (loops == 0 && mod(CONST_LENGTH, 64) != 0) {
+      unsigned int padding = 0x80 << (((CONST_LENGTH + 4) - ((CONST_LENGTH + 4) / 4 * 4)) * 8);
+      int v = mod(CONST_LENGTH, 64);
+      W[v / 4] |= SWAP(padding);
+      if ((CONST_LENGTH & 0x3b) != 0x3b) {
+        /* Let's add length */
+        W[0x0f] = CONST_LENGTH * 8;
+      }
+    }
+
+    sha1_process(W, State);
+    current_loop++;
+  }
+
+  if (mod(input_len, 16) == 0) {
+    for (int m = 0; m < 16; m++) {
+      W[m] = 0x0;
+    }
+
+    if ((CONST_LENGTH & 0x3b) != 0x3b) {
+      unsigned int padding = 0x80 << (((CONST_LENGTH + 4) - ((CONST_LENGTH + 4) / 4 * 4)) * 8);
+      W[0] |= SWAP(padding);
+    }
+
+    /* Let's add length */
+    W[0x0f] = CONST_LENGTH * 8;
+
+    sha1_process(W, State);
+  }
+
+  hash[0] = SWAP(State[0]);
+  hash[1] = SWAP(State[1]);
+  hash[2] = SWAP(State[2]);
+  hash[3] = SWAP(State[3]);
+  hash[4] = SWAP(State[4]);
+  return;
+}
+
+#undef F0
+#undef F1
+#undef F2
+
+#undef mod
+#undef rotl32
+
+//______________________________________________________________________________
+// Find the hash for a limited number of targets
+//
+// Defines:
+// CONST_BEGIN {:d} # The index of where the variable part begins
+// CONST_END {:d} # The index past of where the variable part ends
+// CONST_CHECKSUM {:d} # If defined, the algorithm of the check digits ending the variable part
+// CONST_CHECKSUM_BASE {:d} # The constant part of the checksum sum
+// CONST_CHECKSUM_LENGTH {:d} # The number of check digits
+// CONST_LENGTH {:d} # The length of the payload (salt + value + salt, depending on its position)
+// CONST_LENGTH_ON_CPU {:d} # Positions the iterations are substituting
+// CONST_MASK {:d} # If defined, the variable part is enumerated from the mask tables
+// CONST_TARGET_COUNT {:d} # The number of items in the targets array
+// CONST_UTF16LE {:d} # If defined, every character is encoded in two bytes, as UTF-16LE
+// CONST_VALUE_LENGTH {:d} # The size of the buffer of the value, a block of 64 bytes
+// CONST_WORDLIST {:d} # If defined, the variable part is read from a batch of words
+//
+// targets: Target hashes
+// output: Matched values
+// words: Batch of words, only with CONST_WORDLIST
+//______________________________________________________________________________
+__kernel void crack(constant Hash * targets,
+    global unsigned int * output,
+#ifdef CONST_WORDLIST
+    global const unsigned char * words,
+#endif
+    private const unsigned int prefix) {
+  unsigned int index = get_global_id(0);
+
+  // Buffer for the hash
+  Hash hash;
+
+  // Zero initialize
+  Value value = {};
+
+#ifdef CONST_WORDLIST
+  load(words, index, &value);
+#else
+  prepare(index, prefix, &value);
+#endif
+
+  // %%PREFIX%%
+
+  // %%TRANSFORM%%
+
+  // %%SUFFIX%%
+
+  // Inject size
+  value.longs[7] = CONST_LENGTH << 3;
+
+  // Inject padding
+  value.bytes[CONST_LENGTH] = 0x80;
+
+  // Actually cracking
+  sha1(hash.ints, value.ints);
+
+  // The hash is 20 bytes long, so it is compared as ints rather than longs
+#if CONST_TARGET_COUNT < 32
+#pragma unroll
+  for (unsigned int i = 0; i < CONST_TARGET_COUNT; i++) {
+    if (hash.ints[4] == targets[i].ints[4]
+        && hash.ints[3] == targets[i].ints[3]
+        && hash.ints[2] == targets[i].ints[2]
+        && hash.ints[1] == targets[i].ints[1]
+        && hash.ints[0] == targets[i].ints[0]) {
+      output[i << 1] = (unsigned int)(index & 0xFFFFFF);
+      output[(i << 1) + 1] = (unsigned int)(prefix & 0xFFFFFF);
+      return;
+    }
+  }
+#else
+  unsigned int i = 0;
+  while (i < CONST_TARGET_COUNT) {
+    int j = 4;
+    while (j > 0 && hash.ints[j] == targets[i].ints[j]) {
+      j--;
+    }
+    if (hash.ints[j] == targets[i].ints[j]) {
+      output[i << 1] = (unsigned int)(index & 0xFFFFFF);
+      output[(i << 1) + 1] = (unsigned int)(prefix & 0xFFFFFF);
+      return;
+    }
+    i = hash.ints[j] < targets[i].ints[j]
+      ? (i << 1) + 1
+      : (i << 1) + 2;
+  }
+#endif //#if CONST_TARGET_COUNT
+}
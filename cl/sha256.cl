@@ -244,6 +244,63 @@ static void sha256(unsigned int * hash, const unsigned int * input) {
   return;
 }
 
+#ifdef CONST_HMAC_INNER
+// Hashes a single block, holding its padding, from the state left by the previous blocks, given
+// the length of the whole message
+static void sha256_from(unsigned int * hash,
+    const unsigned int * state,
+    const unsigned int * input,
+    const unsigned int length) {
+  unsigned int W[0x10];
+  for (int i = 0; i < 0xf; i++) {
+    W[i] = SWAP(input[i]);
+  }
+  W[0xf] = length << 3;
+
+  unsigned int digest[8];
+  for (int i = 0; i < 8; i++) {
+    digest[i] = state[i];
+  }
+  sha256_process2(W, digest);
+
+  for (int i = 0; i < 8; i++) {
+    hash[i] = SWAP(digest[i]);
+  }
+}
+#endif
+
+#ifdef CONST_CHAIN_ROUNDS
+// Hashes a previous hash, whose 64 hex digits fill a whole block before the padding
+static void sha256_chained(unsigned int * hash,
+    const unsigned int * input,
+    const unsigned int length) {
+  unsigned int W[0x10];
+  for (int i = 0; i < 0x10; i++) {
+    W[i] = SWAP(input[i]);
+  }
+
+  unsigned int digest[8] = {
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19
+  };
+  if (length == 64) {
+    sha256_process2(W, digest);
+    for (int i = 0; i < 0x10; i++) {
+      W[i] = 0;
+    }
+    W[0] = 0x80000000;
+  } else {
+    W[length / 4] = 0x80000000;
+  }
+  W[0xf] = length << 3;
+  sha256_process2(W, digest);
+
+  for (int i = 0; i < 8; i++) {
+    hash[i] = SWAP(digest[i]);
+  }
+}
+#endif
+
 #undef F0
 #undef F1
 #undef S0
@@ -261,16 +318,30 @@ static void sha256(unsigned int * hash, const unsigned int * input) {
 // Defines:
 // CONST_BEGIN {:d} # The index of where the variable part begins
 // CONST_END {:d} # The index past of where the variable part ends
-// CONST_BASE64_BEGIN {:d} # The index where to base64 encode from (salt length)
-// CONST_LENGTH {:d} # The length of the payload (salt + value)
-// CONST_LENGTH_ON_CPU {:d} # Decimal places the iterations are substituting
+// CONST_CHECKSUM {:d} # If defined, the algorithm of the check digits ending the variable part
+// CONST_CHECKSUM_BASE {:d} # The constant part of the checksum sum
+// CONST_CHAIN_HEX {:d} # If defined, the rounds of the chain hash the hex digits of the previous hash
+// CONST_CHAIN_ROUNDS {:d} # If defined, the number of hashes of the chain, each hashing the previous one
+// CONST_CHECKSUM_LENGTH {:d} # The number of check digits
+// CONST_HMAC_INNER {...} # If defined, the state once the inner padded key of the HMAC is hashed
+// CONST_HMAC_OUTER {...} # The state once the outer padded key of the HMAC is hashed
+// CONST_LENGTH {:d} # The length of the payload (salt + value + salt, depending on its position)
+// CONST_LENGTH_ON_CPU {:d} # Positions the iterations are substituting
+// CONST_MASK {:d} # If defined, the variable part is enumerated from the mask tables
 // CONST_TARGET_COUNT {:d} # The number of items in the targets array
+// CONST_UTF16LE {:d} # If defined, every character is encoded in two bytes, as UTF-16LE
+// CONST_VALUE_LENGTH {:d} # The size of the buffer of the value, a block of 64 bytes
+// CONST_WORDLIST {:d} # If defined, the variable part is read from a batch of words
 //
 // targets: Target hashes
 // output: Matched values
+// words: Batch of words, only with CONST_WORDLIST
 //______________________________________________________________________________
 __kernel void crack(constant Hash * targets,
     global unsigned int * output,
+#ifdef CONST_WORDLIST
+    global const unsigned char * words,
+#endif
     private const unsigned int prefix) {
   unsigned int index = get_global_id(0);
 
@@ -280,19 +351,36 @@ __kernel void crack(constant Hash * targets,
   // Zero initialize
   Value value = {};
 
-#ifdef CONST_LENGTH_ON_CPU
-  prepare(index, prefix, &value);
+#ifdef CONST_WORDLIST
+  load(words, index, &value);
 #else
-  prepare(index, &value);
+  prepare(index, prefix, &value);
 #endif
 
   // %%PREFIX%%
 
-#ifdef CONST_BASE64_BEGIN
-  // %%XOR%%
-  to_base64(&value);
-#endif
+  // %%TRANSFORM%%
 
+  // %%SUFFIX%%
+
+#ifdef CONST_HMAC_INNER
+  // Inject padding
+  value.bytes[CONST_LENGTH] = 0x80;
+
+  // Inner hash, from the inner padded key, the value following it
+  const unsigned int inner[8] = CONST_HMAC_INNER;
+  sha256_from(hash.ints, inner, value.ints, CONST_LENGTH + 64);
+
+  // Outer hash of the inner hash, from the outer padded key
+  Value outer = {};
+  outer.longs[0] = hash.longs[0];
+  outer.longs[1] = hash.longs[1];
+  outer.longs[2] = hash.longs[2];
+  outer.longs[3] = hash.longs[3];
+  outer.bytes[32] = 0x80;
+  const unsigned int outer_state[8] = CONST_HMAC_OUTER;
+  sha256_from(hash.ints, outer_state, outer.ints, 64 + 32);
+#else
   // Inject size
   value.longs[7] = CONST_LENGTH << 3;
 
@@ -301,6 +389,16 @@ __kernel void crack(constant Hash * targets,
 
   // Actually cracking
   sha256(hash.ints, value.ints);
+#endif
+
+#ifdef CONST_CHAIN_ROUNDS
+  // Every extra round hashes the hash of the previous one
+  for (unsigned int round = 1; round < CONST_CHAIN_ROUNDS; round++) {
+    Value chained = {};
+    const unsigned int length = chain_value(hash.bytes, 32, &chained);
+    sha256_chained(hash.ints, chained.ints, length);
+  }
+#endif
 
 #if CONST_TARGET_COUNT < 32
 #pragma unroll
@@ -0,0 +1,215 @@
+/*
+ * SHA-512, and SHA-384 when CONST_SHA384 is defined
+ *
+ * The value is hashed as a single 128 byte block, so CONST_LENGTH must
+ * leave room for the padding and the 128 bit length
+ */
+
+#ifdef CONST_SHA384
+#define DIGEST_LONGS 6
+#else
+#define DIGEST_LONGS 8
+#endif
+
+union Hash {
+  unsigned char bytes[DIGEST_LONGS * 8];
+  unsigned int ints[DIGEST_LONGS * 2];
+  unsigned long longs[DIGEST_LONGS];
+};
+typedef union Hash Hash;
+
+#define F1(x, y, z) (bitselect(z,y,x))
+#define F0(x, y, z) (bitselect (x, y, ((x) ^ (z))))
+#define rotr64(a, n) rotate ((a), (unsigned long)(64 - (n)))
+
+#define S0(x) (rotr64 ((x),  1) ^ rotr64 ((x),  8) ^ ((x) >> 7))
+#define S1(x) (rotr64 ((x), 19) ^ rotr64 ((x), 61) ^ ((x) >> 6))
+#define S2(x) (rotr64 ((x), 28) ^ rotr64 ((x), 34) ^ rotr64 ((x), 39))
+#define S3(x) (rotr64 ((x), 14) ^ rotr64 ((x), 18) ^ rotr64 ((x), 41))
+
+#define SWAP32(x) ((((x) & 0xff) << 24) | (((x) & 0xff00) << 8) | (((x) & 0xff0000) >> 8) | (((x) >> 24) & 0xff))
+#define SWAP64(x) (((unsigned long)SWAP32((unsigned int)(x)) << 32) | SWAP32((unsigned int)((x) >> 32)))
+
+__constant unsigned long k_sha512[80] =
+{
+  0x428a2f98d728ae22ul, 0x7137449123ef65cdul, 0xb5c0fbcfec4d3b2ful, 0xe9b5dba58189dbbcul,
+  0x3956c25bf348b538ul, 0x59f111f1b605d019ul, 0x923f82a4af194f9bul, 0xab1c5ed5da6d8118ul,
+  0xd807aa98a3030242ul, 0x12835b0145706fbeul, 0x243185be4ee4b28cul, 0x550c7dc3d5ffb4e2ul,
+  0x72be5d74f27b896ful, 0x80deb1fe3b1696b1ul, 0x9bdc06a725c71235ul, 0xc19bf174cf692694ul,
+  0xe49b69c19ef14ad2ul, 0xefbe4786384f25e3ul, 0x0fc19dc68b8cd5b5ul, 0x240ca1cc77ac9c65ul,
+  0x2de92c6f592b0275ul, 0x4a7484aa6ea6e483ul, 0x5cb0a9dcbd41fbd4ul, 0x76f988da831153b5ul,
+  0x983e5152ee66dfabul, 0xa831c66d2db43210ul, 0xb00327c898fb213ful, 0xbf597fc7beef0ee4ul,
+  0xc6e00bf33da88fc2ul, 0xd5a79147930aa725ul, 0x06ca6351e003826ful, 0x142929670a0e6e70ul,
+  0x27b70a8546d22ffcul, 0x2e1b21385c26c926ul, 0x4d2c6dfc5ac42aedul, 0x53380d139d95b3dful,
+  0x650a73548baf63deul, 0x766a0abb3c77b2a8ul, 0x81c2c92e47edaee6ul, 0x92722c851482353bul,
+  0xa2bfe8a14cf10364ul, 0xa81a664bbc423001ul, 0xc24b8b70d0f89791ul, 0xc76c51a30654be30ul,
+  0xd192e819d6ef5218ul, 0xd69906245565a910ul, 0xf40e35855771202aul, 0x106aa07032bbd1b8ul,
+  0x19a4c116b8d2d0c8ul, 0x1e376c085141ab53ul, 0x2748774cdf8eeb99ul, 0x34b0bcb5e19b48a8ul,
+  0x391c0cb3c5c95a63ul, 0x4ed8aa4ae3418acbul, 0x5b9cca4f7763e373ul, 0x682e6ff3d6b2b8a3ul,
+  0x748f82ee5defb2fcul, 0x78a5636f43172f60ul, 0x84c87814a1f0ab72ul, 0x8cc702081a6439ecul,
+  0x90befffa23631e28ul, 0xa4506cebde82bde9ul, 0xbef9a3f7b2c67915ul, 0xc67178f2e372532bul,
+  0xca273eceea26619cul, 0xd186b8c721c0c207ul, 0xeada7dd6cde0eb1eul, 0xf57d4f7fee6ed178ul,
+  0x06f067aa72176fbaul, 0x0a637dc5a2c898a6ul, 0x113f9804bef90daeul, 0x1b710b35131c471bul,
+  0x28db77f523047d84ul, 0x32caab7b40c72493ul, 0x3c9ebe0a15c9bebcul, 0x431d67c49c100d4cul,
+  0x4cc5d4becb3e42b6ul, 0x597f299cfc657e2aul, 0x5fcb6fab3ad6faecul, 0x6c44198c4a475817ul,
+};
+
+#ifdef CONST_SHA384
+__constant unsigned long iv_sha512[8] =
+{
+  0xcbbb9d5dc1059ed8ul, 0x629a292a367cd507ul, 0x9159015a3070dd17ul, 0x152fecd8f70e5939ul,
+  0x67332667ffc00b31ul, 0x8eb44a8768581511ul, 0xdb0c2e0d64f98fa7ul, 0x47b5481dbefa4fa4ul,
+};
+#else
+__constant unsigned long iv_sha512[8] =
+{
+  0x6a09e667f3bcc908ul, 0xbb67ae8584caa73bul, 0x3c6ef372fe94f82bul, 0xa54ff53a5f1d36f1ul,
+  0x510e527fade682d1ul, 0x9b05688c2b3e6c1ful, 0x1f83d9abfb41bd6bul, 0x5be0cd19137e2179ul,
+};
+#endif
+
+/* The main hashing function, over the padded block */
+static void sha512(unsigned long * hash, const unsigned long * input) {
+  unsigned long W[16];
+
+#pragma unroll
+  for (int i = 0; i < 15; i++) {
+    W[i] = SWAP64(input[i]);
+  }
+
+  /* Let's add length */
+  W[15] = CONST_LENGTH * 8;
+
+  unsigned long a = iv_sha512[0];
+  unsigned long b = iv_sha512[1];
+  unsigned long c = iv_sha512[2];
+  unsigned long d = iv_sha512[3];
+  unsigned long e = iv_sha512[4];
+  unsigned long f = iv_sha512[5];
+  unsigned long g = iv_sha512[6];
+  unsigned long h = iv_sha512[7];
+
+#pragma unroll
+  for (int i = 0; i < 80; i++) {
+    if (i >= 16) {
+      W[i & 15] += S1(W[(i - 2) & 15]) + W[(i - 7) & 15] + S0(W[(i - 15) & 15]);
+    }
+
+    unsigned long t1 = h + S3(e) + F1(e, f, g) + k_sha512[i] + W[i & 15];
+    unsigned long t2 = S2(a) + F0(a, b, c);
+    h = g;
+    g = f;
+    f = e;
+    e = d + t1;
+    d = c;
+    c = b;
+    b = a;
+    a = t1 + t2;
+  }
+
+  unsigned long State[8] = {a, b, c, d, e, f, g, h};
+
+#pragma unroll
+  for (int i = 0; i < DIGEST_LONGS; i++) {
+    hash[i] = SWAP64(iv_sha512[i] + State[i]);
+  }
+}
+
+#undef F0
+#undef F1
+#undef S0
+#undef S1
+#undef S2
+#undef S3
+
+#undef rotr64
+#undef SWAP32
+#undef SWAP64
+
+//______________________________________________________________________________
+// Find the hash for a limited number of targets
+//
+// Defines:
+// CONST_BEGIN {:d} # The index of where the variable part begins
+// CONST_END {:d} # The index past of where the variable part ends
+// CONST_CHECKSUM {:d} # If defined, the algorithm of the check digits ending the variable part
+// CONST_CHECKSUM_BASE {:d} # The constant part of the checksum sum
+// CONST_CHECKSUM_LENGTH {:d} # The number of check digits
+// CONST_LENGTH {:d} # The length of the payload (salt + value + salt, depending on its position)
+// CONST_LENGTH_ON_CPU {:d} # Positions the iterations are substituting
+// CONST_MASK {:d} # If defined, the variable part is enumerated from the mask tables
+// CONST_SHA384 {:d} # If defined, the hash is SHA-384 instead of SHA-512
+// CONST_TARGET_COUNT {:d} # The number of items in the targets array
+// CONST_UTF16LE {:d} # If defined, every character is encoded in two bytes, as UTF-16LE
+// CONST_VALUE_LENGTH {:d} # The size of the buffer of the value, a block of 128 bytes
+// CONST_WORDLIST {:d} # If defined, the variable part is read from a batch of words
+//
+// targets: Target hashes
+// output: Matched values
+// words: Batch of words, only with CONST_WORDLIST
+//______________________________________________________________________________
+__kernel void crack(constant Hash * targets,
+    global unsigned int * output,
+#ifdef CONST_WORDLIST
+    global const unsigned char * words,
+#endif
+    private const unsigned int prefix) {
+  unsigned int index = get_global_id(0);
+
+  // Buffer for the hash
+  Hash hash;
+
+  // Zero initialize
+  Value value = {};
+
+#ifdef CONST_WORDLIST
+  load(words, index, &value);
+#else
+  prepare(index, prefix, &value);
+#endif
+
+  // %%PREFIX%%
+
+  // %%TRANSFORM%%
+
+  // %%SUFFIX%%
+
+  // Inject padding, the length is added while hashing
+  value.bytes[CONST_LENGTH] = 0x80;
+
+  // Actually cracking
+  sha512(hash.longs, value.longs);
+
+#if CONST_TARGET_COUNT < 32
+#pragma unroll
+  for (unsigned int i = 0; i < CONST_TARGET_COUNT; i++) {
+    int j = DIGEST_LONGS - 1;
+    while (j >= 0 && hash.longs[j] == targets[i].longs[j]) {
+      j--;
+    }
+    if (j < 0) {
+      output[i << 1] = (unsigned int)(index & 0xFFFFFF);
+      output[(i << 1) + 1] = (unsigned int)(prefix & 0xFFFFFF);
+      return;
+    }
+  }
+#else
+  unsigned int i = 0;
+  while (i < CONST_TARGET_COUNT) {
+    int j = DIGEST_LONGS - 1;
+    while (j > 0 && hash.longs[j] == targets[i].longs[j]) {
+      j--;
+    }
+    if (hash.longs[j] == targets[i].longs[j]) {
+      output[i << 1] = (unsigned int)(index & 0xFFFFFF);
+      output[(i << 1) + 1] = (unsigned int)(prefix & 0xFFFFFF);
+      return;
+    }
+    i = hash.longs[j] < targets[i].longs[j]
+      ? (i << 1) + 1
+      : (i << 1) + 2;
+  }
+#endif //#if CONST_TARGET_COUNT
+}
+
+#undef DIGEST_LONGS
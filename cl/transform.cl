@@ -0,0 +1,142 @@
+/*
+ * Transforms of the plain value, applied in place from `begin` before it
+ * is hashed. `length` is the length of the value before the transform,
+ * the caller knows the length after it
+ */
+__constant char base64_map[64] = {
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l',
+    'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4',
+    '5', '6', '7', '8', '9', '+', '/'
+};
+
+__constant char base64url_map[64] = {
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l',
+    'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4',
+    '5', '6', '7', '8', '9', '-', '_'
+};
+
+__constant char hex_map[16] = {
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f'
+};
+
+inline void to_base64(Value * value, unsigned char begin, unsigned char length,
+    __constant char * map) {
+  unsigned char bytes[CONST_VALUE_LENGTH];
+  unsigned char index = begin;
+
+#pragma unroll
+  for (unsigned char i = 0; i < length; i++) {
+    bytes[i] = value->bytes[begin + i];
+  }
+
+#pragma unroll
+  for (unsigned char i = 0; i < length;) {
+    unsigned char x, y, z;
+
+    x = bytes[i++];
+
+    // xxxxxxxxyyyyyyyyzzzzzzzz
+    // aaaaaabbbbbbccccccdddddd
+
+    // xxxxxxxx >> 2
+    // xxxxxx
+    // aaaaaa
+    value->bytes[index++] = map[x >> 2];
+
+    if (i < length) {
+      y = bytes[i++];
+
+      // xxxxxxxx & 0b11
+      //       xx << 4
+      //   xx____
+      // yyyyyyyy >> 4
+      // yyyy
+      //   xx____ | yyyy
+      // xxyyyy
+      // bbbbbb
+      value->bytes[index++] = map[((x & 0b11) << 4) | (y >> 4)];
+
+      if (i < length) {
+        z = bytes[i++];
+
+        // yyyyyyyy & 0b1111
+        //     yyyy << 2
+        //   yyyy__
+        // zzzzzzzz >> 6
+        // zz
+        //   yyyy__ | zz
+        // yyyyzz
+        // cccccc
+        value->bytes[index++] = map[((y & 0b1111) << 2) | (z >> 6)];
+
+        // zzzzzzzz & 0b111111
+        // zzzzzz
+        // dddddd
+        value->bytes[index++] = map[z & 0b111111];
+      } else {
+        value->bytes[index++] = map[(y & 0b1111) << 2];
+        value->bytes[index++] = '=';
+      }
+    } else {
+      value->bytes[index++] = map[(x & 0b11) << 4];
+      value->bytes[index++] = '=';
+      value->bytes[index++] = '=';
+    }
+  }
+}
+
+// Expanded from the end, so every byte is read before being overwritten
+inline void to_hex(Value * value, unsigned char begin, unsigned char length) {
+#pragma unroll
+  for (unsigned char i = length; i > 0; i--) {
+    unsigned char byte = value->bytes[begin + i - 1];
+    value->bytes[begin + 2 * i - 1] = hex_map[byte & 0xf];
+    value->bytes[begin + 2 * i - 2] = hex_map[byte >> 4];
+  }
+}
+
+inline void reverse(Value * value, unsigned char begin, unsigned char length) {
+#pragma unroll
+  for (unsigned char i = 0; i < length / 2; i++) {
+    unsigned char byte = value->bytes[begin + i];
+    value->bytes[begin + i] = value->bytes[begin + length - i - 1];
+    value->bytes[begin + length - i - 1] = byte;
+  }
+}
+
+inline void to_upper(Value * value, unsigned char begin, unsigned char length) {
+#pragma unroll
+  for (unsigned char i = begin; i < begin + length; i++) {
+    if (value->bytes[i] >= 'a' && value->bytes[i] <= 'z') {
+      value->bytes[i] -= 'a' - 'A';
+    }
+  }
+}
+
+inline void to_lower(Value * value, unsigned char begin, unsigned char length) {
+#pragma unroll
+  for (unsigned char i = begin; i < begin + length; i++) {
+    if (value->bytes[i] >= 'A' && value->bytes[i] <= 'Z') {
+      value->bytes[i] += 'a' - 'A';
+    }
+  }
+}
+
+// Shifts the value to the right and fills the start, up to `width`
+inline void pad(Value * value, unsigned char begin, unsigned char length, unsigned char width,
+    unsigned char fill) {
+  if (length >= width) {
+    return;
+  }
+
+#pragma unroll
+  for (unsigned char i = length; i > 0; i--) {
+    value->bytes[begin + width - length + i - 1] = value->bytes[begin + i - 1];
+  }
+#pragma unroll
+  for (unsigned char i = 0; i < width - length; i++) {
+    value->bytes[begin + i] = fill;
+  }
+}
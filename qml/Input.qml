@@ -104,8 +104,8 @@ Column {
 
           textDocument: hashesEdit.textDocument
           color: root.palette.text
-          useSha256: parameters.useSha256
-          onUseSha256Changed: {
+          algorithm: parameters.algorithm
+          onAlgorithmChanged: {
             this.rehighlight();
             hashesList.model = this.hashes(hashesEdit.text);
           }
@@ -9,12 +9,13 @@ Column {
   property alias length: length.value
   property alias saltCustom: saltCustom.checked
   property alias saltValue: saltValue.text
-  property alias useSha256: algorithmSha256.checked
+  property alias algorithm: algorithms.algorithm
   property alias deviceAutomatic: deviceAutomatic.checked
   property alias useGpu: gpu.checked
   property alias useMask: maskEnabled.checked
   property alias customMask: maskCustom.checked
   property alias maskValue: maskValue.text
+  property alias decodeMask: maskDecode.checked
   property Item _current: null
 
   state: _current ? 'Expanded' : ''
@@ -131,15 +132,81 @@ Column {
     expanded: root._current === this
     onClicked: root._current = this
 
+    ButtonGroup {
+      id: algorithms
+
+      property string algorithm: checkedButton ? checkedButton.algorithm : 'sha256'
+    }
+
     Radio {
-      id: algorithmSha256
+      property string algorithm: 'sha256'
 
       text: qsTr('Sha256')
       checked: true
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'sha512'
+
+      text: qsTr('Sha512')
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'sha384'
+
+      text: qsTr('Sha384')
+      ButtonGroup.group: algorithms
     }
 
     Radio {
+      property string algorithm: 'sha1'
+
+      text: qsTr('Sha1')
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'sha3_256'
+
+      text: qsTr('Sha3-256')
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'keccak256'
+
+      text: qsTr('Keccak-256')
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'blake2b'
+
+      text: qsTr('Blake2b')
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'blake3'
+
+      text: qsTr('Blake3')
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'md5'
+
       text: qsTr('Md5')
+      ButtonGroup.group: algorithms
+    }
+
+    Radio {
+      property string algorithm: 'md4'
+
+      text: qsTr('Md4')
+      ButtonGroup.group: algorithms
     }
 
   }
@@ -199,6 +266,15 @@ Column {
         onCheckedChanged: maskCustom.checked && maskValue.forceActiveFocus()
       }
 
+      Switch {
+        id: maskDecode
+
+        text: qsTr('Decode')
+        enabled: maskEnabled.checked
+        checked: true
+        opacity: enabled ? 1 : 0.5
+      }
+
     }
 
     TextField {
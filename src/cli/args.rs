@@ -1,6 +1,8 @@
+use crate::detect;
 use crate::error;
 use crate::files;
 use crate::hash;
+use crate::kdf;
 use crate::options;
 use crate::secrets;
 
@@ -8,52 +10,282 @@ use super::print;
 
 const SALT_ENV: &str = "HASHER_SALT";
 const XOR_ENV: &str = "HASHER_XOR";
+const HMAC_KEY_ENV: &str = "HASHER_HMAC_KEY";
+const DEFAULT_LENGTH: u8 = 12;
 
 type Result<T> = std::result::Result<T, error::Error>;
 
-/// SHA256 hasher/cracker
+// The modes of every algorithm are the same, only their description differs
+macro_rules! algorithm_modes {
+    ($($name:ident: $about:literal),+) => {
+        $(pub mod $name {
+            use super::{hash, options, print, Result};
+            use super::{RawCrackShared, RawHash, RawSaltFind, RawXorFind};
+
+            #[doc = $about]
+            #[derive(clap::Parser, Debug)]
+            #[clap(
+                name = "Hasher",
+                version,
+                after_help = "Input can be provided through stdin or as parameters"
+            )]
+            // Allowed because the mode is parsed only once
+            #[allow(clippy::large_enum_variant)]
+            pub enum RawMode {
+                /// Generate hashes
+                Hash(RawHash),
+
+                /// Crack hashes
+                #[clap(
+                    after_help = "The cracker will exit with an error if any of the input hashes could not be cracked\n\n\
+                                  Pbkdf2, bcrypt, scrypt, argon2 and the crypt formats md5crypt, sha256crypt and sha512crypt \
+                                  carry their own salt and cost, and can only be cracked on the CPU. Without an algorithm or a \
+                                  chain, the algorithm of every hash is detected from its length or its prefix, hex hashes being \
+                                  taken as md5, sha1, sha256, sha384 or sha512"
+                )]
+                Crack(RawCrack),
+
+                /// Find the salt of hashes with known values
+                #[clap(
+                    after_help = "The finder will exit with an error if the salt of any of the input hashes could not be found"
+                )]
+                SaltFind(RawSaltFind),
+
+                /// Find the XOR mask of values with known hashes or masked forms
+                #[clap(
+                    after_help = "The finder will exit with an error if any of the input values could not be reproduced"
+                )]
+                XorFind(RawXorFind),
+            }
+
+            #[derive(clap::Parser, Debug)]
+            pub struct RawCrack {
+                #[clap(flatten)]
+                pub(super) shared: RawCrackShared,
+
+                /// Hashed values to crack. Expected to be the hash of a numeric value
+                ///
+                /// If a single hash is given, only the cracked value will be printed to stdout.
+                /// If more than one hash is given, the pairs <hash>:<cracked value> will be printed to stdout,
+                /// one per line. With a salt format, each hash may be given along with its salt
+                pub(super) input: Vec<String>,
+            }
+
+            pub fn parse() -> Result<(options::Mode<hash::$name::Hash>, print::Printer)> {
+                use clap::Parser;
+                use hash::$name::Hash as H;
+
+                let (mode, mut printer) = match RawMode::parse() {
+                    RawMode::Hash(encrypt) => super::compose_hash::<H>(encrypt),
+                    RawMode::Crack(decrypt) => super::compose_crack::<H>(&decrypt.shared, &decrypt.input),
+                    RawMode::SaltFind(find) => super::compose_salt_find::<H>(find),
+                    RawMode::XorFind(find) => super::compose_xor_find::<H>(find),
+                }?;
+
+                if mode.input_len() == 1 {
+                    printer.set_single_input_mode();
+                }
+
+                Ok((mode, printer))
+            }
+        })+
+    };
+}
+
+algorithm_modes!(
+    sha256: "SHA256 hasher/cracker",
+    md4: "Md4 hasher/cracker",
+    md5: "Md5 hasher/cracker",
+    sha1: "Sha1 hasher/cracker",
+    sha384: "Sha384 hasher/cracker",
+    sha512: "Sha512 hasher/cracker",
+    sha3_256: "Sha3-256 hasher/cracker",
+    keccak256: "Keccak-256 hasher/cracker",
+    blake2b: "Blake2b hasher/cracker",
+    blake3: "Blake3 hasher/cracker"
+);
+
+/// Slow password hash cracker
 #[derive(clap::Parser, Debug)]
 #[clap(
     name = "Hasher",
     version,
     after_help = "Input can be provided through stdin or as parameters"
 )]
-pub enum RawModeSha256 {
-    /// Generate hashes
-    Hash(RawHash),
-
+pub enum RawKdfMode {
     /// Crack hashes
     #[clap(
-        after_help = "The cracker will exit with an error if any of the input hashes could not be cracked"
+        after_help = "The cracker will exit with an error if any of the input hashes could not be cracked\n\n\
+                      Pbkdf2, bcrypt, scrypt, argon2 and the crypt formats md5crypt, sha256crypt and sha512crypt \
+                      carry their own salt and cost, and can only be cracked on the CPU"
     )]
-    Crack(RawCrackSha256),
+    Crack(RawKdfCrack),
 }
 
-/// Md5 hasher/cracker
 #[derive(clap::Parser, Debug)]
-#[clap(
-    name = "Hasher",
-    version,
-    after_help = "Input can be provided through stdin or as parameters"
-)]
-pub enum RawModeMd5 {
-    /// Generate hashes
-    Hash(RawHash),
+pub struct RawKdfCrack {
+    #[clap(flatten)]
+    shared: RawCrackShared,
 
-    /// Crack hashes
-    #[clap(
-        after_help = "The cracker will exit with an error if any of the input hashes could not be cracked"
-    )]
-    Crack(RawCrackMd5),
+    /// Hashes to crack, as modular crypt or PHC strings carrying their own salt and cost
+    ///
+    /// If a single hash is given, only the cracked value will be printed to stdout.
+    /// If more than one hash is given, the pairs <hash>:<cracked value> will be printed to stdout,
+    /// one per line
+    input: Vec<String>,
+}
+
+pub fn parse_kdf(kdf: kdf::Kdf) -> Result<(options::KdfDecrypt, print::Printer)> {
+    use clap::Parser;
+
+    let RawKdfMode::Crack(crack) = RawKdfMode::parse();
+    let (options, mut printer) = compose_kdf_crack(kdf, &crack.shared, crack.input)?;
+
+    if options.targets().len() == 1 {
+        printer.set_single_input_mode();
+    }
+
+    Ok((options, printer))
+}
+
+/// Hashes to crack without an algorithm, grouped by the format detected for each of them
+pub struct Detection {
+    shared: RawCrackShared,
+    files: std::collections::HashSet<std::path::PathBuf>,
+    input: std::collections::BTreeMap<detect::Format, std::collections::HashSet<String>>,
+    printer: print::Printer,
+}
+
+impl Detection {
+    /// Formats detected, each cracked on its own
+    pub fn formats(&self) -> Vec<detect::Format> {
+        self.input.keys().copied().collect()
+    }
+
+    /// Files read, written once every format is cracked
+    pub fn files(&self) -> &std::collections::HashSet<std::path::PathBuf> {
+        &self.files
+    }
+
+    pub fn emit(&self) -> options::Emit {
+        self.shared.emit
+    }
+
+    pub fn printer(&self) -> print::Printer {
+        self.printer
+    }
+
+    /// Matches the hashes of every format detected, to write the files
+    pub fn regex(&self) -> Result<regex::Regex> {
+        detect::regex(&self.formats())
+    }
+
+    pub fn compose<H: hash::Hash>(&self) -> Result<(options::Mode<H>, print::Printer)> {
+        let values = self
+            .input
+            .get(&detect::Format::Hash(H::algorithm()))
+            .into_iter()
+            .flatten();
+        let (input, salts) = parse_hashes::<H>(values, self.shared.salted)?;
+
+        Ok((
+            options::Mode::Decrypt(decrypt(&self.shared, self.files.clone(), input, salts)?),
+            self.printer,
+        ))
+    }
+
+    pub fn compose_kdf(&self, kdf: kdf::Kdf) -> Result<(options::KdfDecrypt, print::Printer)> {
+        check_kdf(kdf, &self.shared)?;
+        let input = self
+            .input
+            .get(&detect::Format::Kdf(kdf))
+            .into_iter()
+            .flatten()
+            .map(|value| kdf::Target::parse(kdf, value))
+            .collect::<Result<_>>()?;
+
+        Ok((
+            kdf_decrypt(kdf, &self.shared, self.files.clone(), input)?,
+            self.printer,
+        ))
+    }
+}
+
+/// Whether the hashes to crack are to be detected, as neither an algorithm nor a chain is given
+pub fn detected() -> bool {
+    Given::parse().map_or(false, |given| given.detected())
+}
+
+// The subcommands are the same for every algorithm, so they are parsed as of any of them
+pub fn parse_detected() -> Result<Detection> {
+    use clap::Parser;
+
+    let crack = match sha256::RawMode::parse() {
+        sha256::RawMode::Crack(crack) => crack,
+        _ => bail!("Only hashes to crack can be detected"),
+    };
+    let (shared, values) = (crack.shared, crack.input);
+    let mut printer = print::new(shared.shared.verbose, shared.shared.colored);
+
+    let files = crack_files(&shared);
+    let mut input = std::collections::BTreeMap::<_, std::collections::HashSet<_>>::new();
+    for value in values {
+        let format = match shared.salted {
+            Some(format) if value.contains(':') => files::detect_salted(&value, format)?,
+            _ => detect::Format::detect(&value)
+                .ok_or_else(|| error!("Could not detect the algorithm of '{}'", value))?,
+        };
+        input.entry(format).or_default().insert(value);
+    }
+
+    for file in &files {
+        printer.read_start(file.display().to_string());
+        printer.read_done(files::read_detected(&mut input, file, shared.salted));
+    }
+
+    // A wordlist read from stdin takes it over
+    if !atty::is(atty::Stream::Stdin) && shared.wordlist != Some(options::Wordlist::Stdin) {
+        printer.read_start("stdin");
+        printer.read_done(files::read_detected_from_stream(
+            &mut input,
+            std::io::stdin().lock(),
+            shared.salted,
+        ));
+    }
+
+    if input.is_empty() {
+        bail!("No valid input provided");
+    }
+    for format in input.keys() {
+        let alternatives = format.alternatives();
+        if !alternatives.is_empty() {
+            let alternatives = alternatives.iter().map(ToString::to_string);
+            printer.note(format!(
+                "Hashes taken as {} may also be {}, which are cracked when given with --algorithm",
+                format,
+                alternatives.collect::<Vec<_>>().join(", ")
+            ));
+        }
+    }
+    if input
+        .values()
+        .map(std::collections::HashSet::len)
+        .sum::<usize>()
+        == 1
+    {
+        printer.set_single_input_mode();
+    }
+
+    Ok(Detection {
+        shared,
+        files,
+        input,
+        printer,
+    })
 }
 
 #[derive(clap::Parser, Debug)]
 pub struct RawShared {
-    /// Salt to prepend when generating hash [env: HASHER_SALT]
-    #[clap(short, long)]
-    #[allow(clippy::option_option)]
-    salt: Option<Option<String>>,
-
     /// Verbose mode (-v, -vv)
     ///
     /// All verboseness will be printed to stderr
@@ -67,8 +299,56 @@ pub struct RawShared {
     // Allowed because this is done for the help rendering, but fetched manually from the params
     #[allow(dead_code)]
     /// Algorithm to use
-    #[clap(short, long, default_value = "sha256", possible_values = hash::Algorithm::variants(), parse(try_from_str = to_algorithm))]
-    algorithm: hash::Algorithm,
+    #[clap(short, long, default_value = "sha256", possible_values = algorithms())]
+    algorithm: String,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct RawSalt {
+    /// Salt to add when generating hash [env: HASHER_SALT]
+    #[clap(short, long)]
+    #[allow(clippy::option_option)]
+    salt: Option<Option<String>>,
+
+    /// Where the salt is added: before the value, after it, or on both sides
+    #[clap(long, default_value = "prepend", possible_values = options::SaltPosition::variants(), parse(try_from_str = to_salt_position))]
+    salt_position: options::SaltPosition,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct RawDigest {
+    /// Transforms to apply in order to plain values prior to hashing
+    ///
+    /// Any of xor, base64, base64url, hex, reverse, upper, lower or pad:<width>[:<fill>], which
+    /// pads on the left with '0' by default. An xor must be followed by an encoding and uses the
+    /// XOR mask. Replaces the default transforms of the XOR mask, which are xor,base64
+    #[clap(long, multiple_occurrences(true), use_value_delimiter(true))]
+    transform: Vec<String>,
+
+    /// Encoding of values once transformed, as hashed
+    ///
+    /// With utf16le, every character takes two bytes, so NTLM hashes are given by md4 and utf16le
+    #[clap(short, long, default_value = "utf8", possible_values = options::Encoding::variants(), parse(try_from_str = to_encoding))]
+    encoding: options::Encoding,
+
+    /// Key of the HMAC of values, which replaces their plain hash [env: HASHER_HMAC_KEY]
+    ///
+    /// The salt, if any, is still added to the values
+    #[clap(long)]
+    #[allow(clippy::option_option)]
+    hmac_key: Option<Option<String>>,
+
+    /// Chain of hashes of values, e.g. sha256(md5($p.$s)), which replaces the algorithm
+    ///
+    /// $p is the value and $s the salt, concatenated with '.'. The salt is only hashed where $s
+    /// is, regardless of its position. A hash may be repeated on its own hash, as in
+    /// sha256^1000($p)
+    #[clap(long, conflicts_with_all = &["algorithm", "hmac-key"])]
+    chain: Option<String>,
+
+    /// How the hashes inside a chain are hashed by the hash enclosing them
+    #[clap(long, default_value = "hex", possible_values = options::Intermediate::variants(), parse(try_from_str = to_intermediate))]
+    intermediate: options::Intermediate,
 }
 
 #[derive(clap::Parser, Debug)]
@@ -76,6 +356,29 @@ pub struct RawHash {
     #[clap(flatten)]
     shared: RawShared,
 
+    #[clap(flatten)]
+    salt: RawSalt,
+
+    #[clap(flatten)]
+    digest: RawDigest,
+
+    /// XOR mask to apply to plain values prior to hashing [env: HASHER_XOR]
+    ///
+    /// The mask is expected to be given as a base64 encoded representation
+    #[clap(short, long)]
+    #[allow(clippy::option_option)]
+    xor: Option<Option<String>>,
+
+    /// Prefix to add before every value
+    #[clap(short, long)]
+    prefix: Option<String>,
+
+    /// Length of values, prefix included, which are padded with leading zeros up to it
+    ///
+    /// Values are hashed the way the cracker enumerates them for the same prefix and length
+    #[clap(short, long)]
+    length: Option<u8>,
+
     /// Values to hash
     ///
     /// If a single input is given, only the hash will be printed to stdout. If more than one input
@@ -88,6 +391,12 @@ pub struct RawCrackShared {
     #[clap(flatten)]
     shared: RawShared,
 
+    #[clap(flatten)]
+    salt: RawSalt,
+
+    #[clap(flatten)]
+    digest: RawDigest,
+
     /// Input files. Will be scanned for hashes to crack
     ///
     /// If any hash from a given file is cracked, a copy of the file will be created in the same
@@ -95,6 +404,13 @@ pub struct RawCrackShared {
     #[clap(short, long, parse(try_from_str = to_path))]
     files: Vec<std::path::PathBuf>,
 
+    /// Format of input lines giving every hash its own salt, separated by a ':'
+    ///
+    /// Applies to files, stdin and parameters. Hashes sharing a salt are searched together, one
+    /// salt after the other, while hashes of parameters without a salt use the salt option
+    #[clap(long, possible_values = options::SaltFormat::variants(), parse(try_from_str = to_salt_format))]
+    salted: Option<options::SaltFormat>,
+
     /// XOR mask to apply to plain values prior to hashing [env: HASHER_XOR]
     ///
     /// The mask is expected to be given as a base64 encoded representation
@@ -102,57 +418,190 @@ pub struct RawCrackShared {
     #[allow(clippy::option_option)]
     xor: Option<Option<String>>,
 
+    /// Value reported for cracked hashes: the original value, or the value once transformed
+    ///
+    /// They only differ with an XOR mask or transforms, in which case the original value is the
+    /// prefix followed by the enumerated part, as given to the hash subcommand
+    #[clap(long, default_value = "original", possible_values = options::Emit::variants(), parse(try_from_str = to_emit))]
+    emit: options::Emit,
+
     /// Known prefix of original values
-    #[clap(short, long)]
-    prefix: Option<String>,
+    ///
+    /// May be given multiple times. Every prefix is combined with every length and all
+    /// combinations are searched in the same run
+    #[clap(short, long, multiple_occurrences(true), use_value_delimiter(true))]
+    prefix: Vec<String>,
 
     /// Number of threads to spawn, automatic deduction if omitted
     #[clap(short, long)]
     threads: Option<u8>,
 
     /// Device to run in (auto-detection if omitted)
+    ///
+    /// Sha3-256, Keccak-256, Blake2b and Blake3, as well as HMACs other than of md5 and sha256
+    /// and chains other than of md5 or sha256 over the salted value, are only cracked on the CPU
     #[clap(short, long, possible_values = options::Device::variants(), parse(try_from_str = to_device))]
     device: Option<options::Device>,
 
-    /// Length of original values
-    #[clap(short, long, default_value = "12")]
-    length: u8,
+    /// Length of original values [default: 12, unless a mask or template is given]
+    ///
+    /// May be given multiple times. Every length is combined with every prefix and all
+    /// combinations are searched in the same run
+    #[clap(short, long, multiple_occurrences(true), use_value_delimiter(true))]
+    length: Vec<u8>,
+
+    /// Mask of original values, following the prefix
+    ///
+    /// Placeholders: ?d (0-9), ?l (a-z), ?u (A-Z), ?h (0-9a-f), ?1 to ?4 (custom charsets) and
+    /// ?? (literal '?'). Any other character is a literal. May be given multiple times, in which
+    /// case every mask is combined with every prefix
+    #[clap(short, long, multiple_occurrences(true))]
+    mask: Vec<String>,
+
+    /// Fixed-format template of original values, following the prefix
+    ///
+    /// Each '#' is a digit and any other character is a literal, e.g. ###-##-####. May be given
+    /// multiple times, in which case every template is combined with every prefix
+    #[clap(short('T'), long, multiple_occurrences(true))]
+    template: Vec<String>,
+
+    /// Date format of original values, following the prefix and followed by lengths or masks
+    ///
+    /// Made of YYYY or YY, MM and DD in any order, e.g. YYYYMMDD. Only valid dates are
+    /// enumerated. Lengths include the date and, without any length or mask, only dates are
+    /// searched
+    #[clap(short('D'), long)]
+    date: Option<String>,
+
+    /// Range of years of dates, either a single year or two years separated by '-'
+    #[clap(short, long, default_value = "1930-2029", parse(try_from_str = to_years))]
+    years: (u16, u16),
+
+    /// Wordlist of original values, following the prefix, or '-' to read it from stdin
+    ///
+    /// One word per line. Words are hashed after every prefix and followed by every length, mask
+    /// or date if any, in which case lengths do not include the words
+    #[clap(short, long, parse(try_from_str = to_wordlist))]
+    wordlist: Option<options::Wordlist>,
+
+    /// Rule file mangling every word of the wordlist, one hashcat-style rule per line
+    ///
+    /// Each word is replaced by the output of every rule, so ':' keeps it unchanged. Lines starting
+    /// with '#' are ignored
+    #[clap(short, long, parse(try_from_str = to_path))]
+    rules: Option<std::path::PathBuf>,
+
+    /// Check digits ending original values, derived from the other digits
+    ///
+    /// Only the other digits are enumerated. Lengths include the check digits, which are appended
+    /// after masks and templates
+    #[clap(short, long, possible_values = options::Checksum::variants(), parse(try_from_str = to_checksum))]
+    checksum: Option<options::Checksum>,
+
+    /// Custom charset for the ?1 mask placeholder
+    #[clap(short('1'), long)]
+    charset1: Option<String>,
+
+    /// Custom charset for the ?2 mask placeholder
+    #[clap(short('2'), long)]
+    charset2: Option<String>,
+
+    /// Custom charset for the ?3 mask placeholder
+    #[clap(short('3'), long)]
+    charset3: Option<String>,
+
+    /// Custom charset for the ?4 mask placeholder
+    #[clap(short('4'), long)]
+    charset4: Option<String>,
 }
 
 #[derive(clap::Parser, Debug)]
-pub struct RawCrackSha256 {
+pub struct RawSaltFind {
     #[clap(flatten)]
-    shared: RawCrackShared,
+    shared: RawShared,
 
-    /// Hashed values to crack. Expected to be the hash of a numeric value
+    /// Input files. Will be read for known pairs, one <value>:<hash> per line
+    #[clap(short, long, parse(try_from_str = to_path))]
+    files: Vec<std::path::PathBuf>,
+
+    /// Candidate salt
     ///
-    /// If a single hash is given, only the cracked value will be printed to stdout.
-    /// If more than one hash is given, the pairs <hash>:<cracked value> will be printed to stdout,
-    /// one per line
-    #[clap(parse(try_from_str = <hash::sha256::Hash as hash::Hash>::from_str))]
-    input: Vec<hash::sha256::Hash>,
+    /// May be given multiple times
+    #[clap(short, long, multiple_occurrences(true))]
+    salt: Vec<String>,
+
+    /// Wordlist of candidate salts, or '-' to read it from stdin
+    #[clap(short, long, parse(try_from_str = to_wordlist))]
+    wordlist: Option<options::Wordlist>,
+
+    /// Mask of candidate salts
+    ///
+    /// Placeholders: ?d (0-9), ?l (a-z), ?u (A-Z), ?h (0-9a-f) and ?? (literal '?'). Any other
+    /// character is a literal
+    #[clap(short, long)]
+    mask: Option<String>,
+
+    /// Where the salt is added: before the value, after it, or on both sides [default: all]
+    #[clap(long, possible_values = options::SaltPosition::variants(), parse(try_from_str = to_salt_position))]
+    salt_position: Option<options::SaltPosition>,
+
+    /// Number of threads to spawn, automatic deduction if omitted
+    #[clap(short, long)]
+    threads: Option<u8>,
+
+    /// Known pairs of values and hashes, as <value>:<hash>
+    ///
+    /// If a single pair is given, only the salt position and salt will be printed to stdout. If
+    /// more than one pair is given, the triples <hash>:<salt position>:<salt> will be printed to
+    /// stdout, one per line
+    input: Vec<String>,
 }
 
 #[derive(clap::Parser, Debug)]
-pub struct RawCrackMd5 {
+pub struct RawXorFind {
     #[clap(flatten)]
-    shared: RawCrackShared,
+    shared: RawShared,
+
+    #[clap(flatten)]
+    salt: RawSalt,
 
-    /// Hashed values to crack. Expected to be the hash of a numeric value
+    /// Input files. Will be read for known values, one <value>:<hash> per line
+    #[clap(short, long, parse(try_from_str = to_path))]
+    files: Vec<std::path::PathBuf>,
+
+    /// Known values are followed by their masked form, base64 encoded, instead of its hash
     ///
-    /// If a single hash is given, only the cracked value will be printed to stdout.
-    /// If more than one hash is given, the pairs <hash>:<cracked value> will be printed to stdout,
-    /// one per line
-    #[clap(parse(try_from_str = <hash::md5::Hash as hash::Hash>::from_str))]
-    input: Vec<hash::md5::Hash>,
+    /// The mask is then derived from them, without hashing
+    #[clap(short, long)]
+    encoded: bool,
+
+    /// Number of threads to spawn, automatic deduction if omitted
+    #[clap(short, long)]
+    threads: Option<u8>,
+
+    /// Known values along with their hashes, as <value>:<hash>
+    ///
+    /// Every byte of the mask is searched with the shortest value covering it, so the lengths of
+    /// the values may be at most 3 apart. The recovered mask will be printed to stdout, base64
+    /// encoded
+    input: Vec<String>,
 }
 
 fn to_algorithm(value: &str) -> Result<hash::Algorithm> {
-    match value.to_uppercase().as_str() {
-        "SHA256" => Ok(hash::Algorithm::sha256),
-        "MD5" => Ok(hash::Algorithm::md5),
-        _ => bail!("possible values are [sha256, md5]",),
+    value.parse()
+}
+
+// Names of the hash algorithms, followed by those of the slow functions
+fn algorithms() -> &'static [&'static str] {
+    use lazy_static::lazy_static;
+    lazy_static! {
+        static ref ALGORITHMS: Vec<&'static str> = hash::Algorithm::variants()
+            .iter()
+            .chain(kdf::Kdf::variants())
+            .copied()
+            .collect();
     }
+    &ALGORITHMS
 }
 
 fn to_path(value: &str) -> Result<std::path::PathBuf> {
@@ -175,6 +624,77 @@ fn to_device(value: &str) -> Result<options::Device> {
     }
 }
 
+fn to_wordlist(value: &str) -> Result<options::Wordlist> {
+    if value == "-" {
+        Ok(options::Wordlist::Stdin)
+    } else {
+        to_path(value).map(options::Wordlist::File)
+    }
+}
+
+fn to_years(value: &str) -> Result<(u16, u16)> {
+    let year = |year: &str| {
+        year.trim()
+            .parse::<u16>()
+            .map_err(|err| error!(err; "invalid year '{}'", year))
+    };
+    match value.split_once('-') {
+        Some((first, last)) => Ok((year(first)?, year(last)?)),
+        None => year(value).map(|year| (year, year)),
+    }
+}
+
+fn to_checksum(value: &str) -> Result<options::Checksum> {
+    match value.to_lowercase().as_str() {
+        "luhn" => Ok(options::Checksum::Luhn),
+        "mod11" => Ok(options::Checksum::Mod11),
+        "mod11-cyclic" => Ok(options::Checksum::Mod11Cyclic),
+        "mod97" => Ok(options::Checksum::Mod97),
+        _ => bail!("possible values are [luhn, mod11, mod11-cyclic, mod97]",),
+    }
+}
+
+fn to_emit(value: &str) -> Result<options::Emit> {
+    match value.to_lowercase().as_str() {
+        "original" => Ok(options::Emit::Original),
+        "hashed" => Ok(options::Emit::Hashed),
+        _ => bail!("possible values are [original, hashed]",),
+    }
+}
+
+fn to_encoding(value: &str) -> Result<options::Encoding> {
+    match value.to_lowercase().as_str() {
+        "utf8" => Ok(options::Encoding::Utf8),
+        "utf16le" => Ok(options::Encoding::Utf16Le),
+        _ => bail!("possible values are [utf8, utf16le]",),
+    }
+}
+
+fn to_intermediate(value: &str) -> Result<options::Intermediate> {
+    match value.to_lowercase().as_str() {
+        "hex" => Ok(options::Intermediate::Hex),
+        "raw" => Ok(options::Intermediate::Raw),
+        _ => bail!("possible values are [hex, raw]",),
+    }
+}
+
+fn to_salt_position(value: &str) -> Result<options::SaltPosition> {
+    match value.to_lowercase().as_str() {
+        "prepend" => Ok(options::SaltPosition::Prepend),
+        "append" => Ok(options::SaltPosition::Append),
+        "wrap" => Ok(options::SaltPosition::Wrap),
+        _ => bail!("possible values are [prepend, append, wrap]",),
+    }
+}
+
+fn to_salt_format(value: &str) -> Result<options::SaltFormat> {
+    match value.to_lowercase().as_str() {
+        "hash:salt" => Ok(options::SaltFormat::HashSalt),
+        "salt:hash" => Ok(options::SaltFormat::SaltHash),
+        _ => bail!("possible values are [hash:salt, salt:hash]",),
+    }
+}
+
 fn to_verboseness(value: u64) -> print::Verboseness {
     match value {
         0 => print::Verboseness::None,
@@ -183,96 +703,435 @@ fn to_verboseness(value: u64) -> print::Verboseness {
     }
 }
 
-pub fn algorithm() -> hash::Algorithm {
-    std::env::args()
-        .position(|arg| arg == "-a" || arg == "--algorithm")
-        .and_then(|index| {
-            std::env::args()
-                .nth(index + 1)
-                .and_then(|string| to_algorithm(&string).ok())
-        })
-        .unwrap_or(hash::Algorithm::sha256)
+// The subcommand given along with its algorithm and chain, parsed as for any algorithm since
+// their modes only differ by their description. Invalid arguments are left to the parsing of the
+// mode to report
+#[derive(Debug)]
+struct Given {
+    subcommand: String,
+    algorithm: Option<String>,
+    chain: Option<String>,
 }
 
-pub fn parse_sha256() -> Result<(options::Mode<hash::sha256::Hash>, print::Printer)> {
-    use clap::Parser;
-    use hash::sha256::Hash as H;
+impl Given {
+    fn parse() -> Option<Self> {
+        Self::parse_from(std::env::args_os())
+    }
 
-    let (mode, mut printer) = match RawModeSha256::parse() {
-        RawModeSha256::Hash(encrypt) => compose_hash::<H>(encrypt),
-        RawModeSha256::Crack(decrypt) => compose_crack::<H>(decrypt.shared, decrypt.input),
-    }?;
+    fn parse_from<I, T>(args: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        use clap::CommandFactory;
 
-    if mode.input_len() == 1 {
-        printer.set_single_input_mode();
+        let matches = sha256::RawMode::command().try_get_matches_from(args).ok()?;
+        let (subcommand, matches) = matches.subcommand()?;
+        // Only hashing and cracking take a chain
+        let chain = match subcommand {
+            "hash" | "crack" => given_value(matches, "chain"),
+            _ => None,
+        };
+
+        Some(Self {
+            subcommand: subcommand.to_owned(),
+            algorithm: given_value(matches, "algorithm"),
+            chain,
+        })
     }
 
-    Ok((mode, printer))
-}
+    fn detected(&self) -> bool {
+        self.subcommand == "crack" && self.algorithm.is_none() && self.chain.is_none()
+    }
 
-pub fn parse_md5() -> Result<(options::Mode<hash::md5::Hash>, print::Printer)> {
-    use clap::Parser;
-    use hash::md5::Hash as H;
+    // A chain gives the algorithm of its outermost hash
+    fn algorithm(&self) -> Option<hash::Algorithm> {
+        self.chain
+            .as_deref()
+            .and_then(|spec| options::Chain::parse(spec, options::Intermediate::Hex).ok())
+            .map(|chain| chain.algorithm())
+            .or_else(|| {
+                self.algorithm
+                    .as_deref()
+                    .and_then(|string| to_algorithm(string).ok())
+            })
+    }
 
-    let (mode, mut printer) = match RawModeMd5::parse() {
-        RawModeMd5::Hash(encrypt) => compose_hash::<H>(encrypt),
-        RawModeMd5::Crack(decrypt) => compose_crack::<H>(decrypt.shared, decrypt.input),
-    }?;
+    // Slow hashes are only ever cracked
+    fn kdf(&self) -> Result<Option<kdf::Kdf>> {
+        match self.algorithm.as_deref().map(str::parse) {
+            Some(Ok(kdf)) if self.subcommand != "crack" => {
+                bail!("{} hashes can only be cracked", kdf)
+            }
+            Some(Ok(kdf)) => Ok(Some(kdf)),
+            _ => Ok(None),
+        }
+    }
+}
 
-    if mode.input_len() == 1 {
-        printer.set_single_input_mode();
+// The value of an argument, unless it is only its default
+fn given_value(matches: &clap::ArgMatches, id: &str) -> Option<String> {
+    if matches.occurrences_of(id) == 0 {
+        return None;
     }
+    matches.value_of(id).map(String::from)
+}
+
+pub fn algorithm() -> hash::Algorithm {
+    Given::parse()
+        .and_then(|given| given.algorithm())
+        .unwrap_or(hash::Algorithm::sha256)
+}
 
-    Ok((mode, printer))
+/// The slow function given as the algorithm, if any
+pub fn kdf() -> Result<Option<kdf::Kdf>> {
+    Given::parse().map_or(Ok(None), |given| given.kdf())
 }
 
 fn compose_hash<H: hash::Hash>(encrypt: RawHash) -> Result<(options::Mode<H>, print::Printer)> {
     let printer = print::new(encrypt.shared.verbose, encrypt.shared.colored);
 
+    // The XOR mask alone stands for its default transforms
+    let transforms = if encrypt.digest.transform.is_empty() {
+        xor(encrypt.xor)?.map_or_else(options::Pipeline::default, options::Pipeline::xor)
+    } else {
+        options::Pipeline::new(
+            transforms(&encrypt.digest.transform, encrypt.xor)?.unwrap_or_default(),
+        )?
+    };
+
     Ok((
         options::Mode::Encrypt(options::Encrypt::<H>::new(
             read_string_from_stdin(encrypt.input.into_iter().collect(), printer),
-            salt(encrypt.shared.salt.map(Option::unwrap_or_default)),
+            salt(encrypt.salt.salt.map(Option::unwrap_or_default)),
+            encrypt.salt.salt_position,
+            transforms,
+            encrypt.digest.encoding,
+            hmac_key(encrypt.digest.hmac_key)?,
+            chain(encrypt.digest.chain.as_deref(), encrypt.digest.intermediate)?,
+            encrypt.prefix,
+            encrypt.length,
         )?),
         printer,
     ))
 }
 
 fn compose_crack<H: hash::Hash>(
-    shared: RawCrackShared,
-    input: Vec<H>,
+    shared: &RawCrackShared,
+    values: &[String],
 ) -> Result<(options::Mode<H>, print::Printer)> {
     let printer = print::new(shared.shared.verbose, shared.shared.colored);
 
-    let prefix = shared.prefix.unwrap_or_default();
+    let files = crack_files(shared);
+    let (mut input, mut salts) = parse_hashes::<H>(values, shared.salted)?;
+
+    for file in &files {
+        printer.read_start(file.display().to_string());
+        printer.read_done(match shared.salted {
+            Some(format) => files::read_salted(&mut salts, file, format),
+            None => files::read(&mut input, file),
+        });
+    }
+
+    // A wordlist read from stdin takes it over
+    if !atty::is(atty::Stream::Stdin) && shared.wordlist != Some(options::Wordlist::Stdin) {
+        printer.read_start("stdin");
+        let stdin = std::io::stdin();
+        printer.read_done(match shared.salted {
+            Some(format) => files::read_salted_from_stream(&mut salts, stdin.lock(), format),
+            None => files::read_from_stream(&mut input, stdin.lock()),
+        });
+    }
+
+    Ok((
+        options::Mode::Decrypt(decrypt(shared, files, input, salts)?),
+        printer,
+    ))
+}
 
-    let files = shared
-        .files
-        .into_iter()
-        .collect::<std::collections::HashSet<_>>();
-    let mut input: std::collections::HashSet<H> = input.into_iter().collect();
+// Options of the cracker hashing values without a salt or a key of their own are refused
+fn compose_kdf_crack(
+    kdf: kdf::Kdf,
+    shared: &RawCrackShared,
+    values: Vec<String>,
+) -> Result<(options::KdfDecrypt, print::Printer)> {
+    let printer = print::new(shared.shared.verbose, shared.shared.colored);
+    check_kdf(kdf, shared)?;
+
+    let files = crack_files(shared);
+    let mut input = std::collections::HashSet::with_capacity(values.len());
+    for value in values {
+        input.insert(kdf::Target::parse(kdf, &value)?);
+    }
 
     for file in &files {
         printer.read_start(file.display().to_string());
-        printer.read_done(files::read(&mut input, file));
+        printer.read_done(files::read_targets(&mut input, kdf, file));
     }
 
-    if !atty::is(atty::Stream::Stdin) {
+    // A wordlist read from stdin takes it over
+    if !atty::is(atty::Stream::Stdin) && shared.wordlist != Some(options::Wordlist::Stdin) {
+        printer.read_start("stdin");
+        printer.read_done(files::read_targets_from_stream(
+            &mut input,
+            kdf,
+            std::io::stdin().lock(),
+        ));
+    }
+
+    Ok((kdf_decrypt(kdf, shared, files, input)?, printer))
+}
+
+fn crack_files(shared: &RawCrackShared) -> std::collections::HashSet<std::path::PathBuf> {
+    shared.files.iter().cloned().collect()
+}
+
+// With a salt format, hashes given along with their salt are kept apart
+fn parse_hashes<H: hash::Hash>(
+    values: impl IntoIterator<Item = impl AsRef<str>>,
+    salted: Option<options::SaltFormat>,
+) -> Result<(
+    std::collections::HashSet<H>,
+    std::collections::HashMap<H, String>,
+)> {
+    let mut input = std::collections::HashSet::new();
+    let mut salts = std::collections::HashMap::new();
+    for value in values {
+        let value = value.as_ref();
+        match salted {
+            Some(format) if value.contains(':') => {
+                let (hash, salt) = files::parse_salted(value, format)?;
+                files::insert_salted(&mut salts, hash, salt)?;
+            }
+            _ => {
+                input.insert(H::from_str(value)?);
+            }
+        }
+    }
+    Ok((input, salts))
+}
+
+fn decrypt<H: hash::Hash>(
+    shared: &RawCrackShared,
+    files: std::collections::HashSet<std::path::PathBuf>,
+    input: std::collections::HashSet<H>,
+    salts: std::collections::HashMap<H, String>,
+) -> Result<options::Decrypt<H>> {
+    let (transforms, xor) = crack_transforms(&shared.digest.transform, shared.xor.clone())?;
+    let keyspace = Keyspace::new(shared)?;
+
+    options::DecryptBuilder::new(input, keyspace.lengths)
+        .chain(chain(
+            shared.digest.chain.as_deref(),
+            shared.digest.intermediate,
+        )?)
+        .checksum(shared.checksum)
+        .dates(keyspace.dates)
+        .device(shared.device)
+        .emit(shared.emit)
+        .encoding(shared.digest.encoding)
+        .files(files)
+        .hmac_key(hmac_key(shared.digest.hmac_key.clone())?)
+        .masks(keyspace.masks)
+        .prefixes(shared.prefix.clone())
+        .rules(keyspace.rules)
+        .salt(salt(
+            shared.salt.salt.clone().map(Option::unwrap_or_default),
+        ))
+        .salt_position(shared.salt.salt_position)
+        .salts(salts)
+        .threads(shared.threads)
+        .transforms(transforms)
+        .wordlist(shared.wordlist.clone())
+        .xor(xor)
+        .build()
+}
+
+fn check_kdf(kdf: kdf::Kdf, shared: &RawCrackShared) -> Result<()> {
+    if shared.salt.salt.is_some() || shared.salted.is_some() {
+        bail!("{} hashes carry their own salt", kdf);
+    }
+    if shared.digest.hmac_key.is_some() || shared.digest.chain.is_some() {
+        bail!("{} hashes cannot be keyed or chained", kdf);
+    }
+    if shared.device == Some(options::Device::Gpu) {
+        bail!("{} can only be cracked on the CPU", kdf);
+    }
+    Ok(())
+}
+
+fn kdf_decrypt(
+    kdf: kdf::Kdf,
+    shared: &RawCrackShared,
+    files: std::collections::HashSet<std::path::PathBuf>,
+    input: std::collections::HashSet<kdf::Target>,
+) -> Result<options::KdfDecrypt> {
+    let (transforms, xor) = crack_transforms(&shared.digest.transform, shared.xor.clone())?;
+    let keyspace = Keyspace::new(shared)?;
+
+    options::KdfDecryptBuilder::new(kdf, input, keyspace.lengths)
+        .checksum(shared.checksum)
+        .dates(keyspace.dates)
+        .emit(shared.emit)
+        .encoding(shared.digest.encoding)
+        .files(files)
+        .masks(keyspace.masks)
+        .prefixes(shared.prefix.clone())
+        .rules(keyspace.rules)
+        .threads(shared.threads)
+        .transforms(transforms)
+        .wordlist(shared.wordlist.clone())
+        .xor(xor)
+        .build()
+}
+
+/// Candidates of a crack besides its prefixes and wordlist
+struct Keyspace {
+    lengths: Vec<u8>,
+    masks: Vec<options::Mask>,
+    dates: Option<options::Dates>,
+    rules: Option<Vec<options::Rule>>,
+}
+
+impl Keyspace {
+    // Without any length, mask, date or wordlist, the default length is searched
+    fn new(shared: &RawCrackShared) -> Result<Self> {
+        let charsets = [
+            shared.charset1.clone(),
+            shared.charset2.clone(),
+            shared.charset3.clone(),
+            shared.charset4.clone(),
+        ];
+        let masks = shared
+            .mask
+            .iter()
+            .map(|mask| options::Mask::parse(mask, &charsets))
+            .chain(shared.template.iter().map(|t| options::Mask::template(t)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let (first_year, last_year) = shared.years;
+        let dates = shared
+            .date
+            .as_deref()
+            .map(|format| options::Dates::parse(format, first_year, last_year))
+            .transpose()?;
+
+        let rules = shared.rules.as_deref().map(files::read_rules).transpose()?;
+
+        let lengths = if shared.length.is_empty()
+            && masks.is_empty()
+            && dates.is_none()
+            && shared.wordlist.is_none()
+        {
+            vec![DEFAULT_LENGTH]
+        } else {
+            shared.length.clone()
+        };
+
+        Ok(Self {
+            lengths,
+            masks,
+            dates,
+            rules,
+        })
+    }
+}
+
+fn compose_salt_find<H: hash::Hash>(
+    find: RawSaltFind,
+) -> Result<(options::Mode<H>, print::Printer)> {
+    let printer = print::new(find.shared.verbose, find.shared.colored);
+
+    let mut pairs = std::collections::HashMap::<H, String>::new();
+    for value in find.input {
+        let (hash, value) = files::parse_pair(&value)?;
+        pairs.insert(hash, value);
+    }
+
+    for file in &find.files {
+        printer.read_start(file.display().to_string());
+        printer.read_done(files::read_pairs(&mut pairs, file));
+    }
+
+    // A wordlist read from stdin takes it over
+    if !atty::is(atty::Stream::Stdin) && find.wordlist != Some(options::Wordlist::Stdin) {
         printer.read_start("stdin");
-        printer.read_done(files::read_from_stream(&mut input, std::io::stdin().lock()));
+        printer.read_done(files::read_pairs_from_stream(
+            &mut pairs,
+            std::io::stdin().lock(),
+        ));
     }
 
+    let candidates = match (find.salt.is_empty(), find.wordlist, find.mask) {
+        (false, None, None) => options::SaltCandidates::List(find.salt),
+        (true, Some(wordlist), None) => options::SaltCandidates::Wordlist(wordlist),
+        (true, None, Some(mask)) => {
+            options::SaltCandidates::Mask(options::Mask::parse(&mask, &[])?)
+        }
+        _ => bail!("Exactly one of salts, wordlist or mask must be provided"),
+    };
+
+    Ok((
+        options::Mode::SaltFind(options::SaltFind::new(
+            pairs,
+            candidates,
+            find.salt_position,
+            find.threads,
+        )?),
+        printer,
+    ))
+}
+
+fn compose_xor_find<H: hash::Hash>(find: RawXorFind) -> Result<(options::Mode<H>, print::Printer)> {
+    let mut printer = print::new(find.shared.verbose, find.shared.colored);
+    // Only the mask is printed
+    printer.set_single_input_mode();
+
+    let known = if find.encoded {
+        let mut pairs = std::collections::HashMap::new();
+        for value in find.input {
+            let (encoded, value) = files::parse_encoded_pair(&value)?;
+            pairs.insert(encoded, value);
+        }
+        for file in &find.files {
+            printer.read_start(file.display().to_string());
+            printer.read_done(files::read_encoded_pairs(&mut pairs, file));
+        }
+        if !atty::is(atty::Stream::Stdin) {
+            printer.read_start("stdin");
+            printer.read_done(files::read_encoded_pairs_from_stream(
+                &mut pairs,
+                std::io::stdin().lock(),
+            ));
+        }
+        options::XorKnown::Encoded(pairs.into_iter().collect())
+    } else {
+        let mut pairs = std::collections::HashMap::new();
+        for value in find.input {
+            let (hash, value) = files::parse_pair(&value)?;
+            pairs.insert(hash, value);
+        }
+        for file in &find.files {
+            printer.read_start(file.display().to_string());
+            printer.read_done(files::read_pairs(&mut pairs, file));
+        }
+        if !atty::is(atty::Stream::Stdin) {
+            printer.read_start("stdin");
+            printer.read_done(files::read_pairs_from_stream(
+                &mut pairs,
+                std::io::stdin().lock(),
+            ));
+        }
+        options::XorKnown::Hashes(pairs.into_iter().collect())
+    };
+
     Ok((
-        options::Mode::Decrypt(
-            options::DecryptBuilder::new(input, shared.length)
-                .device(shared.device)
-                .files(files)
-                .prefix(prefix)
-                .salt(salt(shared.shared.salt.map(Option::unwrap_or_default)))
-                .threads(shared.threads)
-                .xor(xor(shared.xor)?)
-                .build()?,
-        ),
+        options::Mode::XorFind(options::XorFind::new(
+            known,
+            salt(find.salt.salt.map(Option::unwrap_or_default)),
+            find.salt.salt_position,
+            find.threads,
+        )?),
         printer,
     ))
 }
@@ -312,3 +1171,130 @@ fn xor(maybe_xor: Option<Option<String>>) -> Result<Option<Vec<u8>>> {
             .map_err(|err| error!(err; "Failed to decode XOR mask"))
     })
 }
+
+// Without a value, the key is read from the environment
+#[allow(clippy::option_option)]
+fn hmac_key(maybe_key: Option<Option<String>>) -> Result<Option<String>> {
+    maybe_key.map_or(Ok(None), |maybe_key| {
+        maybe_key
+            .or_else(|| std::env::var(HMAC_KEY_ENV).ok())
+            .map(Option::Some)
+            .ok_or_else(|| error!("No HMAC key given, nor in {}", HMAC_KEY_ENV))
+    })
+}
+
+fn chain(
+    spec: Option<&str>,
+    intermediate: options::Intermediate,
+) -> Result<Option<options::Chain>> {
+    spec.map(|spec| options::Chain::parse(spec, intermediate))
+        .transpose()
+}
+
+/// Transforms of a crack, or the XOR mask applied without them
+type CrackTransforms = (Option<Vec<options::Transform>>, Option<Vec<u8>>);
+
+// The XOR mask alone stands for its default transforms
+#[allow(clippy::option_option)]
+fn crack_transforms(
+    specs: &[String],
+    maybe_xor: Option<Option<String>>,
+) -> Result<CrackTransforms> {
+    if specs.is_empty() {
+        Ok((None, xor(maybe_xor)?))
+    } else {
+        Ok((transforms(specs, maybe_xor)?, None))
+    }
+}
+
+// The XOR mask is only resolved if an xor transform uses it
+#[allow(clippy::option_option)]
+fn transforms(
+    specs: &[String],
+    maybe_xor: Option<Option<String>>,
+) -> Result<Option<Vec<options::Transform>>> {
+    if specs.is_empty() {
+        return Ok(None);
+    }
+
+    let mask = if specs.iter().any(|spec| spec.eq_ignore_ascii_case("xor")) {
+        xor(Some(maybe_xor.flatten()))?
+    } else {
+        None
+    };
+    specs
+        .iter()
+        .map(|spec| options::Transform::parse(spec, mask.as_deref()))
+        .collect::<Result<Vec<_>>>()
+        .map(Option::Some)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Given;
+    use crate::hash;
+    use crate::kdf;
+
+    fn given(args: &[&str]) -> Given {
+        Given::parse_from(std::iter::once("hasher").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn given_algorithm() {
+        for args in [
+            &["crack", "-a", "md5", "a1d0c6e83f027327d8461063f4ac58a6"][..],
+            &[
+                "crack",
+                "--algorithm",
+                "md5",
+                "a1d0c6e83f027327d8461063f4ac58a6",
+            ],
+            &[
+                "crack",
+                "--algorithm=md5",
+                "a1d0c6e83f027327d8461063f4ac58a6",
+            ],
+            &["crack", "-amd5", "a1d0c6e83f027327d8461063f4ac58a6"],
+        ] {
+            let given = given(args);
+            assert!(!given.detected(), "{:?}", args);
+            assert_eq!(given.algorithm(), Some(hash::Algorithm::md5), "{:?}", args);
+        }
+
+        assert_eq!(
+            given(&["hash", "-amd4", "abc"]).algorithm(),
+            Some(hash::Algorithm::md4)
+        );
+        assert_eq!(
+            given(&["crack", "-abcrypt"]).kdf().unwrap(),
+            Some(kdf::Kdf::Bcrypt)
+        );
+        assert_eq!(given(&["crack", "--algorithm=md5"]).kdf().unwrap(), None);
+        assert!(given(&["hash", "-a", "bcrypt", "42"]).kdf().is_err());
+        assert!(given(&["salt-find", "--algorithm=bcrypt"]).kdf().is_err());
+    }
+
+    #[test]
+    fn given_chain() {
+        for args in [
+            &["crack", "--chain", "md5(sha1($p))"][..],
+            &["crack", "--chain=md5(sha1($p))"],
+        ] {
+            let given = given(args);
+            assert!(!given.detected(), "{:?}", args);
+            assert_eq!(given.algorithm(), Some(hash::Algorithm::md5), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn given_detected() {
+        let detected = given(&["crack", "-v", "a1d0c6e83f027327d8461063f4ac58a6"]);
+        assert!(detected.detected());
+        assert_eq!(detected.algorithm(), None);
+        assert_eq!(detected.kdf().unwrap(), None);
+
+        assert!(!given(&["hash", "abc"]).detected());
+        assert!(!given(&["salt-find", "-amd5", "abc"]).detected());
+        assert!(Given::parse_from(["hasher", "crack", "--unknown"]).is_none());
+    }
+}
@@ -1,9 +1,14 @@
 use crate::decrypt;
+use crate::detect;
 use crate::encrypt;
 use crate::error;
 use crate::files;
 use crate::hash;
+use crate::kdf;
 use crate::options;
+use crate::results;
+use crate::salt;
+use crate::xor;
 
 mod args;
 mod channel;
@@ -11,6 +16,7 @@ mod print;
 
 pub fn run() {
     setup_panic();
+    setup_cancel();
 
     if !run_wrapped().map_err(print_error).unwrap_or(false) {
         std::process::exit(-1);
@@ -18,9 +24,81 @@ pub fn run() {
 }
 
 fn run_wrapped() -> Result<bool, error::Error> {
+    if let Some(kdf) = args::kdf()? {
+        return Ok(run_kdf(args::parse_kdf(kdf)?));
+    }
+    if args::detected() {
+        return run_detected(&args::parse_detected()?);
+    }
+
     Ok(match args::algorithm() {
-        hash::Algorithm::sha256 => run_algorithm(args::parse_sha256()?),
-        hash::Algorithm::md5 => run_algorithm(args::parse_md5()?),
+        hash::Algorithm::sha256 => run_algorithm(args::sha256::parse()?),
+        hash::Algorithm::md4 => run_algorithm(args::md4::parse()?),
+        hash::Algorithm::md5 => run_algorithm(args::md5::parse()?),
+        hash::Algorithm::sha1 => run_algorithm(args::sha1::parse()?),
+        hash::Algorithm::sha384 => run_algorithm(args::sha384::parse()?),
+        hash::Algorithm::sha512 => run_algorithm(args::sha512::parse()?),
+        hash::Algorithm::sha3_256 => run_algorithm(args::sha3_256::parse()?),
+        hash::Algorithm::keccak256 => run_algorithm(args::keccak256::parse()?),
+        hash::Algorithm::blake2b => run_algorithm(args::blake2b::parse()?),
+        hash::Algorithm::blake3 => run_algorithm(args::blake3::parse()?),
+    })
+}
+
+// Every format detected is cracked in turn, even once one of them could not be fully cracked or
+// could not be cracked at all, and the files are then written once with the hashes of all of them
+fn run_detected(detection: &args::Detection) -> Result<bool, error::Error> {
+    let mut cracked = true;
+    let mut results = Vec::new();
+    for format in detection.formats() {
+        let summary = crack_format(detection, format).unwrap_or_else(|e| {
+            print_error(error!(e; "Could not crack the {} hashes", format));
+            None
+        });
+        match summary {
+            Some(summary) => {
+                cracked &= summary.results.len() == summary.total_count;
+                results.extend(summary.results);
+            }
+            None => cracked = false,
+        }
+    }
+
+    write_files(
+        detection.printer().into(),
+        &detection.regex()?,
+        detection.files(),
+        &results,
+        detection.emit(),
+    );
+    Ok(cracked)
+}
+
+fn crack_format(
+    detection: &args::Detection,
+    format: detect::Format,
+) -> Result<Option<results::Summary>, error::Error> {
+    Ok(match format {
+        detect::Format::Kdf(kdf) => {
+            let (options, printer) = detection.compose_kdf(kdf)?;
+            crack_kdf(&options, printer.into())
+        }
+        detect::Format::Hash(algorithm) => match algorithm {
+            hash::Algorithm::sha256 => crack_detected(detection.compose::<hash::sha256::Hash>()?),
+            hash::Algorithm::md4 => crack_detected(detection.compose::<hash::md4::Hash>()?),
+            hash::Algorithm::md5 => crack_detected(detection.compose::<hash::md5::Hash>()?),
+            hash::Algorithm::sha1 => crack_detected(detection.compose::<hash::sha1::Hash>()?),
+            hash::Algorithm::sha384 => crack_detected(detection.compose::<hash::sha384::Hash>()?),
+            hash::Algorithm::sha512 => crack_detected(detection.compose::<hash::sha512::Hash>()?),
+            hash::Algorithm::sha3_256 => {
+                crack_detected(detection.compose::<hash::sha3_256::Hash>()?)
+            }
+            hash::Algorithm::keccak256 => {
+                crack_detected(detection.compose::<hash::keccak256::Hash>()?)
+            }
+            hash::Algorithm::blake2b => crack_detected(detection.compose::<hash::blake2b::Hash>()?),
+            hash::Algorithm::blake3 => crack_detected(detection.compose::<hash::blake3::Hash>()?),
+        },
     })
 }
 
@@ -37,6 +115,13 @@ fn setup_panic() {
     }));
 }
 
+fn setup_cancel() {
+    if let Err(err) = ctrlc::set_handler(channel::cancel) {
+        eprintln!("Failed to capture SIGINT: {}", err);
+        eprintln!("CTRL + C will not interrupt the threads");
+    }
+}
+
 fn print_error<E: std::fmt::Display>(error: E) {
     use colored::Colorize;
     eprintln!("{} {}", "Error:".bright_red(), error);
@@ -45,40 +130,143 @@ fn print_error<E: std::fmt::Display>(error: E) {
 fn run_algorithm<H: hash::Hash>((options, printer): (options::Mode<H>, print::Printer)) -> bool {
     let channel: channel::Channel = printer.into();
 
-    if let Err(err) = ctrlc::set_handler(move || {
-        channel::cancel();
-    }) {
-        eprintln!("Failed to capture SIGINT: {}", err);
-        eprintln!("CTRL + C will not interrupt the threads");
-    }
-
     channel.options(&options);
 
     match &options {
         options::Mode::Encrypt(options) => encrypt::execute(options, &channel),
         options::Mode::Decrypt(options) => decrypt(options, channel),
+        options::Mode::SaltFind(options) => salt_find(options, channel),
+        options::Mode::XorFind(options) => xor_find(options, channel),
     }
 }
 
 fn decrypt<H: hash::Hash>(options: &options::Decrypt<H>, channel: channel::Channel) -> bool {
+    let summary = match crack(options, channel) {
+        Some(summary) => summary,
+        None => return false,
+    };
+
+    write_files(
+        channel,
+        H::regex(),
+        options.files(),
+        &summary.results,
+        options.emit(),
+    );
+    summary.results.len() == summary.total_count
+}
+
+// Detected hashes are only ever composed to be cracked
+fn crack_detected<H: hash::Hash>(
+    (options, printer): (options::Mode<H>, print::Printer),
+) -> Option<results::Summary> {
+    let channel: channel::Channel = printer.into();
+
+    channel.options(&options);
+
+    match &options {
+        options::Mode::Decrypt(options) => crack(options, channel),
+        _ => unreachable!("Detected hashes can only be cracked"),
+    }
+}
+
+fn crack<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    channel: channel::Channel,
+) -> Option<results::Summary> {
     let summary = match decrypt::execute(options, &channel) {
         Ok(summary) => summary,
         Err(err) => {
             print_error(err);
-            return false;
+            return None;
         }
     };
 
     channel.clear_progress();
     channel.summary(&summary);
 
-    if !options.files().is_empty() {
-        channel.files();
-        for file in options.files() {
-            channel.write_start(file.display().to_string());
-            channel.write_done(files::write(H::regex(), file, None, &summary.results));
+    Some(summary)
+}
+
+fn run_kdf((options, printer): (options::KdfDecrypt, print::Printer)) -> bool {
+    let channel: channel::Channel = printer.into();
+    let summary = match crack_kdf(&options, channel) {
+        Some(summary) => summary,
+        None => return false,
+    };
+
+    write_files(
+        channel,
+        options.kdf().regex(),
+        options.files(),
+        &summary.results,
+        options.emit(),
+    );
+    summary.results.len() == summary.total_count
+}
+
+fn crack_kdf(options: &options::KdfDecrypt, channel: channel::Channel) -> Option<results::Summary> {
+    channel.kdf_options(options);
+
+    let summary = match kdf::execute(options, &channel) {
+        Ok(summary) => summary,
+        Err(err) => {
+            print_error(err);
+            return None;
         }
+    };
+
+    channel.clear_progress();
+    channel.summary(&summary);
+
+    Some(summary)
+}
+
+// Every file is written once, with the cracked values in place of their hashes
+fn write_files(
+    channel: channel::Channel,
+    regex: &regex::Regex,
+    files: &std::collections::HashSet<std::path::PathBuf>,
+    results: &[results::Pair],
+    emit: options::Emit,
+) {
+    if files.is_empty() {
+        return;
+    }
+
+    channel.files();
+    for file in files {
+        channel.write_start(file.display().to_string());
+        channel.write_done(files::write(regex, file, None, results, emit));
     }
+}
+
+fn salt_find<H: hash::Hash>(options: &options::SaltFind<H>, channel: channel::Channel) -> bool {
+    let summary = match salt::execute(options, &channel) {
+        Ok(summary) => summary,
+        Err(err) => {
+            print_error(err);
+            return false;
+        }
+    };
+
+    channel.clear_progress();
+    channel.summary(&summary);
+
+    summary.results.len() == summary.total_count
+}
+
+fn xor_find<H: hash::Hash>(options: &options::XorFind<H>, channel: channel::Channel) -> bool {
+    let summary = match xor::execute(options, &channel) {
+        Ok(summary) => summary,
+        Err(err) => {
+            print_error(err);
+            return false;
+        }
+    };
+
+    channel.clear_progress();
+    channel.summary(&summary);
 
     summary.results.len() == summary.total_count
 }
@@ -1,5 +1,6 @@
 use crate::error;
 use crate::hash;
+use crate::kdf;
 use crate::options;
 use crate::results;
 use crate::Input;
@@ -65,6 +66,18 @@ impl Printer {
         }
     }
 
+    pub fn kdf_options(self, options: &options::KdfDecrypt) {
+        if self.verboseness as u8 > 1 {
+            section!("Options", self.colored);
+            kdf_decrypt_options(self.colored, options);
+            eprintln!();
+            kdf_input(self.colored, options.targets());
+        }
+        if self.verboseness as u8 > 0 {
+            section!("Output", self.colored);
+        }
+    }
+
     pub fn summary(self, summary: &results::Summary) {
         if self.verboseness as u8 > 0 {
             print_summary(self.colored, summary);
@@ -132,6 +145,15 @@ impl Printer {
         }
     }
 
+    pub fn note(self, note: impl std::fmt::Display) {
+        if self.colored {
+            use colored::Colorize;
+            eprintln!("{} {}", "Note:".yellow(), note);
+        } else {
+            eprintln!("Note: {}", note);
+        }
+    }
+
     pub fn report(self, input: &str, output: &str) {
         self.clear_progress();
         if self.single_input {
@@ -166,6 +188,8 @@ fn mode_options<H: hash::Hash>(colored: bool, options: &options::Mode<H>) {
     match options {
         options::Mode::Encrypt(options) => encrypt_options(colored, options),
         options::Mode::Decrypt(options) => decrypt_options(colored, options),
+        options::Mode::SaltFind(options) => salt_find_options(colored, options),
+        options::Mode::XorFind(options) => xor_find_options(colored, options),
     }
 
     eprintln!();
@@ -177,19 +201,78 @@ fn shared_options<T: Input, O: options::SharedAccessor<T>>(
     algorithm: &str,
 ) {
     eprintln!("{:15}{}", colorize!("Algorithm:", colored), algorithm);
+    if let Some(chain) = options.chain() {
+        eprintln!("{:15}{}", colorize!("Chain:", colored), chain);
+        eprintln!(
+            "{:15}{}",
+            colorize!("Intermediate:", colored),
+            chain.intermediate()
+        );
+    }
     if !options.salt().is_empty() {
         eprintln!("{:15}{}", colorize!("Salt:", colored), options.salt());
+        // The chain places the salt itself
+        if options.chain().is_none() {
+            eprintln!(
+                "{:15}{}",
+                colorize!("Salt position:", colored),
+                options.salt_position()
+            );
+        }
+    }
+    if !options.transforms().is_empty() {
+        eprintln!(
+            "{:15}{}",
+            colorize!("Transforms:", colored),
+            options.transforms()
+        );
+    }
+    if let Some(key) = options.hmac_key() {
+        eprintln!("{:15}{}", colorize!("HMAC key:", colored), key);
+    }
+    if options.encoding() != options::Encoding::Utf8 {
+        eprintln!(
+            "{:15}{}",
+            colorize!("Encoding:", colored),
+            options.encoding()
+        );
+    }
+    for transform in options.transforms().transforms() {
+        if let options::Transform::Xor(xor) = transform {
+            eprintln!("{:15}{}", colorize!("XOR:", colored), base64::encode(xor));
+        }
     }
 }
 
 fn encrypt_options<H: hash::Hash>(colored: bool, options: &options::Encrypt<H>) {
     shared_options(colored, options, H::name());
+    if !options.prefix().is_empty() {
+        eprintln!("{:15}{}", colorize!("Prefix:", colored), options.prefix());
+    }
+    if let Some(length) = options.length() {
+        eprintln!("{:15}{}", colorize!("Length:", colored), length);
+    }
 }
 
 fn decrypt_options<H: hash::Hash>(colored: bool, options: &options::Decrypt<H>) {
+    use options::SharedAccessor;
     shared_options(colored, options, H::name());
-    if let Some(ref xor) = options.xor() {
-        eprintln!("{:15}{}", colorize!("XOR:", colored), base64::encode(xor));
+    if !options.salts().is_empty() {
+        eprintln!(
+            "{:15}{}",
+            colorize!("Own salts:", colored),
+            options.salts().len()
+        );
+        if options.salt().is_empty() {
+            eprintln!(
+                "{:15}{}",
+                colorize!("Salt position:", colored),
+                options.salt_position()
+            );
+        }
+    }
+    if !options.transforms().is_empty() {
+        eprintln!("{:15}{}", colorize!("Emit:", colored), options.emit());
     }
     eprintln!("{:15}{}", colorize!("Device:", colored), options.device());
     if options::Device::Cpu == options.device() {
@@ -203,19 +286,171 @@ fn decrypt_options<H: hash::Hash>(colored: bool, options: &options::Decrypt<H>)
             }
         );
     }
-    if !options.prefix().is_empty() {
-        eprintln!("{:15}{}", colorize!("Prefix:", colored), options.prefix());
+    keyspace_options(
+        colored,
+        options.wordlist(),
+        options.rules(),
+        options.segments(),
+        options.number_space(),
+    );
+}
+
+fn kdf_decrypt_options(colored: bool, options: &options::KdfDecrypt) {
+    eprintln!("{:15}{}", colorize!("Algorithm:", colored), options.kdf());
+    if !options.transforms().is_empty() {
+        eprintln!(
+            "{:15}{}",
+            colorize!("Transforms:", colored),
+            options.transforms()
+        );
+        eprintln!("{:15}{}", colorize!("Emit:", colored), options.emit());
+    }
+    if options.encoding() != options::Encoding::Utf8 {
+        eprintln!(
+            "{:15}{}",
+            colorize!("Encoding:", colored),
+            options.encoding()
+        );
+    }
+    eprintln!("{:15}{}", colorize!("Threads:", colored), options.threads());
+    keyspace_options(
+        colored,
+        options.wordlist(),
+        options.rules(),
+        options.segments(),
+        options.number_space(),
+    );
+}
+
+fn keyspace_options(
+    colored: bool,
+    wordlist: Option<&options::Wordlist>,
+    rules: &[options::Rule],
+    segments: &[options::Segment],
+    number_space: u64,
+) {
+    if let Some(wordlist) = wordlist {
+        eprintln!("{:15}{}", colorize!("Wordlist:", colored), wordlist);
+        if !rules.is_empty() {
+            eprintln!("{:15}{}", colorize!("Rules:", colored), rules.len());
+        }
+        if segments.iter().all(|segment| segment.length() == 0) {
+            let prefixes = segments
+                .iter()
+                .map(options::Segment::prefix)
+                .filter(|prefix| !prefix.is_empty())
+                .collect::<Vec<_>>();
+            if !prefixes.is_empty() {
+                eprintln!(
+                    "{:15}{}",
+                    colorize!("Prefixes:", colored),
+                    prefixes.join(", ")
+                );
+            }
+            return;
+        }
+    }
+
+    if let [segment] = segments {
+        segment_options(colored, segment, "");
+    } else {
+        eprintln!("{:15}", colorize!("Segments:", colored));
+        for segment in segments {
+            segment_options(colored, segment, "  ");
+        }
     }
+    eprintln!(
+        "{:15}{}{}",
+        colorize!("Possibilities:", colored),
+        number(number_space),
+        if wordlist.is_some() { " per word" } else { "" }
+    );
+}
+
+fn salt_find_options<H: hash::Hash>(colored: bool, options: &options::SaltFind<H>) {
+    eprintln!("{:15}{}", colorize!("Algorithm:", colored), H::name());
     eprintln!(
         "{:15}{}",
-        colorize!("Length:", colored),
-        options.length() + options.prefix_length()
+        colorize!("Salts:", colored),
+        options.candidates()
     );
     eprintln!(
         "{:15}{}",
-        colorize!("Possibilities:", colored),
-        number(options.number_space())
+        colorize!("Salt position:", colored),
+        options
+            .positions()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
     );
+    eprintln!("{:15}{}", colorize!("Threads:", colored), options.threads());
+}
+
+fn xor_find_options<H: hash::Hash>(colored: bool, options: &options::XorFind<H>) {
+    if let options::XorKnown::Encoded(_) = options.known() {
+        eprintln!("{:15}Masked values", colorize!("Known:", colored));
+        return;
+    }
+
+    eprintln!("{:15}{}", colorize!("Algorithm:", colored), H::name());
+    if !options.salt().is_empty() {
+        eprintln!("{:15}{}", colorize!("Salt:", colored), options.salt());
+        eprintln!(
+            "{:15}{}",
+            colorize!("Salt position:", colored),
+            options.salt_position()
+        );
+    }
+    eprintln!("{:15}{}", colorize!("Threads:", colored), options.threads());
+}
+
+fn segment_options(colored: bool, segment: &options::Segment, indent: &str) {
+    let width = 15 - indent.len();
+    if !segment.prefix().is_empty() {
+        eprintln!(
+            "{}{:3$}{}",
+            indent,
+            colorize!("Prefix:", colored),
+            segment.prefix(),
+            width
+        );
+    }
+    if let Some(dates) = segment.dates() {
+        eprintln!(
+            "{}{:3$}{}",
+            indent,
+            colorize!("Dates:", colored),
+            dates,
+            width
+        );
+    }
+    if segment.mask().is_decimal() {
+        eprintln!(
+            "{}{:3$}{}",
+            indent,
+            colorize!("Length:", colored),
+            segment.length() + segment.prefix_length(),
+            width
+        );
+    } else {
+        eprintln!(
+            "{}{:3$}{}",
+            indent,
+            colorize!("Mask:", colored),
+            segment.mask(),
+            width
+        );
+    }
+    if let Some(checksum) = segment.checksum() {
+        eprintln!(
+            "{}{:3$}{}",
+            indent,
+            colorize!("Checksum:", colored),
+            checksum,
+            width
+        );
+    }
 }
 
 fn input<H: hash::Hash>(colored: bool, options: &options::Mode<H>) {
@@ -223,7 +458,32 @@ fn input<H: hash::Hash>(colored: bool, options: &options::Mode<H>) {
     section!("Input", colored);
     match options {
         options::Mode::Encrypt(mode) => mode.input().iter().for_each(|i| eprintln!("{}", i)),
-        options::Mode::Decrypt(mode) => mode.input().iter().for_each(|i| eprintln!("{}", i)),
+        options::Mode::Decrypt(mode) => mode.input().iter().for_each(|i| {
+            if let Some(salt) = mode.salts().get(i) {
+                eprintln!("{}:{}", i, salt);
+            } else {
+                eprintln!("{}", i);
+            }
+        }),
+        options::Mode::SaltFind(mode) => mode
+            .pairs()
+            .iter()
+            .for_each(|(hash, value)| eprintln!("{}:{}", value, hash)),
+        options::Mode::XorFind(mode) => match mode.known() {
+            options::XorKnown::Hashes(pairs) => pairs
+                .iter()
+                .for_each(|(hash, value)| eprintln!("{}:{}", value, hash)),
+            options::XorKnown::Encoded(pairs) => pairs
+                .iter()
+                .for_each(|(encoded, value)| eprintln!("{}:{}", value, base64::encode(encoded))),
+        },
+    }
+}
+
+fn kdf_input(colored: bool, targets: &[kdf::Target]) {
+    section!("Input", colored);
+    for target in targets {
+        eprintln!("{}", target);
     }
 }
 
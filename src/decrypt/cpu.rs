@@ -1,5 +1,6 @@
 use crate::channel;
 use crate::error;
+use crate::files;
 use crate::hash;
 use crate::options;
 use crate::results;
@@ -12,7 +13,7 @@ pub const OPTIMAL_HASHES_PER_THREAD: u64 = 1024 * 16;
 // 1: A sender raw pointer may not outlive the thread
 // 2: All threads are joined before the references go out of scope
 #[derive(Clone, Copy)]
-pub struct Sender<T>(*const T);
+pub struct Sender<T>(pub *const T);
 
 impl<T> std::ops::Deref for Sender<T> {
     type Target = T;
@@ -31,35 +32,79 @@ pub fn execute<H: hash::Hash>(
     let time = std::time::Instant::now();
 
     let count = std::sync::atomic::AtomicUsize::new(options.input().len());
+    let found = std::sync::Mutex::new(std::collections::HashSet::new());
     let input = options.input_as_eytzinger();
 
-    let thread_count = options.threads();
-    let thread_space = options.number_space() / u64::from(thread_count);
-    let mut threads = Vec::<_>::with_capacity(thread_count as usize);
+    let mut hash_count = 0;
+    let mut results = Vec::with_capacity(input.len());
+    let mut offset = 0;
 
     channel.progress(0);
-    for t in 0..u64::from(thread_count) {
-        let count_sender = Sender(&count);
-        let input_sender = Sender(&input);
-        let xor_sender = Sender(options.xor());
+    for segment in options.segments() {
+        if channel.should_terminate() || count.load(std::sync::atomic::Ordering::Acquire) == 0 {
+            break;
+        }
+
+        let (segment_count, segment_results) =
+            execute_segment(options, segment, offset, &input, &count, &found, channel)?;
+        hash_count += segment_count;
+        results.extend(segment_results);
+        offset += segment.number_space();
+    }
+
+    Ok(results::Summary {
+        total_count: input.len(),
+        duration: time.elapsed(),
+        hash_count,
+        threads: u32::from(options.threads()),
+        results,
+    })
+}
+
+// Allowed because the `Sender` needs a sized type to point to
+#[allow(clippy::ptr_arg)]
+fn execute_segment<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    segment: &options::Segment,
+    offset: u64,
+    input: &Vec<H>,
+    count: &std::sync::atomic::AtomicUsize,
+    found: &std::sync::Mutex<std::collections::HashSet<H>>,
+    channel: &impl channel::Channel,
+) -> Result<(u64, Vec<results::Pair>), error::Error> {
+    let thread_count = u64::from(options.threads());
+    let thread_space = (segment.number_space() + thread_count - 1) / thread_count;
+    let mut threads = Vec::<_>::with_capacity(options.threads() as usize);
+
+    for t in 0..thread_count {
+        let count_sender = Sender(count);
+        let found_sender = Sender(found);
+        let input_sender = Sender(input);
+        let transforms_sender = Sender(options.transforms());
+        let emit = options.emit();
+        let options_sender = Sender(options);
         let channel_sender = Sender(channel);
 
-        let prefix = String::from(options.prefix());
-        let salt = if options.xor().is_some() {
-            options.salt().to_string()
+        let prefix = String::from(segment.prefix());
+        let salt = if options.transforms().is_empty() {
+            // If no transforms, optimize by precalculating the salted prefix
+            format!("{}{}", options.salt_prefix(), segment.prefix())
         } else {
-            // If no xor, optimize by precalculating the salted prefix
-            format!("{}{}", options.salt(), options.prefix())
+            options.salt_prefix().to_string()
         };
-        let length = options.length() as usize;
-        let first = t * thread_space;
-        let last = std::cmp::min(first + thread_space, options.number_space());
+        let salt_suffix = options.salt_suffix().to_string();
+        let first = std::cmp::min(t * thread_space, segment.number_space());
+        let last = std::cmp::min(first + thread_space, segment.number_space());
+        let segment = segment.clone();
+        let total = options.number_space();
 
         threads.push(std::thread::spawn(move || {
             let count = count_sender;
+            let found = found_sender;
             let input = input_sender;
             let channel = channel_sender;
-            let xor = xor_sender;
+            let transforms = transforms_sender;
+            let options = options_sender;
             let mut decrypted = Vec::new();
 
             for n in first..last {
@@ -73,32 +118,44 @@ pub fn execute<H: hash::Hash>(
                     }
                     if t == 0 {
                         // Allowed because of division; value will stay in bound
-                        // `n` is less than `last`
+                        // `n * thread_count` is at most the segment's number space
                         #[allow(clippy::cast_possible_truncation)]
-                        channel.progress((n * 100 / last) as u8);
+                        channel.progress(((offset + n * thread_count) * 100 / total) as u8);
                     }
                 }
 
-                let number = if let Some(xor) = xor.as_ref() {
-                    let mut number = format!("{}{:02$}", prefix, n, length).into_bytes();
-                    number.iter_mut().zip(xor.iter()).for_each(|(b, x)| *b ^= x);
-                    base64::encode(number)
+                let number = if transforms.is_empty() {
+                    segment.candidate(n)
+                } else if let Some(number) =
+                    transforms.apply(&format!("{}{}", prefix, segment.candidate(n)))
+                {
+                    number
                 } else {
-                    format!("{:01$}", n, length)
+                    continue;
                 };
-                let hash = H::digest(&salt, &number);
+                let hash = options.digest::<H>(&salt, &number, &salt_suffix);
                 if input.eytzinger_search(&hash).is_some() {
-                    count.fetch_sub(1, std::sync::atomic::Ordering::Release);
-                    let result = if xor.is_some() {
-                        number
+                    // Segments may overlap, such as with prefixes of one another, each hash is
+                    // only reported once
+                    if !found.lock().map_or(false, |mut found| found.insert(hash)) {
+                        continue;
+                    }
+
+                    let remaining = count.fetch_sub(1, std::sync::atomic::Ordering::Release);
+                    let pair = if transforms.is_empty() {
+                        results::Pair::new(hash.to_string(), format!("{}{}", &prefix, number))
                     } else {
-                        format!("{}{:02$}", &prefix, n, length)
+                        results::Pair::transformed(
+                            hash.to_string(),
+                            number,
+                            format!("{}{}", &prefix, segment.candidate(n)),
+                        )
                     };
-                    decrypted.push(results::Pair::new(hash.to_string(), result.clone()));
 
-                    channel.result(&format!("{:x}", hash), &result);
-                    if input.len() == 1 {
-                        return (n - first, decrypted);
+                    channel.result(&format!("{:x}", hash), pair.emitted(emit));
+                    decrypted.push(pair);
+                    if remaining == 1 {
+                        return (n - first + 1, decrypted);
                     }
                 }
             }
@@ -106,7 +163,7 @@ pub fn execute<H: hash::Hash>(
         }));
     }
 
-    let (hash_count, results) = threads
+    threads
         .into_iter()
         .map(|t| t.join().map_err(error::on_join))
         .fold(Ok((0, Vec::new())), |acc, curr| {
@@ -120,17 +177,198 @@ pub fn execute<H: hash::Hash>(
             } else {
                 acc
             }
-        })?;
+        })
+}
+
+/// Hashes every word of the wordlist, mangled by every rule, after the prefix of every segment
+/// and followed by its keyspace
+pub fn execute_wordlist<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    wordlist: &options::Wordlist,
+    channel: &impl channel::Channel,
+) -> Result<results::Summary, error::Error> {
+    let time = std::time::Instant::now();
+
+    let count = std::sync::atomic::AtomicUsize::new(options.input().len());
+    let found = std::sync::Mutex::new(std::collections::HashSet::new());
+    let input = options.input_as_eytzinger();
+    let mut words = files::Words::open(wordlist)?;
+
+    // Every word yields a candidate per rule, each followed by the keyspace of every segment
+    // Allowed because the batch size is far below usize::MAX
+    #[allow(clippy::cast_possible_truncation)]
+    let batch_size = std::cmp::max(
+        u64::from(options.threads()) * OPTIMAL_HASHES_PER_THREAD
+            / (std::cmp::max(options.rules().len(), 1) as u64)
+            / options.number_space(),
+        1,
+    ) as usize;
+
+    let mut hash_count = 0;
+    let mut results = Vec::with_capacity(input.len());
+
+    channel.progress(0);
+    while !channel.should_terminate() && count.load(std::sync::atomic::Ordering::Acquire) > 0 {
+        let batch = words.next_batch(batch_size)?;
+        if batch.is_empty() {
+            break;
+        }
+
+        let candidates = batch
+            .iter()
+            .flat_map(|word| options.candidates(word))
+            .collect::<Vec<_>>();
+        let (batch_count, batch_results) =
+            execute_words(options, &candidates, &input, &count, &found, channel)?;
+        hash_count += batch_count;
+        results.extend(batch_results);
+        channel.progress(words.progress());
+    }
 
     Ok(results::Summary {
         total_count: input.len(),
         duration: time.elapsed(),
         hash_count,
-        threads: u32::from(thread_count),
+        threads: u32::from(options.threads()),
         results,
     })
 }
 
+// The keyspaces following every word are split between the threads, so that a few words with a
+// large keyspace are hashed as fast as many words with a small one
+// Allowed because the `Sender` needs a sized type to point to
+#[allow(clippy::ptr_arg)]
+fn execute_words<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    words: &Vec<String>,
+    input: &Vec<H>,
+    count: &std::sync::atomic::AtomicUsize,
+    found: &std::sync::Mutex<std::collections::HashSet<H>>,
+    channel: &impl channel::Channel,
+) -> Result<(u64, Vec<results::Pair>), error::Error> {
+    let word_space = options.number_space();
+    let space = (words.len() as u64)
+        .checked_mul(word_space)
+        .ok_or_else(|| error!("Hybrid keyspace is too large"))?;
+    let thread_count = u64::from(options.threads());
+    let thread_space = (space + thread_count - 1) / thread_count;
+    let mut threads = Vec::<_>::with_capacity(options.threads() as usize);
+
+    for t in 0..thread_count {
+        let options_sender = Sender(options);
+        let words_sender = Sender(words);
+        let input_sender = Sender(input);
+        let count_sender = Sender(count);
+        let found_sender = Sender(found);
+        let channel_sender = Sender(channel);
+
+        let first = std::cmp::min(t * thread_space, space);
+        let last = std::cmp::min(first + thread_space, space);
+
+        threads.push(std::thread::spawn(move || {
+            let options = options_sender;
+            let words = words_sender;
+            let input = input_sender;
+            let count = count_sender;
+            let found = found_sender;
+            let channel = channel_sender;
+            let mut hash_count = 0;
+            let mut decrypted = Vec::new();
+
+            let mut unit = first;
+            while unit < last {
+                // Allowed because the quotient is an index of `words`
+                #[allow(clippy::cast_possible_truncation)]
+                let word = &words[(unit / word_space) as usize];
+                let mut offset = unit - unit % word_space;
+
+                for segment in options.segments() {
+                    let begin = std::cmp::max(unit, offset);
+                    let end = std::cmp::min(last, offset + segment.number_space());
+                    if begin < end {
+                        let segment = segment.with_word(word);
+                        for n in begin - offset..end - offset {
+                            if hash_count & (OPTIMAL_HASHES_PER_THREAD - 1)
+                                == OPTIMAL_HASHES_PER_THREAD - 1
+                                && (channel.should_terminate()
+                                    || count.load(std::sync::atomic::Ordering::Relaxed) == 0)
+                            {
+                                return (hash_count, decrypted);
+                            }
+
+                            hash_count += 1;
+                            let candidate = segment.candidate(n);
+                            let (hash, pair) = match lookup_word(
+                                &*options,
+                                segment.prefix(),
+                                &candidate,
+                                &input,
+                            ) {
+                                Some(pair) => pair,
+                                None => continue,
+                            };
+
+                            // Wordlists and rules may repeat words, each hash is only reported once
+                            if !found.lock().map_or(false, |mut found| found.insert(hash)) {
+                                continue;
+                            }
+
+                            let remaining =
+                                count.fetch_sub(1, std::sync::atomic::Ordering::Release);
+                            channel.result(&format!("{:x}", hash), pair.emitted(options.emit()));
+                            decrypted.push(pair);
+                            if remaining == 1 {
+                                return (hash_count, decrypted);
+                            }
+                        }
+                    }
+                    offset += segment.number_space();
+                }
+                unit = offset;
+            }
+            (hash_count, decrypted)
+        }));
+    }
+
+    threads
+        .into_iter()
+        .map(|t| t.join().map_err(error::on_join))
+        .fold(Ok((0, Vec::new())), |acc, curr| {
+            if let Ok(mut acc) = acc {
+                curr.map(|(count, results)| {
+                    (acc.0 + count, {
+                        acc.1.extend(results);
+                        acc.1
+                    })
+                })
+            } else {
+                acc
+            }
+        })
+}
+
+/// Hashes a value after a prefix the same way the kernels do, returning the hash and its pair if
+/// it is one of the targets. Values which cannot be transformed are skipped
+pub(super) fn lookup_word<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    prefix: &str,
+    value: &str,
+    input: &[H],
+) -> Option<(H, results::Pair)> {
+    use eytzinger::SliceExt;
+
+    let original = format!("{}{}", prefix, value);
+    let plain = options.transforms().apply(&original)?;
+
+    let hash = options.digest::<H>(options.salt_prefix(), &plain, options.salt_suffix());
+    input.eytzinger_search(&hash).map(|_| {
+        (
+            hash,
+            results::Pair::transformed(hash.to_string(), plain, original),
+        )
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::channel;
@@ -146,8 +384,45 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_overlapping_segments() {
+        use super::{execute, hash, options, results};
+
+        let mut expected = vec![
+            results::Pair::new(
+                String::from("e99a18c428cb38d5f260853678922e03"),
+                String::from("123"),
+            ),
+            results::Pair::new(
+                String::from("361ac235e1e08be7325a8ced898e6ff4"),
+                String::from("199"),
+            ),
+        ];
+        expected.sort();
+
+        // The hash of 123 is found by both segments, yet must only be counted once
+        let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+            expected
+                .iter()
+                .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                .collect(),
+            vec![3],
+        )
+        .device(options::Device::Cpu)
+        .prefixes(vec![String::from("12"), String::from("1")])
+        .salt(String::from("abc"))
+        .threads(1)
+        .build()
+        .unwrap();
+
+        let mut results = execute(&options, &Channel).unwrap().results;
+        results.sort();
+
+        assert_eq!(results, expected);
+    }
+
     mod sha256 {
-        use super::super::{execute, hash, options, results};
+        use super::super::{execute, execute_wordlist, hash, options, results};
         use super::Channel;
 
         #[test]
@@ -156,24 +431,24 @@ mod test {
             let prefix = String::from("1");
 
             let expected = vec![
-                results::Pair {
-                    hash: String::from(
+                results::Pair::new(
+                    String::from(
                         "6ca13d52ca70c883e0f0bb101e425a89e8624de51db2d2392593af6a84118090",
                     ),
-                    plain: prefix.clone() + "23",
-                },
-                results::Pair {
-                    hash: String::from(
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from(
                         "97193f3095a7fc166ae10276c083735b41a36abdaac6a33e62d15b7eafa22a67",
                     ),
-                    plain: prefix.clone() + "55",
-                },
-                results::Pair {
-                    hash: String::from(
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
                         "237dd1639d476eda038aff4b83283e3c657a9f38b50c2d7177336d344fe8992e",
                     ),
-                    plain: prefix.clone() + "99",
-                },
+                    prefix.clone() + "99",
+                ),
             ];
 
             let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
@@ -181,10 +456,10 @@ mod test {
                     .iter()
                     .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![3],
             )
             .device(options::Device::Cpu)
-            .prefix(prefix)
+            .prefixes(vec![prefix])
             .salt(salt)
             .threads(4)
             .build()
@@ -193,6 +468,157 @@ mod test {
             assert_eq!(execute(&options, &Channel).unwrap().results, expected);
         }
 
+        #[test]
+        fn test_chain_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "1a570c9a7f7152a553ce6e6bd80d3f8d49c78f2ac5d10b3f0802a4e135481af8",
+                    ),
+                    prefix.clone() + "99",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "75e69b9e35a4a926f77e6eee7cff6aa37d3b81f867456a8e86d5d6c38999a2b4",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "7eb2a56cb8c4ee606c0fb90a78bf1dcb5a7f1b4814e600935b9b31e338f05ab8",
+                    ),
+                    prefix.clone() + "23",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .chain(options::Chain::parse("sha256^2($p.$s)", options::Intermediate::Raw).unwrap())
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_mixed_chain_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "0b547aebaaf807c82ab22527cb7deaf5259c3c09993f5e94d73db77a54c241b6",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "2e1081a182cc2c99820c5ea6ec47a980286c67f73c89f75b29d07895c62f1eef",
+                    ),
+                    prefix.clone() + "99",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "b14dacf78ba8e9231ba50beee1cd8ead736fc1ef357f2ebd7e3d9c516890663e",
+                    ),
+                    prefix.clone() + "23",
+                ),
+            ];
+            expected.sort();
+
+            let chain =
+                options::Chain::parse("sha256(md5($p).$s)", options::Intermediate::Hex).unwrap();
+            let builder = |device: Option<options::Device>| {
+                options::DecryptBuilder::<hash::sha256::Hash>::new(
+                    expected
+                        .iter()
+                        .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                        .collect(),
+                    vec![3],
+                )
+                .chain(chain.clone())
+                .device(device)
+                .prefixes(vec![prefix.clone()])
+                .salt(salt.clone())
+                .threads(4)
+                .build()
+            };
+            assert!(builder(Some(options::Device::Gpu)).is_err());
+
+            let options = builder(None).unwrap();
+            assert_eq!(options.device(), options::Device::Cpu);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hmac_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "493d75d3869aad0fcbf2bf1962a152a0719445338d3f627ba93b697d8e3528e5",
+                    ),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "4ba3136c47ef1506462d6098ca41172f4fde878f4e061c330c6ac3eea3647250",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "af10149fd819f355be11b49a454d2c68d9ba6ff3d7a7b4bb17aa74544b391136",
+                    ),
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .hmac_key(String::from("key"))
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
         #[test]
         fn test_xor_decryption() {
             let salt = String::from("abc");
@@ -205,18 +631,21 @@ mod test {
                         "f3b90305e926c8d7ad0c4a1750532341875df1aeecde3c508bfbe4be1969180c",
                     ),
                     plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
                 },
                 results::Pair {
                     hash: String::from(
                         "836bfc1d576b5a04e1688cd4603f42a67dda7e31c2e7adb5142eb4c4e898a66d",
                     ),
                     plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
                 },
                 results::Pair {
                     hash: String::from(
                         "8823993be0da4a4f07aa33dd3ebfe1a33b36f01d5d11d64e93235119e8b3468f",
                     ),
                     plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
                 },
             ];
 
@@ -225,10 +654,10 @@ mod test {
                     .iter()
                     .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![3],
             )
             .device(options::Device::Cpu)
-            .prefix(prefix)
+            .prefixes(vec![prefix])
             .salt(salt)
             .threads(4)
             .xor(xor)
@@ -237,79 +666,2022 @@ mod test {
 
             assert_eq!(execute(&options, &Channel).unwrap().results, expected);
         }
-    }
+        #[test]
+        fn test_multiple_segments() {
+            let salt = String::from("abc");
 
-    mod md5 {
-        use super::super::{execute, hash, options, results};
-        use super::Channel;
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "6ca13d52ca70c883e0f0bb101e425a89e8624de51db2d2392593af6a84118090",
+                    ),
+                    String::from("123"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "74045e177bc8794af8dc5d9afd6aa9e5e72d531265f26a75bef19533c842f347",
+                    ),
+                    String::from("2047"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "1e429ebbb49a57113a6c7e62ceb9c30093c08642466963143782f04d84e913d8",
+                    ),
+                    String::from("1999"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3, 4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![String::from("1"), String::from("2")])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
 
         #[test]
-        fn test_decryption() {
+        fn test_mask_decryption() {
             let salt = String::from("abc");
             let prefix = String::from("1");
+            let mask = options::Mask::parse("?u-?d", &[]).unwrap();
 
-            let expected = vec![
-                results::Pair {
-                    hash: String::from("e99a18c428cb38d5f260853678922e03"),
-                    plain: prefix.clone() + "23",
-                },
-                results::Pair {
-                    hash: String::from("6b14d696623c7b26c275da041719ce53"),
-                    plain: prefix.clone() + "55",
-                },
-                results::Pair {
-                    hash: String::from("361ac235e1e08be7325a8ced898e6ff4"),
-                    plain: prefix.clone() + "99",
-                },
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "81a7788c6d485444cadedbb056c32cf951975889e50a8fa072ba3133d53badbb",
+                    ),
+                    String::from("1A-0"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "9124e621af8f5cc022c393e3065f38d3407d12339ad5773417fc9cdbef232b6e",
+                    ),
+                    String::from("1Q-5"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "3e4eba59921d0f88b0da0c07264e1f2a8bdb6f885fbba44f1cd0d53fb02ff1c2",
+                    ),
+                    String::from("1Z-9"),
+                ),
             ];
+            expected.sort();
 
-            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
                 expected
                     .iter()
-                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![],
             )
             .device(options::Device::Cpu)
-            .prefix(prefix)
+            .masks(vec![mask])
+            .prefixes(vec![prefix])
             .salt(salt)
             .threads(4)
             .build()
             .unwrap();
 
-            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
         }
 
         #[test]
-        fn test_xor_decryption() {
+        fn test_template_decryption() {
             let salt = String::from("abc");
-            let prefix = String::from("1");
-            let xor = vec![3, 4, 5, 6];
+            let template = options::Mask::template("#-##").unwrap();
 
-            let expected = vec![
-                results::Pair {
-                    hash: String::from("7900c0f65c087c03458293d7bb172ed1"),
-                    plain: String::from("MjY2"),
-                },
-                results::Pair {
-                    hash: String::from("7c1b8268077c6a9439fb82434dd5a5af"),
-                    plain: String::from("MjEw"),
-                },
-                results::Pair {
-                    hash: String::from("dd9eac6ed5ce1d8c5a645b4642ca1cd8"),
-                    plain: String::from("Mj08"),
-                },
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "b12068037a15d824815740c64008f0df8742b67fb15b8098df1e0be709fa3fa1",
+                    ),
+                    String::from("1-23"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "d583098f064a582bfd40b0476b2541f78a65d3c29dc2233e85417d95b8192f4e",
+                    ),
+                    String::from("5-55"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "a4f5a039dff7a965ce0394f600a9b0018e0b6016ef423e78dd52e6fb767b97dd",
+                    ),
+                    String::from("9-09"),
+                ),
             ];
+            expected.sort();
 
-            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
                 expected
                     .iter()
-                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Cpu)
+            .masks(vec![template])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_checksum_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("4");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "f3c6454ea69afb550037babe9ebd67ac1d6b49340e11350153e208f2dad722e7",
+                    ),
+                    String::from("4127"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "f7582bfc861b8cad1d50a941ae0efff9dc639e0b78180b4c70318074207deb4c",
+                    ),
+                    String::from("4564"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "31496a6dfce097063230a86dcbe81edf6c09bf6b251c11055e62297ad681a5b0",
+                    ),
+                    String::from("4994"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .checksum(options::Checksum::Luhn)
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 100);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_date_decryption() {
+            let salt = String::from("abc");
+            let dates = options::Dates::parse("YYMMDD", 2000, 2000).unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "1842f82ed906ceb1ce98175303d589c95c6bf69caab8a4bedecadddd6a8a787d",
+                    ),
+                    String::from("0001010"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "76779a902984629ca6881a461043686b6e389497bfda9e6aba8ab24467fcdebf",
+                    ),
+                    String::from("0002291"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "e3fff5374fe9be455764e221c6c6bfd055f0d533c765676cff989b23a297250e",
+                    ),
+                    String::from("0012315"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![7],
+            )
+            .dates(dates)
+            .device(options::Device::Cpu)
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 3660);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wordlist_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_cpu_sha256_wordlist.txt");
+            std::fs::write(
+                &path,
+                "alpha\nbeta\nbeta\ncorrect-horse-battery-staple-correct-horse-battery-staple-\n",
+            )
+            .unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "b29cb2661f955cbbf71a9fb0006c0ff15b85589a9a991bd88b152c4b1307f55e",
+                    ),
+                    String::from("xbeta"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "8872c8011e0d5c0ceeb5e8078c664ff5ae216bd98e10f1de3a20fdc710df1900",
+                    ),
+                    String::from("xcorrect-horse-battery-staple-correct-horse-battery-staple-"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_rule_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_cpu_sha256_rules.txt");
+            std::fs::write(&path, "alpha\nbeta\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "ba1c578edd06e8e9156a393cfac68cfa6a5da5032d3c003ffc93a143869e7bda",
+                    ),
+                    String::from("xBeta1"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "a2da3e75907d70995ea190d9bb1a62082716881bdc7c6351eff49822741d94c0",
+                    ),
+                    String::from("x4lph4"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .rules(vec![
+                options::Rule::parse("c $1").unwrap(),
+                options::Rule::parse("sa4").unwrap(),
+            ])
+            .salt(salt)
+            .threads(4)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hybrid_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_cpu_sha256_hybrid.txt");
+            std::fs::write(&path, "dep\nops\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "530ddf8db47600d1dc3055be5c6702695309f761862cce1363968704b38051c5",
+                    ),
+                    String::from("xdep07"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "5e83ff2f0615af9839ec714fb46a29b1cb444fe48603eb393ae04df9e7c1190a",
+                    ),
+                    String::from("xops42"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            assert_eq!(options.number_space(), 100);
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_append_salt_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "5a5f0a97ae7123f4216fdf9db93ebab172577b6c06becf25569d6f0eb903b5a0",
+                    ),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "9ea0aa9b58de399edceb6f3ae371b5e83b582e08d296bc08c8927924c97b99b4",
+                    ),
+                    String::from("1784"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Append)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wrap_salt_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from(
+                        "2ede1e066bb5db6dd56f23b8dc5bbf8dca9dc251d0c6533f3f11640c7d164887",
+                    ),
+                    plain: String::from("MjQ3NQ=="),
+                    original: Some(String::from("1023")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "3f54180fe34712dfeff4793c83ff8b3054c0c128a12e0dd82e006e379289aa42",
+                    ),
+                    plain: String::from("MjM9Mg=="),
+                    original: Some(String::from("1784")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Wrap)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+        #[test]
+        fn test_salt_group_decryption() {
+            let salts = vec![
+                (
+                    <hash::sha256::Hash as std::convert::From<&str>>::from(
+                        "828e0ef5b0a2af22879e8cf0eff47c3ebb10938cd97a87569a36d12bc61cac69",
+                    ),
+                    String::from("abc"),
+                ),
+                (
+                    <hash::sha256::Hash as std::convert::From<&str>>::from(
+                        "b98bfe32f45449e27dec687c7b8c7a1b5d333c3f82df4864dd5c887c1dc0ef56",
+                    ),
+                    String::from("xyz"),
+                ),
+            ];
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "828e0ef5b0a2af22879e8cf0eff47c3ebb10938cd97a87569a36d12bc61cac69",
+                    ),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "b98bfe32f45449e27dec687c7b8c7a1b5d333c3f82df4864dd5c887c1dc0ef56",
+                    ),
+                    String::from("1784"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "4c1769388c470b2d8873bb2cbd1b754ef7ddd457451fbf00a4cef4c86d749dbe",
+                    ),
+                    String::from("1500"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                std::iter::once(<hash::sha256::Hash as std::convert::From<&str>>::from(
+                    "4c1769388c470b2d8873bb2cbd1b754ef7ddd457451fbf00a4cef4c86d749dbe",
+                ))
+                .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![String::from("1")])
+            .salt(String::from("s"))
+            .salts(
+                salts
+                    .into_iter()
+                    .collect::<std::collections::HashMap<_, _>>(),
+            )
+            .threads(4)
+            .build()
+            .unwrap();
+            assert_eq!(options.salt_groups().len(), 3);
+
+            let mut results = crate::decrypt::execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_transform_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("ab");
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from(
+                        "1ae88c9e1075fcdcb72aaa9a400eb53527c6895d53db07637b69be4540dbac38",
+                    ),
+                    plain: String::from("323442412d2d"),
+                    original: Some(String::from("ab42")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "7f56765b82015b2b95f90ceceed9b2ea8307a4443d43da2e72b98b5a66650c99",
+                    ),
+                    plain: String::from("373042412d2d"),
+                    original: Some(String::from("ab07")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "3eebf59586ea4ac0496bdac1f793e0b14fb6b497fe7b6f38ab66d5d64a9e4969",
+                    ),
+                    plain: String::from("393942412d2d"),
+                    original: Some(String::from("ab99")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .transforms(vec![
+                options::Transform::Pad(6, b'-'),
+                options::Transform::Reverse,
+                options::Transform::Upper,
+                options::Transform::Hex,
+            ])
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+            assert_eq!(results, expected);
+        }
+    }
+
+    mod md5 {
+        use super::super::{execute, execute_wordlist, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(
+                    String::from("e99a18c428cb38d5f260853678922e03"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("6b14d696623c7b26c275da041719ce53"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("361ac235e1e08be7325a8ced898e6ff4"),
+                    prefix.clone() + "99",
+                ),
+            ];
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_chain_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("57f231b1ec41dc6641270cb09a56f897"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("d174532eaa3cde2ca157bef3f3fa84c2"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("d250a6d292362ef53edb0420df59e19f"),
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .chain(options::Chain::parse("md5(md5($s.$p))", options::Intermediate::Hex).unwrap())
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hmac_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("4cfb3f61bae66342315bb40f06a85cf1"),
+                    prefix.clone() + "99",
+                ),
+                results::Pair::new(
+                    String::from("a02cc11463743195f817be651ae82e2c"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("e33cf8cd1f571c0b6d900fad52ce9a7e"),
+                    prefix.clone() + "23",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .hmac_key(String::from("key"))
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from("7900c0f65c087c03458293d7bb172ed1"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("7c1b8268077c6a9439fb82434dd5a5af"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("dd9eac6ed5ce1d8c5a645b4642ca1cd8"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_multiple_segments() {
+            let salt = String::from("abc");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("e99a18c428cb38d5f260853678922e03"),
+                    String::from("123"),
+                ),
+                results::Pair::new(
+                    String::from("a3f39b0345ec46b53f8c010834e497ef"),
+                    String::from("2047"),
+                ),
+                results::Pair::new(
+                    String::from("0839aa18a5d54727af6109d0cee8a5e6"),
+                    String::from("1999"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3, 4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![String::from("1"), String::from("2")])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_mask_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let mask = options::Mask::parse("?u-?d", &[]).unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("eb3e8b5408e6724dd78c1607f12003bc"),
+                    String::from("1A-0"),
+                ),
+                results::Pair::new(
+                    String::from("6addd30cdefcfc27f4c4ca5cb87bdf31"),
+                    String::from("1Q-5"),
+                ),
+                results::Pair::new(
+                    String::from("fd05263334264f16133cfca8ed60e324"),
+                    String::from("1Z-9"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Cpu)
+            .masks(vec![mask])
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_template_decryption() {
+            let salt = String::from("abc");
+            let template = options::Mask::template("#-##").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("afacebad24c59287638e14cfd9b191b3"),
+                    String::from("1-23"),
+                ),
+                results::Pair::new(
+                    String::from("329e638ebc0ea6d170e9be726960392a"),
+                    String::from("5-55"),
+                ),
+                results::Pair::new(
+                    String::from("97ca4f09ad9eb763042c7b11529ea812"),
+                    String::from("9-09"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Cpu)
+            .masks(vec![template])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_checksum_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("4");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("d9bc4df9dbd29487bccfd9680772f336"),
+                    String::from("4127"),
+                ),
+                results::Pair::new(
+                    String::from("cdbe7576819234366c07750bc38ed93e"),
+                    String::from("4564"),
+                ),
+                results::Pair::new(
+                    String::from("c6fd370306a33e301706d83cb33cb7c1"),
+                    String::from("4994"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .checksum(options::Checksum::Luhn)
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 100);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_date_decryption() {
+            let salt = String::from("abc");
+            let dates = options::Dates::parse("YYMMDD", 2000, 2000).unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("01da062cfe30266e98c0d057987b80b3"),
+                    String::from("0001010"),
+                ),
+                results::Pair::new(
+                    String::from("52780d32918af70bd8e87bd3c749e06d"),
+                    String::from("0002291"),
+                ),
+                results::Pair::new(
+                    String::from("a0c8c2142083c91bb91e3738b5af3aa9"),
+                    String::from("0012315"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![7],
+            )
+            .dates(dates)
+            .device(options::Device::Cpu)
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 3660);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wordlist_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_cpu_md5_wordlist.txt");
+            std::fs::write(
+                &path,
+                "alpha\nbeta\nbeta\ncorrect-horse-battery-staple-correct-horse-battery-staple-\n",
+            )
+            .unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("c5780614ddccd4f32fdf868bda60c406"),
+                    String::from("xbeta"),
+                ),
+                results::Pair::new(
+                    String::from("afab7847a0681f9e7fada1e75b67e562"),
+                    String::from("xcorrect-horse-battery-staple-correct-horse-battery-staple-"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_rule_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_cpu_md5_rules.txt");
+            std::fs::write(&path, "alpha\nbeta\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("989a4b5755bf824a7a5d8780e8f85014"),
+                    String::from("xBeta1"),
+                ),
+                results::Pair::new(
+                    String::from("23a71e917eab67f3c29311235b4fb7c1"),
+                    String::from("x4lph4"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .rules(vec![
+                options::Rule::parse("c $1").unwrap(),
+                options::Rule::parse("sa4").unwrap(),
+            ])
+            .salt(salt)
+            .threads(4)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hybrid_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_cpu_md5_hybrid.txt");
+            std::fs::write(&path, "dep\nops\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("0f946039924090db72a292205ed4f147"),
+                    String::from("xdep07"),
+                ),
+                results::Pair::new(
+                    String::from("dc060c16908f00647b051949aba62de7"),
+                    String::from("xops42"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            assert_eq!(options.number_space(), 100);
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_append_salt_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("18831e8768c11cf4aba64348472462b4"),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from("3de95eae2ba181608a84d617253e3f95"),
+                    String::from("1784"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Append)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wrap_salt_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("5dcae8d249b444207ae7f4d22d8b7f0e"),
+                    plain: String::from("MjQ3NQ=="),
+                    original: Some(String::from("1023")),
+                },
+                results::Pair {
+                    hash: String::from("3a5573e40918b930d87d458b285e07a1"),
+                    plain: String::from("MjM9Mg=="),
+                    original: Some(String::from("1784")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Wrap)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+        #[test]
+        fn test_salt_group_decryption() {
+            let salts = vec![
+                (
+                    <hash::md5::Hash as std::convert::From<&str>>::from(
+                        "b436d3e7f8095de37817a30654a3d03e",
+                    ),
+                    String::from("abc"),
+                ),
+                (
+                    <hash::md5::Hash as std::convert::From<&str>>::from(
+                        "1491d4c280ca4ad2390df04840641348",
+                    ),
+                    String::from("xyz"),
+                ),
+            ];
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("b436d3e7f8095de37817a30654a3d03e"),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from("1491d4c280ca4ad2390df04840641348"),
+                    String::from("1784"),
+                ),
+                results::Pair::new(
+                    String::from("360a03c06795cf8722525ed259bfa6af"),
+                    String::from("1500"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                std::iter::once(<hash::md5::Hash as std::convert::From<&str>>::from(
+                    "360a03c06795cf8722525ed259bfa6af",
+                ))
+                .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![String::from("1")])
+            .salt(String::from("s"))
+            .salts(
+                salts
+                    .into_iter()
+                    .collect::<std::collections::HashMap<_, _>>(),
+            )
+            .threads(4)
+            .build()
+            .unwrap();
+            assert_eq!(options.salt_groups().len(), 3);
+
+            let mut results = crate::decrypt::execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_transform_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("ab");
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("8a86a08951e865cd5bc52be0158c00fc"),
+                    plain: String::from("323442412d2d"),
+                    original: Some(String::from("ab42")),
+                },
+                results::Pair {
+                    hash: String::from("4d2c4bf55a86de580cad3485928e8a97"),
+                    plain: String::from("373042412d2d"),
+                    original: Some(String::from("ab07")),
+                },
+                results::Pair {
+                    hash: String::from("8b11483c0136119fa4ea0b67242a3098"),
+                    plain: String::from("393942412d2d"),
+                    original: Some(String::from("ab99")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .transforms(vec![
+                options::Transform::Pad(6, b'-'),
+                options::Transform::Reverse,
+                options::Transform::Upper,
+                options::Transform::Hex,
+            ])
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+            assert_eq!(results, expected);
+        }
+    }
+
+    mod sha1 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(
+                    String::from("6367c48dd193d56ea7b0baad25b19455e529f5ee"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("acdd5803ea05b235c2b6dcb8f5cc968a1a125fb0"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("7602c363fa5cc0ae5f37830d02cd6bd9ca619d29"),
+                    prefix.clone() + "99",
+                ),
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha1::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha1::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from("3f39a2957f7be65e4a60373d6243d96d2f63896d"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("29ddc9fd8fc2ba5e1618f1bcced3fdd34e2fe748"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("b5a369dff80ab70af958d3b43e1c975be83e2f16"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha1::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha1::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+    }
+
+    mod sha384 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(String::from("a31d79891919cad24f3264479d76884f581bee32e86778373db3a124de975dd86a40fc7f399b331133b281ab4b11a6ca"), prefix.clone() + "23"),
+                results::Pair::new(String::from("7b6e4db3dfde853a2da3dc339ce45900bc6af5d34cfce60589ecb6c412e874d4494fc956f80de4ff8fd3d99d040fde13"), prefix.clone() + "55"),
+                results::Pair::new(String::from("dcf238486a0435bae7283eecaa53b9f3787b1c8942c489af3800368e277e72d4e0d2098fcc19c3ee21c718152d7ab160"), prefix.clone() + "99"),
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha384::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha384::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from("949a2d76da9f76b9eaacbffd1d1cb80868ca13241d5e3c6f15e9012b6cb045d4f661339a459b15c8c6009e0bd32c8d46"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("f5b13216a90cf53017f048cb85eb2094196549ffe52e5d900efd664cf0a9c571fa1130591b6d4e9541bdde55ab040887"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("c7820a98ba2fb1007fb0f249e6755c4bbb6ab2faeb791ba9912bb700fafd0f9e288c5ca1759dca45ccd8ce28a71ab403"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha384::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha384::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+    }
+
+    mod sha512 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(String::from("c70b5dd9ebfb6f51d09d4132b7170c9d20750a7852f00680f65658f0310e810056e6763c34c9a00b0e940076f54495c169fc2302cceb312039271c43469507dc"), prefix.clone() + "23"),
+                results::Pair::new(String::from("b7e24dffbecf2f4562d1bca6e961c1e4a60cca6943566eb8c9d0a90edf2cc43a2d32253ecdc0bdfefac2fdc9f9e4966fb86eb0c9629d1790292bbbbf15d75cde"), prefix.clone() + "55"),
+                results::Pair::new(String::from("c92bcf07f5d16107e644e7874e8495d160f2672067d12f2542074fb0a601615e46b086966d22b7941e50782f91d519e6948bc15b8d988b4c748a929c2fd01ddd"), prefix.clone() + "99"),
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha512::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha512::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from("37df6a25a11f71a88dbfdeb60e00c790df5b373c27872f179634f878f557da6c55819ada7cf9ae47e1a009ca16b8664da7d08ddc8f0fb2e01dd653c8f137da5c"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("a6181b142561943f8c57be00bca1169a61628da96e939385efa1a98ccaac93240181636fd2a1512b27e39276f790e8b3b4d3511d646197cd74d0f221bce2a75f"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("b680dca00d452f682f5add86982a8c71069b8eca1f15047bc960ceb3aa9f8cd00855817979346e3b17e734db9c311581f516d4d8dc4d6d9b744935b1cf32a468"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha512::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha512::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+    }
+
+    mod sha3_256 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "f58fa3df820114f56e1544354379820cff464c9c41cb3ca0ad0b0843c9bb67ee",
+                    ),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "638e40bc4e05f269b6753087298e382d4782701ae5bd8d173bdac896567bca9e",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "9d14d509bbe6cc14c4b754351b3bb1a0ebcda9b9f2cb17783af8f4839b0d8522",
+                    ),
+                    prefix.clone() + "99",
+                ),
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha3_256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha3_256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from(
+                        "0f7982ab5a2e51b037defed8f705b85c657ee1909989b23644206f6cdf82583f",
+                    ),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "00f2cce9e89b1418834e45c0382e097ba5033d16935832d894fcc7cc07cf6e6c",
+                    ),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "48df3ae48b52608db127ce443bf508811a363948510e58729612031f3dbe3bdf",
+                    ),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::sha3_256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha3_256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+    }
+
+    mod keccak256 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "719accc61a9cc126830e5906f9d672d06eab6f8597287095a2c55a8b775e7016",
+                    ),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "3834de7b0dc876a32135a0bc1bc29ea85b7ad2640a2251746eb3337fd0f535e3",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "0ef89872e53006a6b4cc06f16fa55987db74ea3e58b61dbf44901dff2ddcc73e",
+                    ),
+                    prefix.clone() + "99",
+                ),
+            ];
+
+            let options = options::DecryptBuilder::<hash::keccak256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::keccak256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from(
+                        "0a752383275e05d99eed41f3657d3520b1c6bcbf9e945960e453e2a899c114d3",
+                    ),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "a93011ab5b901ea6ee958104e270c042dd6cf9104720c4cd9ddaa9a8926941d9",
+                    ),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "cb6f76b313095d27aac946be05e1673dd0388d301e150c1e720fd1071d179466",
+                    ),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::keccak256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::keccak256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+    }
+
+    mod blake2b {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(String::from("585f3b691b374d85d6883348aaad9d63b4cb6b1c9c01aa1ccd2fcb880b27d2e1023c71be0213f161f3caec468178f9266ce06c0517491feb0f181cb4a0c9e67a"), prefix.clone() + "23"),
+                results::Pair::new(String::from("d48b198df177a4bad092b7fa34d855372b6501d0b7f5cee37b982f0776c67f083ea165cd10789c95820193fb7e9404c2989bc096e54b20215b93580ae5bc56d7"), prefix.clone() + "55"),
+                results::Pair::new(String::from("ee19c9a107c82889279163d797bf148dc6d72cc3be0352e31164a2704be71423aa80a3ec220785e67ecdd394bbec2992bc1916b64dbd541f0b27fcca5357f9fd"), prefix.clone() + "99"),
+            ];
+
+            let options = options::DecryptBuilder::<hash::blake2b::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::blake2b::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from("4fb1ce3dd0323db1add53da001661202e6bcecf4288bf01d7411d76a264fcb54a31880a3cfa09d238e1d28791d14a7e75a7073fd7dfda1e7e7426a34aba84755"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("4cb0bedc04f1efbfa004efb294886f1c9c86b1bd5500307c67afe5aea1b5125233a886f19eebce8fbb9ec1bf27fdcd293b3d2dd20eeb8186f4e91fa02a01f2dc"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("d3fe84c0fdb255daaa077d71c2d463bba5a31303599559cb01c8011c24db50ec84b8f7f2bd19853dbb983cba8b24d8dda7d57d626dbf7735e915c7f2bbca24eb"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::blake2b::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::blake2b::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+    }
+
+    mod blake3 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "00307ced6a8b278d5e3a9f77b138d0e9d2209717c9d45b205f427a73565cc5fb",
+                    ),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "1be7c29fdf7c7a240fb9149ac8904df10d070749a97b17f0a52b5e98fc7cadab",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "a82663f10e27b8e6853c7881bfbf57d6385921c8e8b00a027cb57c6d929798e8",
+                    ),
+                    prefix.clone() + "99",
+                ),
+            ];
+
+            let options = options::DecryptBuilder::<hash::blake3::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::blake3::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from(
+                        "1842c92cf93c336fefff46659a252f5d378cfba3c5c8e1cf7672321037499a94",
+                    ),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "b55f1677dbfc1e26549599c24a425a551d10a6d7e8b4964ce6da8447882c965b",
+                    ),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "3f9a29aa7bd9d85019a019f812aa663384f998d7f0584876dcd57204782ddb95",
+                    ),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::blake3::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::blake3::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+    }
+
+    mod md4 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(
+                    String::from("0ceb1fd260c35bd50005341532748de6"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("1025cb0d7173de66e7f7d1d04116513c"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("30ff4087cdfb981bfbb450eb4324c1c2"),
+                    prefix.clone() + "99",
+                ),
+            ];
+
+            let options = options::DecryptBuilder::<hash::md4::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md4::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_utf16_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let expected = vec![
+                results::Pair::new(
+                    String::from("f9e37e83b83c47a93c2f09f66408631b"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("fcab0630394d2ed489456061eac69fe1"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("e4c3752bbb42f24dc3c818df94df958a"),
+                    prefix.clone() + "99",
+                ),
+            ];
+
+            let options = options::DecryptBuilder::<hash::md4::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md4::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Cpu)
+            .encoding(options::Encoding::Utf16Le)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .build()
+            .unwrap();
+
+            assert_eq!(execute(&options, &Channel).unwrap().results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let expected = vec![
+                results::Pair {
+                    hash: String::from("2cebee61f26034e66f3a5fbaedf39065"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("ad9c78aafc8bdda4db218ff0890d3452"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("bfceb3751f8c64368fe69145ddbfcbf7"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+
+            let options = options::DecryptBuilder::<hash::md4::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md4::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![3],
             )
             .device(options::Device::Cpu)
-            .prefix(prefix)
+            .prefixes(vec![prefix])
             .salt(salt)
             .threads(4)
             .xor(xor)
@@ -1,7 +1,9 @@
+use super::cpu;
 use super::opencl;
 
 use crate::channel;
 use crate::error;
+use crate::files;
 use crate::hash;
 use crate::options;
 use crate::results;
@@ -21,38 +23,28 @@ fn compute_results<'a, H: hash::Hash>(
         .map_err(|err| error!(err; "OpenCL: Failed to read output buffer"))?;
 
     let mut results = Vec::with_capacity(out_buffer.len());
+    let prefix = environment.segment().prefix();
 
     for (i, plain) in output.iter().enumerate() {
         if plain.is_valid() {
-            let mut output = format!("{}{}", &options.prefix(), plain.printable(environment));
-            if let Some(xor) = options.xor().as_ref() {
-                unsafe {
-                    output
-                        .as_bytes_mut()
-                        .iter_mut()
-                        .zip(xor.iter())
-                        .for_each(|(b, x)| *b ^= x);
-                }
-                output = base64::encode(output);
+            let original = format!("{}{}", prefix, plain.printable(environment));
+            if let Some(output) = options.transforms().apply(&original) {
+                results.push(results::Pair::transformed(
+                    input[i].to_string(),
+                    output,
+                    original,
+                ));
             }
-            results.push(results::Pair::new(input[i].to_string(), output));
         }
     }
 
     // The kernel will output zeros if nothing is found
     // We should hash this in the CPU to make sure it doesn't match anything
     if results.len() < input.len() {
-        let salted_prefix = format!("{}{}", &options.salt(), &options.prefix());
-
         for i in 0..environment.cpu_iterations() {
-            use eytzinger::SliceExt;
-
             let zeros = opencl::Output::new(0, i).printable(environment);
-            let hash = H::digest(&salted_prefix, &zeros);
-
-            if input.eytzinger_search(&hash).is_some() {
-                let result = format!("{}{}", &options.prefix(), &zeros);
-                results.push(results::Pair::new(hash.to_string(), result));
+            if let Some((_, pair)) = cpu::lookup_word(options, prefix, &zeros, input) {
+                results.push(pair);
             }
 
             if results.len() == input.len() {
@@ -64,40 +56,109 @@ fn compute_results<'a, H: hash::Hash>(
     Ok(results)
 }
 
-pub fn execute<H: hash::Hash>(
-    options: &options::Decrypt<H>,
-    channel: &impl channel::Channel,
-) -> Result<results::Summary, error::Error> {
-    let time = std::time::Instant::now();
+// Number of words of the same length hashed by a single kernel run
+const WORDLIST_BATCH: u32 = 1 << 20;
+
+// Largest salted value the kernels hash, as it must fit in a single block with its padding and
+// its length, which takes an eighth of the block
+fn max_kernel_length<H: hash::Hash>() -> usize {
+    let block_length = opencl::value_length::<H>();
+    block_length - 1 - block_length / 8
+}
 
+fn check_input_count<H: hash::Hash>(options: &options::Decrypt<H>) -> Result<(), error::Error> {
     if (options.input().len() as u64) >= (i32::max_value() as u64) {
         bail!("Input count too large. GPU kernel defines are fixed at i32 (2,147,483,647)");
     }
+    Ok(())
+}
 
-    let input = options.input_as_eytzinger();
-
-    let environment = opencl::setup_for(options)?;
-    let program = environment.make_program()?;
-
-    let in_buffer = if environment.memory() < H::bytes() * input.len() as u64 {
-        unsafe { ocl::Buffer::builder().use_host_slice(&input) }
+fn input_buffer<H: hash::Hash>(
+    environment: &opencl::Environment<'_, H>,
+    input: &[H],
+) -> Result<ocl::Buffer<H>, error::Error> {
+    if environment.memory() < H::bytes() * input.len() as u64 {
+        unsafe { ocl::Buffer::builder().use_host_slice(input) }
     } else {
-        ocl::Buffer::builder().copy_host_slice(&input)
+        ocl::Buffer::builder().copy_host_slice(input)
     }
     .flags(ocl::MemFlags::READ_ONLY)
-    .len(options.input().len())
+    .len(input.len())
     .queue(environment.queue().clone())
     .build()
-    .map_err(|err| error!(err; "OpenCL: Failed to create input buffer"))?;
+    .map_err(|err| error!(err; "OpenCL: Failed to create input buffer"))
+}
 
-    let out_buffer = ocl::Buffer::builder()
+fn output_buffer<H: hash::Hash>(
+    environment: &opencl::Environment<'_, H>,
+    input: &[H],
+) -> Result<ocl::Buffer<opencl::Output>, error::Error> {
+    ocl::Buffer::builder()
         .flags(ocl::MemFlags::WRITE_ONLY)
-        .len(options.input().len())
+        .len(input.len())
         .queue(environment.queue().clone())
         .build()
-        .map_err(|err| error!(err; "OpenCL: Failed to create output buffer"))?;
+        .map_err(|err| error!(err; "OpenCL: Failed to create output buffer"))
+}
+
+pub fn execute<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    channel: &impl channel::Channel,
+) -> Result<results::Summary, error::Error> {
+    let time = std::time::Instant::now();
+
+    check_input_count(options)?;
+
+    let input = options.input_as_eytzinger();
+
+    let mut hash_count = 0;
+    let mut threads = 0;
+    let mut results = Vec::<results::Pair>::with_capacity(input.len());
+    let configuration = opencl::Configuration::new()?;
 
     channel.progress(0);
+    for segment in options.segments() {
+        if channel.should_terminate() || results.len() == input.len() {
+            break;
+        }
+
+        let environment = opencl::setup_for(options, segment, &configuration)?;
+        let segment_results =
+            execute_segment(&environment, &input, Some(hash_count), options, channel)?;
+
+        for result in segment_results {
+            if !results.iter().any(|r| r.hash == result.hash) {
+                channel.result(&result.hash, result.emitted(options.emit()));
+                results.push(result);
+            }
+        }
+
+        hash_count += segment.number_space();
+        threads = std::cmp::max(threads, environment.range());
+    }
+
+    Ok(results::Summary {
+        total_count: input.len(),
+        duration: time.elapsed(),
+        hash_count,
+        threads,
+        results,
+    })
+}
+
+// The progress is only reported given the offset of the segment in the number space
+fn execute_segment<H: hash::Hash>(
+    environment: &opencl::Environment<'_, H>,
+    input: &[H],
+    offset: Option<u64>,
+    options: &options::Decrypt<H>,
+    channel: &impl channel::Channel,
+) -> Result<Vec<results::Pair>, error::Error> {
+    let program = environment.make_program()?;
+    let in_buffer = input_buffer(environment, input)?;
+    let out_buffer = output_buffer(environment, input)?;
+
+    let segment_space = environment.segment().number_space();
     for i in 0..environment.cpu_iterations() {
         let kernel = ocl::Kernel::builder()
             .program(&program)
@@ -122,9 +183,13 @@ pub fn execute<H: hash::Hash>(
             if channel.should_terminate() {
                 break;
             }
-            // Allowed because it will always be <= 100
-            #[allow(clippy::cast_possible_truncation)]
-            channel.progress((i * 100 / environment.cpu_iterations()) as u8);
+            if let Some(offset) = offset {
+                let done =
+                    offset + segment_space * u64::from(i) / u64::from(environment.cpu_iterations());
+                // Allowed because it will always be <= 100
+                #[allow(clippy::cast_possible_truncation)]
+                channel.progress((done * 100 / options.number_space()) as u8);
+            }
             environment
                 .queue()
                 .finish()
@@ -137,21 +202,321 @@ pub fn execute<H: hash::Hash>(
         .finish()
         .map_err(|err| error!(err; "OpenCL: Failed to wait for queue to finish"))?;
 
-    let results = compute_results(&environment, &input, &out_buffer, options)?;
+    compute_results(environment, input, &out_buffer, options)
+}
+
+// A program built for a segment and a word length, along with its buffers
+struct WordKernel<'a, H: hash::Hash> {
+    environment: opencl::Environment<'a, H>,
+    program: ocl::Program,
+    in_buffer: ocl::Buffer<H>,
+    out_buffer: ocl::Buffer<opencl::Output>,
+}
+
+/// Hashes every word of the wordlist, mangled by every rule, after the prefix of every segment
+/// and followed by its keyspace
+///
+/// Without keyspace, words are batched by length, as the kernels are built for a fixed length.
+/// Otherwise, a kernel is built for every word, injected after the prefix. Values too long to be
+/// hashed by the kernels are hashed on the CPU instead
+pub fn execute_wordlist<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    wordlist: &options::Wordlist,
+    channel: &impl channel::Channel,
+) -> Result<results::Summary, error::Error> {
+    let time = std::time::Instant::now();
+
+    check_input_count(options)?;
+
+    let input = options.input_as_eytzinger();
+    let mut words = files::Words::open(wordlist)?;
+    let configuration = opencl::Configuration::new()?;
+    let mut kernels = std::collections::HashMap::new();
+    let mut batches = std::collections::HashMap::<usize, Vec<u8>>::new();
+    let word_only = options
+        .segments()
+        .iter()
+        .filter(|segment| is_word_only(segment))
+        .collect::<Vec<_>>();
+
+    let mut hash_count = 0;
+    let mut results = Vec::<results::Pair>::with_capacity(input.len());
 
-    for result in &results {
-        channel.result(&result.hash, &result.plain);
+    channel.progress(0);
+    while !channel.should_terminate() && results.len() < input.len() {
+        let batch = words.next_batch(WORDLIST_BATCH as usize)?;
+        if batch.is_empty() {
+            for (length, batch) in std::mem::take(&mut batches) {
+                hash_count += execute_words(
+                    &mut kernels,
+                    length,
+                    &batch,
+                    &input,
+                    &mut results,
+                    options,
+                    &configuration,
+                    channel,
+                )?;
+            }
+            break;
+        }
+
+        for word in batch.iter().flat_map(|word| options.candidates(word)) {
+            hash_count += execute_hybrid(
+                &word,
+                &input,
+                &mut results,
+                options,
+                &configuration,
+                channel,
+            )?;
+            if word_only.is_empty() {
+                continue;
+            }
+
+            let length = word.len();
+            if !is_encodable(options, &word)
+                || !word_only
+                    .iter()
+                    .all(|segment| fits_kernel(options, segment, length))
+            {
+                for segment in &word_only {
+                    hash_count += 1;
+                    if let Some((_, pair)) =
+                        cpu::lookup_word(options, segment.prefix(), &word, &input)
+                    {
+                        report(&mut results, pair, options.emit(), channel);
+                    }
+                }
+                continue;
+            }
+
+            let batch = batches.entry(length).or_default();
+            batch.extend_from_slice(word.as_bytes());
+            if batch.len() / length >= WORDLIST_BATCH as usize {
+                let batch = batches.remove(&length).unwrap_or_default();
+                hash_count += execute_words(
+                    &mut kernels,
+                    length,
+                    &batch,
+                    &input,
+                    &mut results,
+                    options,
+                    &configuration,
+                    channel,
+                )?;
+            }
+        }
+        channel.progress(words.progress());
     }
 
     Ok(results::Summary {
         total_count: input.len(),
         duration: time.elapsed(),
-        hash_count: options.number_space(),
-        threads: environment.range(),
+        hash_count,
+        threads: WORDLIST_BATCH,
         results,
     })
 }
 
+// If the segment has no keyspace, its words being hashed in batches
+fn is_word_only(segment: &options::Segment) -> bool {
+    segment.length() == 0
+}
+
+// If values of `length` can be hashed by the kernels after the prefix of the segment
+fn fits_kernel<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    segment: &options::Segment,
+    length: usize,
+) -> bool {
+    let plain_length = segment.prefix().len() + length;
+    let transforms = options.transforms();
+    let salt_length = options.salt_prefix().len() + options.salt_suffix().len();
+    let width = opencl::char_width(options.encoding());
+    // Values are transformed in the buffer of the kernels, after the salt
+    match (
+        transforms.length(plain_length),
+        transforms.max_length(plain_length),
+    ) {
+        (Some(hashed_length), Some(max_length)) => {
+            length > 0
+                && is_encodable(options, segment.prefix())
+                && (salt_length + hashed_length) * width <= max_kernel_length::<H>()
+                && (options.salt_prefix().len() + max_length) * width <= opencl::value_length::<H>()
+        }
+        _ => false,
+    }
+}
+
+// The kernels only widen single bytes into UTF-16LE characters, so the value and the salt must
+// be ASCII
+fn is_encodable<H: hash::Hash>(options: &options::Decrypt<H>, value: &str) -> bool {
+    options.encoding() == options::Encoding::Utf8 || (value.is_ascii() && options.salt().is_ascii())
+}
+
+// Enumerates the keyspace of every segment after the word, returning the hash count
+fn execute_hybrid<H: hash::Hash>(
+    word: &str,
+    input: &[H],
+    results: &mut Vec<results::Pair>,
+    options: &options::Decrypt<H>,
+    configuration: &opencl::Configuration,
+    channel: &impl channel::Channel,
+) -> Result<u64, error::Error> {
+    let mut hash_count = 0;
+
+    for segment in options.segments() {
+        if is_word_only(segment) {
+            continue;
+        }
+        if channel.should_terminate() || results.len() == input.len() {
+            break;
+        }
+
+        let segment = segment.with_word(word);
+        if fits_kernel(options, &segment, usize::from(segment.length())) {
+            let environment = opencl::setup_for(options, &segment, configuration)?;
+            for result in execute_segment(&environment, input, None, options, channel)? {
+                report(results, result, options.emit(), channel);
+            }
+        } else {
+            for n in 0..segment.number_space() {
+                if let Some((_, pair)) =
+                    cpu::lookup_word(options, segment.prefix(), &segment.candidate(n), input)
+                {
+                    report(results, pair, options.emit(), channel);
+                }
+            }
+        }
+        hash_count += segment.number_space();
+    }
+
+    Ok(hash_count)
+}
+
+fn report(
+    results: &mut Vec<results::Pair>,
+    pair: results::Pair,
+    emit: options::Emit,
+    channel: &impl channel::Channel,
+) {
+    if !results.iter().any(|r| r.hash == pair.hash) {
+        channel.result(&pair.hash, pair.emitted(emit));
+        results.push(pair);
+    }
+}
+
+// Hashes a batch of words of the same length after the prefix of every segment without keyspace,
+// returning the hash count
+// Allowed because the state of the whole run is shared by the batches of every length
+#[allow(clippy::too_many_arguments)]
+fn execute_words<'a, H: hash::Hash>(
+    kernels: &mut std::collections::HashMap<(usize, usize), WordKernel<'a, H>>,
+    length: usize,
+    batch: &[u8],
+    input: &[H],
+    results: &mut Vec<results::Pair>,
+    options: &'a options::Decrypt<H>,
+    configuration: &'a opencl::Configuration,
+    channel: &impl channel::Channel,
+) -> Result<u64, error::Error> {
+    let count = batch.len() / length;
+    let mut hash_count = 0;
+
+    for (index, segment) in options.segments().iter().enumerate() {
+        if !is_word_only(segment) {
+            continue;
+        }
+
+        let kernel = match kernels.entry((index, length)) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                // Allowed because the length was checked to fit the kernel
+                #[allow(clippy::cast_possible_truncation)]
+                let environment =
+                    opencl::setup_for_words(options, segment, length as u8, configuration)?;
+                let program = environment.make_program()?;
+                let in_buffer = input_buffer(&environment, input)?;
+                let out_buffer = output_buffer(&environment, input)?;
+                entry.insert(WordKernel {
+                    environment,
+                    program,
+                    in_buffer,
+                    out_buffer,
+                })
+            }
+        };
+        let queue = kernel.environment.queue();
+
+        let words_buffer = ocl::Buffer::builder()
+            .flags(ocl::MemFlags::READ_ONLY)
+            .len(batch.len())
+            .copy_host_slice(batch)
+            .queue(queue.clone())
+            .build()
+            .map_err(|err| error!(err; "OpenCL: Failed to create words buffer"))?;
+
+        kernel
+            .out_buffer
+            .cmd()
+            .fill(opencl::Output::default(), None)
+            .enq()
+            .map_err(|err| error!(err; "OpenCL: Failed to clear output buffer"))?;
+
+        let crack = ocl::Kernel::builder()
+            .program(&kernel.program)
+            .name("crack")
+            .queue(queue.clone())
+            .global_work_size(count)
+            .arg(&kernel.in_buffer)
+            .arg(&kernel.out_buffer)
+            .arg(&words_buffer)
+            .arg(0_u32)
+            .build()
+            .map_err(|err| error!(err; "OpenCL: Failed to build kernel"))?;
+
+        unsafe {
+            crack
+                .enq()
+                .map_err(|err| error!(err; "OpenCL: Failed to enqueue kernel"))?;
+        }
+        queue
+            .finish()
+            .map_err(|err| error!(err; "OpenCL: Failed to wait for queue to finish"))?;
+
+        let mut output = vec![opencl::Output::default(); kernel.out_buffer.len()];
+        kernel
+            .out_buffer
+            .read(&mut output)
+            .enq()
+            .map_err(|err| error!(err; "OpenCL: Failed to read output buffer"))?;
+
+        let word = |index: usize| {
+            std::str::from_utf8(&batch[index * length..(index + 1) * length]).unwrap_or_default()
+        };
+        for (i, plain) in output.iter().enumerate() {
+            if plain.is_valid() {
+                if let Some((hash, pair)) =
+                    cpu::lookup_word(options, segment.prefix(), word(plain.word()), input)
+                {
+                    debug_assert_eq!(hash, input[i]);
+                    report(results, pair, options.emit(), channel);
+                }
+            }
+        }
+
+        // The kernel will output zeros if nothing is found, so the first word is checked here
+        if let Some((_, pair)) = cpu::lookup_word(options, segment.prefix(), word(0), input) {
+            report(results, pair, options.emit(), channel);
+        }
+
+        hash_count += count as u64;
+    }
+
+    Ok(hash_count)
+}
+
 #[cfg(all(test, not(gpu_tests_disabled)))]
 mod test {
     use super::channel;
@@ -168,7 +533,7 @@ mod test {
     }
 
     mod sha256 {
-        use super::super::{execute, hash, options, results};
+        use super::super::{execute, execute_wordlist, hash, options, results};
         use super::Channel;
 
         #[test]
@@ -177,24 +542,117 @@ mod test {
             let prefix = String::from("1");
 
             let mut expected = vec![
-                results::Pair {
-                    hash: String::from(
+                results::Pair::new(
+                    String::from(
                         "6ca13d52ca70c883e0f0bb101e425a89e8624de51db2d2392593af6a84118090",
                     ),
-                    plain: prefix.clone() + "23",
-                },
-                results::Pair {
-                    hash: String::from(
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from(
                         "97193f3095a7fc166ae10276c083735b41a36abdaac6a33e62d15b7eafa22a67",
                     ),
-                    plain: prefix.clone() + "55",
-                },
-                results::Pair {
-                    hash: String::from(
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
                         "237dd1639d476eda038aff4b83283e3c657a9f38b50c2d7177336d344fe8992e",
                     ),
-                    plain: prefix.clone() + "99",
-                },
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_chain_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "1a570c9a7f7152a553ce6e6bd80d3f8d49c78f2ac5d10b3f0802a4e135481af8",
+                    ),
+                    prefix.clone() + "99",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "75e69b9e35a4a926f77e6eee7cff6aa37d3b81f867456a8e86d5d6c38999a2b4",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "7eb2a56cb8c4ee606c0fb90a78bf1dcb5a7f1b4814e600935b9b31e338f05ab8",
+                    ),
+                    prefix.clone() + "23",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .chain(options::Chain::parse("sha256^2($p.$s)", options::Intermediate::Raw).unwrap())
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hmac_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "493d75d3869aad0fcbf2bf1962a152a0719445338d3f627ba93b697d8e3528e5",
+                    ),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "4ba3136c47ef1506462d6098ca41172f4fde878f4e061c330c6ac3eea3647250",
+                    ),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from(
+                        "af10149fd819f355be11b49a454d2c68d9ba6ff3d7a7b4bb17aa74544b391136",
+                    ),
+                    prefix.clone() + "99",
+                ),
             ];
             expected.sort();
 
@@ -203,10 +661,11 @@ mod test {
                     .iter()
                     .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![3],
             )
             .device(options::Device::Gpu)
-            .prefix(prefix)
+            .hmac_key(String::from("key"))
+            .prefixes(vec![prefix])
             .salt(salt)
             .build()
             .unwrap();
@@ -229,18 +688,21 @@ mod test {
                         "f3b90305e926c8d7ad0c4a1750532341875df1aeecde3c508bfbe4be1969180c",
                     ),
                     plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
                 },
                 results::Pair {
                     hash: String::from(
                         "836bfc1d576b5a04e1688cd4603f42a67dda7e31c2e7adb5142eb4c4e898a66d",
                     ),
                     plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
                 },
                 results::Pair {
                     hash: String::from(
                         "8823993be0da4a4f07aa33dd3ebfe1a33b36f01d5d11d64e93235119e8b3468f",
                     ),
                     plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
                 },
             ];
             expected.sort();
@@ -250,10 +712,10 @@ mod test {
                     .iter()
                     .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![3],
             )
             .device(options::Device::Gpu)
-            .prefix(prefix)
+            .prefixes(vec![prefix])
             .salt(salt)
             .xor(xor)
             .build()
@@ -264,42 +726,45 @@ mod test {
 
             assert_eq!(results, expected);
         }
-    }
-
-    mod md5 {
-        use super::super::{execute, hash, options, results};
-        use super::Channel;
 
         #[test]
-        fn test_decryption() {
+        fn test_mask_decryption() {
             let salt = String::from("abc");
             let prefix = String::from("1");
+            let mask = options::Mask::parse("?u-?d", &[]).unwrap();
 
             let mut expected = vec![
-                results::Pair {
-                    hash: String::from("e99a18c428cb38d5f260853678922e03"),
-                    plain: prefix.clone() + "23",
-                },
-                results::Pair {
-                    hash: String::from("6b14d696623c7b26c275da041719ce53"),
-                    plain: prefix.clone() + "55",
-                },
-                results::Pair {
-                    hash: String::from("361ac235e1e08be7325a8ced898e6ff4"),
-                    plain: prefix.clone() + "99",
-                },
+                results::Pair::new(
+                    String::from(
+                        "81a7788c6d485444cadedbb056c32cf951975889e50a8fa072ba3133d53badbb",
+                    ),
+                    String::from("1A-0"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "9124e621af8f5cc022c393e3065f38d3407d12339ad5773417fc9cdbef232b6e",
+                    ),
+                    String::from("1Q-5"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "3e4eba59921d0f88b0da0c07264e1f2a8bdb6f885fbba44f1cd0d53fb02ff1c2",
+                    ),
+                    String::from("1Z-9"),
+                ),
             ];
             expected.sort();
 
-            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
                 expected
                     .iter()
-                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![],
             )
             .device(options::Device::Gpu)
-            .prefix(prefix)
+            .masks(vec![mask])
+            .prefixes(vec![prefix])
             .salt(salt)
             .build()
             .unwrap();
@@ -311,36 +776,1533 @@ mod test {
         }
 
         #[test]
-        fn test_xor_decryption() {
+        fn test_template_decryption() {
             let salt = String::from("abc");
-            let prefix = String::from("1");
-            let xor = vec![3, 4, 5, 6];
+            let template = options::Mask::template("#-##").unwrap();
 
             let mut expected = vec![
-                results::Pair {
-                    hash: String::from("7900c0f65c087c03458293d7bb172ed1"),
-                    plain: String::from("MjY2"),
-                },
-                results::Pair {
-                    hash: String::from("7c1b8268077c6a9439fb82434dd5a5af"),
-                    plain: String::from("MjEw"),
-                },
-                results::Pair {
-                    hash: String::from("dd9eac6ed5ce1d8c5a645b4642ca1cd8"),
-                    plain: String::from("Mj08"),
-                },
+                results::Pair::new(
+                    String::from(
+                        "b12068037a15d824815740c64008f0df8742b67fb15b8098df1e0be709fa3fa1",
+                    ),
+                    String::from("1-23"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "d583098f064a582bfd40b0476b2541f78a65d3c29dc2233e85417d95b8192f4e",
+                    ),
+                    String::from("5-55"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "a4f5a039dff7a965ce0394f600a9b0018e0b6016ef423e78dd52e6fb767b97dd",
+                    ),
+                    String::from("9-09"),
+                ),
             ];
             expected.sort();
 
-            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
                 expected
                     .iter()
-                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Gpu)
+            .masks(vec![template])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_checksum_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("4");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "f3c6454ea69afb550037babe9ebd67ac1d6b49340e11350153e208f2dad722e7",
+                    ),
+                    String::from("4127"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "f7582bfc861b8cad1d50a941ae0efff9dc639e0b78180b4c70318074207deb4c",
+                    ),
+                    String::from("4564"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "31496a6dfce097063230a86dcbe81edf6c09bf6b251c11055e62297ad681a5b0",
+                    ),
+                    String::from("4994"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .checksum(options::Checksum::Luhn)
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 100);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_date_decryption() {
+            let salt = String::from("abc");
+            let dates = options::Dates::parse("YYMMDD", 2000, 2000).unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "1842f82ed906ceb1ce98175303d589c95c6bf69caab8a4bedecadddd6a8a787d",
+                    ),
+                    String::from("0001010"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "76779a902984629ca6881a461043686b6e389497bfda9e6aba8ab24467fcdebf",
+                    ),
+                    String::from("0002291"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "e3fff5374fe9be455764e221c6c6bfd055f0d533c765676cff989b23a297250e",
+                    ),
+                    String::from("0012315"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![7],
+            )
+            .dates(dates)
+            .device(options::Device::Gpu)
+            .salt(salt)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 3660);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wordlist_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_gpu_sha256_wordlist.txt");
+            std::fs::write(
+                &path,
+                "alpha\nbeta\nbeta\ncorrect-horse-battery-staple-correct-horse-battery-staple-\n",
+            )
+            .unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "b29cb2661f955cbbf71a9fb0006c0ff15b85589a9a991bd88b152c4b1307f55e",
+                    ),
+                    String::from("xbeta"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "8872c8011e0d5c0ceeb5e8078c664ff5ae216bd98e10f1de3a20fdc710df1900",
+                    ),
+                    String::from("xcorrect-horse-battery-staple-correct-horse-battery-staple-"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_rule_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_gpu_sha256_rules.txt");
+            std::fs::write(&path, "alpha\nbeta\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "ba1c578edd06e8e9156a393cfac68cfa6a5da5032d3c003ffc93a143869e7bda",
+                    ),
+                    String::from("xBeta1"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "a2da3e75907d70995ea190d9bb1a62082716881bdc7c6351eff49822741d94c0",
+                    ),
+                    String::from("x4lph4"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .rules(vec![
+                options::Rule::parse("c $1").unwrap(),
+                options::Rule::parse("sa4").unwrap(),
+            ])
+            .salt(salt)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hybrid_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_gpu_sha256_hybrid.txt");
+            std::fs::write(&path, "dep\nops\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "530ddf8db47600d1dc3055be5c6702695309f761862cce1363968704b38051c5",
+                    ),
+                    String::from("xdep07"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "5e83ff2f0615af9839ec714fb46a29b1cb444fe48603eb393ae04df9e7c1190a",
+                    ),
+                    String::from("xops42"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            assert_eq!(options.number_space(), 100);
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_append_salt_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "5a5f0a97ae7123f4216fdf9db93ebab172577b6c06becf25569d6f0eb903b5a0",
+                    ),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "9ea0aa9b58de399edceb6f3ae371b5e83b582e08d296bc08c8927924c97b99b4",
+                    ),
+                    String::from("1784"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Append)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wrap_salt_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from(
+                        "2ede1e066bb5db6dd56f23b8dc5bbf8dca9dc251d0c6533f3f11640c7d164887",
+                    ),
+                    plain: String::from("MjQ3NQ=="),
+                    original: Some(String::from("1023")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "3f54180fe34712dfeff4793c83ff8b3054c0c128a12e0dd82e006e379289aa42",
+                    ),
+                    plain: String::from("MjM9Mg=="),
+                    original: Some(String::from("1784")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Wrap)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+        #[test]
+        fn test_salt_group_decryption() {
+            let salts = vec![
+                (
+                    <hash::sha256::Hash as std::convert::From<&str>>::from(
+                        "828e0ef5b0a2af22879e8cf0eff47c3ebb10938cd97a87569a36d12bc61cac69",
+                    ),
+                    String::from("abc"),
+                ),
+                (
+                    <hash::sha256::Hash as std::convert::From<&str>>::from(
+                        "b98bfe32f45449e27dec687c7b8c7a1b5d333c3f82df4864dd5c887c1dc0ef56",
+                    ),
+                    String::from("xyz"),
+                ),
+            ];
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from(
+                        "828e0ef5b0a2af22879e8cf0eff47c3ebb10938cd97a87569a36d12bc61cac69",
+                    ),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "b98bfe32f45449e27dec687c7b8c7a1b5d333c3f82df4864dd5c887c1dc0ef56",
+                    ),
+                    String::from("1784"),
+                ),
+                results::Pair::new(
+                    String::from(
+                        "4c1769388c470b2d8873bb2cbd1b754ef7ddd457451fbf00a4cef4c86d749dbe",
+                    ),
+                    String::from("1500"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                std::iter::once(<hash::sha256::Hash as std::convert::From<&str>>::from(
+                    "4c1769388c470b2d8873bb2cbd1b754ef7ddd457451fbf00a4cef4c86d749dbe",
+                ))
+                .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![String::from("1")])
+            .salt(String::from("s"))
+            .salts(
+                salts
+                    .into_iter()
+                    .collect::<std::collections::HashMap<_, _>>(),
+            )
+            .build()
+            .unwrap();
+            assert_eq!(options.salt_groups().len(), 3);
+
+            let mut results = crate::decrypt::execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_transform_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("ab");
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from(
+                        "1ae88c9e1075fcdcb72aaa9a400eb53527c6895d53db07637b69be4540dbac38",
+                    ),
+                    plain: String::from("323442412d2d"),
+                    original: Some(String::from("ab42")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "7f56765b82015b2b95f90ceceed9b2ea8307a4443d43da2e72b98b5a66650c99",
+                    ),
+                    plain: String::from("373042412d2d"),
+                    original: Some(String::from("ab07")),
+                },
+                results::Pair {
+                    hash: String::from(
+                        "3eebf59586ea4ac0496bdac1f793e0b14fb6b497fe7b6f38ab66d5d64a9e4969",
+                    ),
+                    plain: String::from("393942412d2d"),
+                    original: Some(String::from("ab99")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha256::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .transforms(vec![
+                options::Transform::Pad(6, b'-'),
+                options::Transform::Reverse,
+                options::Transform::Upper,
+                options::Transform::Hex,
+            ])
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+            assert_eq!(results, expected);
+        }
+    }
+
+    mod md5 {
+        use super::super::{execute, execute_wordlist, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("e99a18c428cb38d5f260853678922e03"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("6b14d696623c7b26c275da041719ce53"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("361ac235e1e08be7325a8ced898e6ff4"),
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_chain_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("57f231b1ec41dc6641270cb09a56f897"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("d174532eaa3cde2ca157bef3f3fa84c2"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("d250a6d292362ef53edb0420df59e19f"),
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .chain(options::Chain::parse("md5(md5($s.$p))", options::Intermediate::Hex).unwrap())
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hmac_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("4cfb3f61bae66342315bb40f06a85cf1"),
+                    prefix.clone() + "99",
+                ),
+                results::Pair::new(
+                    String::from("a02cc11463743195f817be651ae82e2c"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("e33cf8cd1f571c0b6d900fad52ce9a7e"),
+                    prefix.clone() + "23",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .hmac_key(String::from("key"))
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("7900c0f65c087c03458293d7bb172ed1"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("7c1b8268077c6a9439fb82434dd5a5af"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("dd9eac6ed5ce1d8c5a645b4642ca1cd8"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_mask_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let mask = options::Mask::parse("?u-?d", &[]).unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("eb3e8b5408e6724dd78c1607f12003bc"),
+                    String::from("1A-0"),
+                ),
+                results::Pair::new(
+                    String::from("6addd30cdefcfc27f4c4ca5cb87bdf31"),
+                    String::from("1Q-5"),
+                ),
+                results::Pair::new(
+                    String::from("fd05263334264f16133cfca8ed60e324"),
+                    String::from("1Z-9"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Gpu)
+            .masks(vec![mask])
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_template_decryption() {
+            let salt = String::from("abc");
+            let template = options::Mask::template("#-##").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("afacebad24c59287638e14cfd9b191b3"),
+                    String::from("1-23"),
+                ),
+                results::Pair::new(
+                    String::from("329e638ebc0ea6d170e9be726960392a"),
+                    String::from("5-55"),
+                ),
+                results::Pair::new(
+                    String::from("97ca4f09ad9eb763042c7b11529ea812"),
+                    String::from("9-09"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Gpu)
+            .masks(vec![template])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_checksum_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("4");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("d9bc4df9dbd29487bccfd9680772f336"),
+                    String::from("4127"),
+                ),
+                results::Pair::new(
+                    String::from("cdbe7576819234366c07750bc38ed93e"),
+                    String::from("4564"),
+                ),
+                results::Pair::new(
+                    String::from("c6fd370306a33e301706d83cb33cb7c1"),
+                    String::from("4994"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .checksum(options::Checksum::Luhn)
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 100);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_date_decryption() {
+            let salt = String::from("abc");
+            let dates = options::Dates::parse("YYMMDD", 2000, 2000).unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("01da062cfe30266e98c0d057987b80b3"),
+                    String::from("0001010"),
+                ),
+                results::Pair::new(
+                    String::from("52780d32918af70bd8e87bd3c749e06d"),
+                    String::from("0002291"),
+                ),
+                results::Pair::new(
+                    String::from("a0c8c2142083c91bb91e3738b5af3aa9"),
+                    String::from("0012315"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![7],
+            )
+            .dates(dates)
+            .device(options::Device::Gpu)
+            .salt(salt)
+            .build()
+            .unwrap();
+            assert_eq!(options.number_space(), 3660);
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wordlist_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_gpu_md5_wordlist.txt");
+            std::fs::write(
+                &path,
+                "alpha\nbeta\nbeta\ncorrect-horse-battery-staple-correct-horse-battery-staple-\n",
+            )
+            .unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("c5780614ddccd4f32fdf868bda60c406"),
+                    String::from("xbeta"),
+                ),
+                results::Pair::new(
+                    String::from("afab7847a0681f9e7fada1e75b67e562"),
+                    String::from("xcorrect-horse-battery-staple-correct-horse-battery-staple-"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_rule_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_gpu_md5_rules.txt");
+            std::fs::write(&path, "alpha\nbeta\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("989a4b5755bf824a7a5d8780e8f85014"),
+                    String::from("xBeta1"),
+                ),
+                results::Pair::new(
+                    String::from("23a71e917eab67f3c29311235b4fb7c1"),
+                    String::from("x4lph4"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .rules(vec![
+                options::Rule::parse("c $1").unwrap(),
+                options::Rule::parse("sa4").unwrap(),
+            ])
+            .salt(salt)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_hybrid_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("x");
+            let path = std::env::temp_dir().join("hasher_gpu_md5_hybrid.txt");
+            std::fs::write(&path, "dep\nops\n").unwrap();
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("0f946039924090db72a292205ed4f147"),
+                    String::from("xdep07"),
+                ),
+                results::Pair::new(
+                    String::from("dc060c16908f00647b051949aba62de7"),
+                    String::from("xops42"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .wordlist(options::Wordlist::File(path.clone()))
+            .build()
+            .unwrap();
+
+            assert_eq!(options.number_space(), 100);
+
+            let wordlist = options.wordlist().unwrap();
+            let mut results = execute_wordlist(&options, wordlist, &Channel)
+                .unwrap()
+                .results;
+            results.sort();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_append_salt_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("18831e8768c11cf4aba64348472462b4"),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from("3de95eae2ba181608a84d617253e3f95"),
+                    String::from("1784"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Append)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_wrap_salt_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("5dcae8d249b444207ae7f4d22d8b7f0e"),
+                    plain: String::from("MjQ3NQ=="),
+                    original: Some(String::from("1023")),
+                },
+                results::Pair {
+                    hash: String::from("3a5573e40918b930d87d458b285e07a1"),
+                    plain: String::from("MjM9Mg=="),
+                    original: Some(String::from("1784")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .salt_position(options::SaltPosition::Wrap)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+        #[test]
+        fn test_salt_group_decryption() {
+            let salts = vec![
+                (
+                    <hash::md5::Hash as std::convert::From<&str>>::from(
+                        "b436d3e7f8095de37817a30654a3d03e",
+                    ),
+                    String::from("abc"),
+                ),
+                (
+                    <hash::md5::Hash as std::convert::From<&str>>::from(
+                        "1491d4c280ca4ad2390df04840641348",
+                    ),
+                    String::from("xyz"),
+                ),
+            ];
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("b436d3e7f8095de37817a30654a3d03e"),
+                    String::from("1023"),
+                ),
+                results::Pair::new(
+                    String::from("1491d4c280ca4ad2390df04840641348"),
+                    String::from("1784"),
+                ),
+                results::Pair::new(
+                    String::from("360a03c06795cf8722525ed259bfa6af"),
+                    String::from("1500"),
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                std::iter::once(<hash::md5::Hash as std::convert::From<&str>>::from(
+                    "360a03c06795cf8722525ed259bfa6af",
+                ))
+                .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![String::from("1")])
+            .salt(String::from("s"))
+            .salts(
+                salts
+                    .into_iter()
+                    .collect::<std::collections::HashMap<_, _>>(),
+            )
+            .build()
+            .unwrap();
+            assert_eq!(options.salt_groups().len(), 3);
+
+            let mut results = crate::decrypt::execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_transform_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("ab");
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("8a86a08951e865cd5bc52be0158c00fc"),
+                    plain: String::from("323442412d2d"),
+                    original: Some(String::from("ab42")),
+                },
+                results::Pair {
+                    hash: String::from("4d2c4bf55a86de580cad3485928e8a97"),
+                    plain: String::from("373042412d2d"),
+                    original: Some(String::from("ab07")),
+                },
+                results::Pair {
+                    hash: String::from("8b11483c0136119fa4ea0b67242a3098"),
+                    plain: String::from("393942412d2d"),
+                    original: Some(String::from("ab99")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md5::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md5::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![4],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .threads(4)
+            .transforms(vec![
+                options::Transform::Pad(6, b'-'),
+                options::Transform::Reverse,
+                options::Transform::Upper,
+                options::Transform::Hex,
+            ])
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+            assert_eq!(results, expected);
+        }
+    }
+
+    mod sha1 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("6367c48dd193d56ea7b0baad25b19455e529f5ee"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("acdd5803ea05b235c2b6dcb8f5cc968a1a125fb0"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("7602c363fa5cc0ae5f37830d02cd6bd9ca619d29"),
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha1::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha1::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("3f39a2957f7be65e4a60373d6243d96d2f63896d"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("29ddc9fd8fc2ba5e1618f1bcced3fdd34e2fe748"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("b5a369dff80ab70af958d3b43e1c975be83e2f16"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha1::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha1::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+    }
+
+    mod sha384 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(String::from("a31d79891919cad24f3264479d76884f581bee32e86778373db3a124de975dd86a40fc7f399b331133b281ab4b11a6ca"), prefix.clone() + "23"),
+                results::Pair::new(String::from("7b6e4db3dfde853a2da3dc339ce45900bc6af5d34cfce60589ecb6c412e874d4494fc956f80de4ff8fd3d99d040fde13"), prefix.clone() + "55"),
+                results::Pair::new(String::from("dcf238486a0435bae7283eecaa53b9f3787b1c8942c489af3800368e277e72d4e0d2098fcc19c3ee21c718152d7ab160"), prefix.clone() + "99"),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha384::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha384::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("949a2d76da9f76b9eaacbffd1d1cb80868ca13241d5e3c6f15e9012b6cb045d4f661339a459b15c8c6009e0bd32c8d46"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("f5b13216a90cf53017f048cb85eb2094196549ffe52e5d900efd664cf0a9c571fa1130591b6d4e9541bdde55ab040887"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("c7820a98ba2fb1007fb0f249e6755c4bbb6ab2faeb791ba9912bb700fafd0f9e288c5ca1759dca45ccd8ce28a71ab403"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha384::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha384::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+    }
+
+    mod sha512 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(String::from("c70b5dd9ebfb6f51d09d4132b7170c9d20750a7852f00680f65658f0310e810056e6763c34c9a00b0e940076f54495c169fc2302cceb312039271c43469507dc"), prefix.clone() + "23"),
+                results::Pair::new(String::from("b7e24dffbecf2f4562d1bca6e961c1e4a60cca6943566eb8c9d0a90edf2cc43a2d32253ecdc0bdfefac2fdc9f9e4966fb86eb0c9629d1790292bbbbf15d75cde"), prefix.clone() + "55"),
+                results::Pair::new(String::from("c92bcf07f5d16107e644e7874e8495d160f2672067d12f2542074fb0a601615e46b086966d22b7941e50782f91d519e6948bc15b8d988b4c748a929c2fd01ddd"), prefix.clone() + "99"),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha512::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha512::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("37df6a25a11f71a88dbfdeb60e00c790df5b373c27872f179634f878f557da6c55819ada7cf9ae47e1a009ca16b8664da7d08ddc8f0fb2e01dd653c8f137da5c"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("a6181b142561943f8c57be00bca1169a61628da96e939385efa1a98ccaac93240181636fd2a1512b27e39276f790e8b3b4d3511d646197cd74d0f221bce2a75f"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("b680dca00d452f682f5add86982a8c71069b8eca1f15047bc960ceb3aa9f8cd00855817979346e3b17e734db9c311581f516d4d8dc4d6d9b744935b1cf32a468"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::sha512::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::sha512::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .xor(xor)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+    }
+
+    mod md4 {
+        use super::super::{execute, hash, options, results};
+        use super::Channel;
+
+        #[test]
+        fn test_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("0ceb1fd260c35bd50005341532748de6"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("1025cb0d7173de66e7f7d1d04116513c"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("30ff4087cdfb981bfbb450eb4324c1c2"),
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md4::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md4::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_utf16_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+
+            let mut expected = vec![
+                results::Pair::new(
+                    String::from("f9e37e83b83c47a93c2f09f66408631b"),
+                    prefix.clone() + "23",
+                ),
+                results::Pair::new(
+                    String::from("fcab0630394d2ed489456061eac69fe1"),
+                    prefix.clone() + "55",
+                ),
+                results::Pair::new(
+                    String::from("e4c3752bbb42f24dc3c818df94df958a"),
+                    prefix.clone() + "99",
+                ),
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md4::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md4::Hash as std::convert::From<&str>>::from(&v.hash))
+                    .collect(),
+                vec![3],
+            )
+            .device(options::Device::Gpu)
+            .encoding(options::Encoding::Utf16Le)
+            .prefixes(vec![prefix])
+            .salt(salt)
+            .build()
+            .unwrap();
+
+            let mut results = execute(&options, &Channel).unwrap().results;
+            results.sort();
+
+            assert_eq!(results, expected);
+        }
+
+        #[test]
+        fn test_xor_decryption() {
+            let salt = String::from("abc");
+            let prefix = String::from("1");
+            let xor = vec![3, 4, 5, 6];
+
+            let mut expected = vec![
+                results::Pair {
+                    hash: String::from("2cebee61f26034e66f3a5fbaedf39065"),
+                    plain: String::from("MjY2"),
+                    original: Some(String::from("123")),
+                },
+                results::Pair {
+                    hash: String::from("ad9c78aafc8bdda4db218ff0890d3452"),
+                    plain: String::from("MjEw"),
+                    original: Some(String::from("155")),
+                },
+                results::Pair {
+                    hash: String::from("bfceb3751f8c64368fe69145ddbfcbf7"),
+                    plain: String::from("Mj08"),
+                    original: Some(String::from("199")),
+                },
+            ];
+            expected.sort();
+
+            let options = options::DecryptBuilder::<hash::md4::Hash>::new(
+                expected
+                    .iter()
+                    .map(|v| <hash::md4::Hash as std::convert::From<&str>>::from(&v.hash))
                     .collect(),
-                3,
+                vec![3],
             )
             .device(options::Device::Gpu)
-            .prefix(prefix)
+            .prefixes(vec![prefix])
             .salt(salt)
             .xor(xor)
             .build()
@@ -8,14 +8,126 @@ mod cpu;
 mod gpu;
 mod opencl;
 
-pub use cpu::OPTIMAL_HASHES_PER_THREAD;
+pub use cpu::{Sender, OPTIMAL_HASHES_PER_THREAD};
+pub use opencl::{has_chain_kernel, has_hmac_kernel, has_kernel};
 
 pub fn execute<H: hash::Hash>(
     options: &options::Decrypt<H>,
     channel: &impl channel::Channel,
 ) -> Result<results::Summary, error::Error> {
-    match options.device() {
-        options::Device::Gpu => gpu::execute(options, channel),
-        options::Device::Cpu => cpu::execute(options, channel),
+    use options::SharedAccessor;
+
+    if options.salts().is_empty() {
+        return execute_group(options, channel);
+    }
+
+    // Targets sharing a salt are searched together, one group after the other
+    let time = std::time::Instant::now();
+    let mut summary = results::Summary {
+        total_count: options.input().len(),
+        duration: std::time::Duration::default(),
+        hash_count: 0,
+        threads: 0,
+        results: Vec::with_capacity(options.input().len()),
+    };
+
+    let groups = options.salt_groups();
+    for (index, group) in groups.iter().enumerate() {
+        if channel.should_terminate() {
+            break;
+        }
+
+        let group_channel = GroupChannel {
+            channel: Sender(channel),
+            index,
+            count: groups.len(),
+        };
+        let group_summary = execute_group(group, &group_channel)?;
+        summary.hash_count += group_summary.hash_count;
+        summary.threads = std::cmp::max(summary.threads, group_summary.threads);
+        summary.results.extend(group_summary.results);
+    }
+
+    summary.duration = time.elapsed();
+    Ok(summary)
+}
+
+fn execute_group<H: hash::Hash>(
+    options: &options::Decrypt<H>,
+    channel: &impl channel::Channel,
+) -> Result<results::Summary, error::Error> {
+    match (options.device(), options.wordlist()) {
+        (options::Device::Gpu, Some(wordlist)) => gpu::execute_wordlist(options, wordlist, channel),
+        (options::Device::Cpu, Some(wordlist)) => cpu::execute_wordlist(options, wordlist, channel),
+        (options::Device::Gpu, None) => gpu::execute(options, channel),
+        (options::Device::Cpu, None) => cpu::execute(options, channel),
+    }
+}
+
+// Reports the progress of a salt group as its share of the progress of every group
+//
+// SAFETY:
+// 1: The channel is only shared for the search of the group, which it outlives
+// 2: The channel is itself Sync
+struct GroupChannel<C> {
+    channel: Sender<C>,
+    index: usize,
+    count: usize,
+}
+
+unsafe impl<C: Sync> Sync for GroupChannel<C> {}
+
+impl<C: channel::Channel> channel::Channel for GroupChannel<C> {
+    // Allowed because it will always be <= 100
+    #[allow(clippy::cast_possible_truncation)]
+    fn progress(&self, progress: u8) {
+        self.channel
+            .progress(((self.index * 100 + usize::from(progress)) / self.count) as u8);
+    }
+
+    fn result(&self, input: &str, output: &str) {
+        self.channel.result(input, output);
+    }
+
+    fn should_terminate(&self) -> bool {
+        self.channel.should_terminate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{channel, GroupChannel, Sender};
+
+    struct Channel(std::sync::Mutex<Vec<u8>>);
+
+    impl channel::Channel for Channel {
+        fn progress(&self, progress: u8) {
+            self.0.lock().unwrap().push(progress);
+        }
+        fn result(&self, _: &str, _: &str) {}
+        fn should_terminate(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_group_progress() {
+        use channel::Channel as _;
+
+        let channel = Channel(std::sync::Mutex::new(Vec::new()));
+        let shared = &channel;
+        for index in 0..4 {
+            let group_channel = GroupChannel {
+                channel: Sender(shared),
+                index,
+                count: 4,
+            };
+            group_channel.progress(0);
+            group_channel.progress(100);
+        }
+        assert_eq!(
+            channel.0.into_inner().unwrap(),
+            vec![0, 25, 25, 50, 50, 75, 75, 100]
+        );
     }
 }
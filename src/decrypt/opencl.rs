@@ -4,31 +4,83 @@ use crate::options;
 
 use crate::options::SharedAccessor;
 
-const MAX_GPU_LENGTH: u8 = 7;
-const BASE64: &str = include_str!("../../cl/base64.cl");
+const MAX_GPU_RANGE: u64 = 10_000_000;
 const PREPARE: &str = include_str!("../../cl/prepare.cl");
+const TRANSFORM: &str = include_str!("../../cl/transform.cl");
 
-pub(super) fn setup_for<H: hash::Hash>(
-    options: &options::Decrypt<H>,
-) -> Result<Environment<'_, H>, error::Error> {
+/// If the algorithm can be cracked on the GPU
+pub fn has_kernel(algorithm: hash::Algorithm) -> bool {
+    source::template(algorithm).is_some()
+}
+
+/// If the HMAC of the algorithm can be cracked on the GPU
+pub fn has_hmac_kernel(algorithm: hash::Algorithm) -> bool {
+    algorithm == hash::Algorithm::md5 || algorithm == hash::Algorithm::sha256
+}
+
+/// If the chain can be cracked on the GPU, only nesting md5 or sha256 over the salted value
+pub fn has_chain_kernel(chain: &options::Chain) -> bool {
+    chain.rounds().is_some()
+        && (chain.algorithm() == hash::Algorithm::md5
+            || chain.algorithm() == hash::Algorithm::sha256)
+}
+
+// Size of the buffer of the kernels, a block of the algorithm
+pub(super) fn value_length<H: hash::Hash>() -> usize {
+    match H::algorithm() {
+        hash::Algorithm::md4
+        | hash::Algorithm::md5
+        | hash::Algorithm::sha1
+        | hash::Algorithm::sha256
+        | hash::Algorithm::blake3 => 64,
+        hash::Algorithm::sha384 | hash::Algorithm::sha512 | hash::Algorithm::blake2b => 128,
+        hash::Algorithm::sha3_256 | hash::Algorithm::keccak256 => 136,
+    }
+}
+
+// Number of bytes of every character hashed by the kernels
+pub(super) fn char_width(encoding: options::Encoding) -> usize {
+    match encoding {
+        options::Encoding::Utf8 => 1,
+        options::Encoding::Utf16Le => 2,
+    }
+}
+
+pub(super) fn setup_for<'a, H: hash::Hash>(
+    options: &'a options::Decrypt<H>,
+    segment: &'a options::Segment,
+    configuration: &'a Configuration,
+) -> Result<Environment<'a, H>, error::Error> {
     Ok(Environment {
         options,
-        configuration: Configuration::new()?,
-        kernel_parameters: KernelParameters::from(options),
+        segment,
+        word_length: None,
+        configuration,
+        kernel_parameters: KernelParameters::from(segment)?,
     })
 }
 
-fn calculate_base64_len(length: usize) -> usize {
-    if length % 3 > 0 {
-        (length / 3 + 1) << 2
-    } else {
-        (length / 3) << 2
-    }
+// The words are read from a buffer, all of the same length, and follow the segment's prefix
+pub(super) fn setup_for_words<'a, H: hash::Hash>(
+    options: &'a options::Decrypt<H>,
+    segment: &'a options::Segment,
+    word_length: u8,
+    configuration: &'a Configuration,
+) -> Result<Environment<'a, H>, error::Error> {
+    Ok(Environment {
+        options,
+        segment,
+        word_length: Some(word_length),
+        configuration,
+        kernel_parameters: KernelParameters::from(segment)?,
+    })
 }
 
 pub(super) struct Environment<'a, H: hash::Hash> {
     options: &'a options::Decrypt<H>, // The environment is locked to the options. It must not change
-    configuration: Configuration,
+    segment: &'a options::Segment,    // The program is only valid for this segment
+    word_length: Option<u8>,          // The program is only valid for words of this length
+    configuration: &'a Configuration, // The device is shared by every program of a run
     kernel_parameters: KernelParameters,
 }
 
@@ -37,41 +89,92 @@ impl<'a, H: hash::Hash> Environment<'a, H> {
     // Allowed because salted prefix is limited in size
     #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
     pub(super) fn make_program(&self) -> Result<ocl::Program, error::Error> {
-        let salted_prefix = format!("{}{}", self.options.salt(), self.options.prefix());
-        let end = i32::from(salted_prefix.len() as u8 + self.options.length());
+        let salted_prefix = format!("{}{}", self.options.salt_prefix(), self.segment.prefix());
+        let salted_suffix = self.options.salt_suffix();
+        let salt_length = self.options.salt_prefix().len();
+        let variable_length = self.word_length.unwrap_or_else(|| self.segment.length());
+        let end = i32::from(salted_prefix.len() as u8 + variable_length);
+        // Characters are written `width` bytes apart, while the positions of the variable part
+        // are given in characters
+        let width = char_width(self.options.encoding());
 
         let mut builder = ocl::Program::builder();
-        builder.source(PREPARE);
-
-        if let Some(xor) = self.options.xor().as_ref() {
-            let length = usize::from(self.options.length() + self.options.prefix_length());
-            let source = source::template::<H>().with_prefix_and_xor(
-                &salted_prefix,
-                self.options.salt().len(),
-                length,
-                xor,
-            );
-
+        if width > 1 {
+            builder.cmplr_def("CONST_UTF16LE", 1);
+        }
+        if self.word_length.is_some() {
+            builder.cmplr_def("CONST_WORDLIST", 1);
+        }
+        if !self.segment.mask().is_decimal() {
             builder
-                .source(BASE64)
-                .source(source.to_string())
-                .cmplr_def("CONST_BASE64_BEGIN", self.options.salt().len() as i32)
-                .cmplr_def(
-                    "CONST_LENGTH",
-                    (calculate_base64_len(length) + self.options.salt().len()) as i32,
-                );
-        } else {
-            let source = source::template::<H>().with_prefix(&salted_prefix);
+                .source(source::mask_tables(self.segment.mask()))
+                .cmplr_def("CONST_MASK", 1);
+        }
+        if let Some(dates) = self.segment.dates() {
+            let (year, year_length, month, day) = dates.layout();
             builder
-                .source(source.to_string())
-                .cmplr_def("CONST_LENGTH", end);
+                .cmplr_def("CONST_DATE_FIRST", dates.first_day() as i32)
+                .cmplr_def("CONST_DATE_LENGTH", dates.len() as i32)
+                .cmplr_def("CONST_DATE_YEAR", year as i32)
+                .cmplr_def("CONST_DATE_YEAR_LENGTH", year_length as i32)
+                .cmplr_def("CONST_DATE_MONTH", month as i32)
+                .cmplr_def("CONST_DATE_DAY", day as i32);
         }
+        if let Some(checksum) = self.segment.checksum() {
+            let (weights, base) = source::checksum_tables(self.segment, checksum);
+            builder
+                .source(weights)
+                .cmplr_def("CONST_CHECKSUM", source::checksum_definition(checksum))
+                .cmplr_def("CONST_CHECKSUM_LENGTH", i32::from(checksum.length()))
+                .cmplr_def("CONST_CHECKSUM_BASE", base as i32);
+        }
+        if let Some(key) = self.options.hmac_key() {
+            builder.source(
+                source::hmac_states(H::algorithm(), key.as_bytes())
+                    .ok_or_else(|| error!("OpenCL: No HMAC kernel for {}", H::algorithm()))?,
+            );
+        }
+        if let Some(chain) = self.options.chain() {
+            let rounds = chain
+                .rounds()
+                .ok_or_else(|| error!("OpenCL: No kernel for chain {}", chain))?;
+            builder.cmplr_def(
+                "CONST_CHAIN_ROUNDS",
+                std::convert::TryFrom::try_from(rounds)
+                    .map_err(|err| error!(err; "OpenCL: Too many rounds in chain {}", chain))?,
+            );
+            if chain.intermediate() == options::Intermediate::Hex {
+                builder.cmplr_def("CONST_CHAIN_HEX", 1);
+            }
+        }
+        builder.source(PREPARE);
+
+        // The prefix and the variable part are transformed together, after the salt
+        let transforms = self.options.transforms();
+        let length = usize::from(variable_length + self.segment.prefix_length());
+        let transformed_end = salt_length
+            + transforms
+                .length(length)
+                .ok_or_else(|| error!("OpenCL: Values cannot be transformed"))?;
+        let source = source::template(H::algorithm())
+            .ok_or_else(|| error!("OpenCL: No kernel for {}", H::algorithm()))?
+            .with_prefix(&salted_prefix, width)
+            .with_transforms(transforms, salt_length, length)
+            .with_suffix(salted_suffix, transformed_end, width);
+        if !transforms.is_empty() {
+            builder.source(TRANSFORM);
+        }
+        builder.source(source.to_string()).cmplr_def(
+            "CONST_LENGTH",
+            ((transformed_end + salted_suffix.len()) * width) as i32,
+        );
 
         builder
             .devices(self.configuration.device)
             .cmplr_def("CONST_BEGIN", salted_prefix.len() as i32)
             .cmplr_def("CONST_END", end)
             .cmplr_def("CONST_TARGET_COUNT", self.options.input().len() as i32)
+            .cmplr_def("CONST_VALUE_LENGTH", value_length::<H>() as i32)
             .cmplr_def(
                 self.kernel_parameters.cpu_length_definition(),
                 i32::from(self.kernel_parameters.length_on_cpu_iterations),
@@ -80,6 +183,10 @@ impl<'a, H: hash::Hash> Environment<'a, H> {
             .map_err(|err| error!(err; "OpenCL: Failed to build program"))
     }
 
+    pub(super) fn segment(&self) -> &options::Segment {
+        self.segment
+    }
+
     pub(super) fn queue(&self) -> &ocl::Queue {
         &self.configuration.queue
     }
@@ -97,7 +204,8 @@ impl<'a, H: hash::Hash> Environment<'a, H> {
     }
 }
 
-struct Configuration {
+// The device along with its context and queue, set up once for a whole run
+pub(super) struct Configuration {
     device: ocl::Device,
     context: ocl::Context,
     queue: ocl::Queue,
@@ -105,7 +213,7 @@ struct Configuration {
 }
 
 impl Configuration {
-    fn new() -> Result<Self, error::Error> {
+    pub(super) fn new() -> Result<Self, error::Error> {
         let (platform, device) = Self::first_gpu()?;
         let context = ocl::Context::builder()
             .platform(platform)
@@ -178,34 +286,44 @@ impl Configuration {
 struct KernelParameters {
     cpu_iterations: u32,
     range: u32,
-    length_on_gpu_kernel: u8,
     length_on_cpu_iterations: u8,
 }
 
 impl KernelParameters {
-    fn from<H: hash::Hash>(options: &options::Decrypt<H>) -> Self {
-        let length_on_cpu_iterations = if MAX_GPU_LENGTH > options.length() {
-            0
-        } else {
-            options.length() - MAX_GPU_LENGTH
-        };
-        let cpu_iterations = 10_u32.pow(u32::from(length_on_cpu_iterations));
-
-        // Allowed because min(MAX_GPU_RANGE, ...) will always fit in u32
-        #[allow(clippy::cast_possible_truncation)]
-        let range = std::cmp::min(
-            10_u64.pow(u32::from(MAX_GPU_LENGTH)),
-            options.number_space(),
-        ) as u32;
-
-        let length_on_gpu_kernel = options.length() - length_on_cpu_iterations;
+    // The trailing positions are enumerated by the kernel, up to `MAX_GPU_RANGE` candidates.
+    // The leading positions are enumerated by the CPU iterations. Dates come before the whole
+    // mask, so they are only enumerated by the kernel along with all of it
+    fn from(segment: &options::Segment) -> Result<Self, error::Error> {
+        let mut range = 1;
+        let mut length_on_gpu_kernel = 0;
+        for position in segment.mask().positions().iter().rev() {
+            if range * position.radix() > MAX_GPU_RANGE {
+                break;
+            }
+            range *= position.radix();
+            length_on_gpu_kernel += 1;
+        }
+        if let Some(dates) = segment.dates() {
+            if length_on_gpu_kernel == segment.mask().len()
+                && range * dates.number_space() <= MAX_GPU_RANGE
+            {
+                range *= dates.number_space();
+            }
+        }
 
-        Self {
-            cpu_iterations,
-            range,
-            length_on_gpu_kernel,
-            length_on_cpu_iterations,
+        let cpu_iterations = segment.number_space() / range;
+        if cpu_iterations > u64::from(u32::max_value()) {
+            bail!("OpenCL: Keyspace is too large to be enumerated on the GPU");
         }
+
+        // Allowed because `range` is capped by `MAX_GPU_RANGE` and `cpu_iterations` was checked
+        // Allowed because the mask is always shorter than the OpenCL value buffer
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(Self {
+            cpu_iterations: cpu_iterations as u32,
+            range: range as u32,
+            length_on_cpu_iterations: (segment.mask().len() - length_on_gpu_kernel) as u8,
+        })
     }
 
     fn cpu_length_definition(&self) -> &'static str {
@@ -235,46 +353,53 @@ impl Output {
         self.data[0] > 0
     }
 
+    /// Index of the matching word in a batch of words
+    pub(super) fn word(self) -> usize {
+        self.data[0] as usize
+    }
+
     pub(super) fn printable<H: hash::Hash>(self, environment: &Environment<'_, H>) -> String {
-        if environment.cpu_iterations() > 1 {
-            format!(
-                "{:02$}{:03$}",
-                self.data[1],
-                self.data[0],
-                usize::from(environment.kernel_parameters.length_on_cpu_iterations),
-                usize::from(environment.kernel_parameters.length_on_gpu_kernel)
-            )
-        } else {
-            format!(
-                "{:01$}",
-                self.data[0],
-                usize::from(environment.kernel_parameters.length_on_gpu_kernel)
-            )
-        }
+        environment.segment().candidate(
+            u64::from(self.data[1]) * u64::from(environment.range()) + u64::from(self.data[0]),
+        )
     }
 }
 
 mod source {
     use crate::hash;
+    use crate::options;
 
+    const MD4: &str = include_str!("../../cl/md4.cl");
     const MD5: &str = include_str!("../../cl/md5.cl");
+    const SHA1: &str = include_str!("../../cl/sha1.cl");
     const SHA256: &str = include_str!("../../cl/sha256.cl");
+    const SHA384: &str = concat!("#define CONST_SHA384\n", include_str!("../../cl/sha512.cl"));
+    const SHA512: &str = include_str!("../../cl/sha512.cl");
 
     pub(super) struct SourceTemplate(&'static str);
     pub(super) struct Source(String);
 
-    pub(super) fn template<H: hash::Hash>() -> SourceTemplate {
-        match H::algorithm() {
-            hash::Algorithm::md5 => SourceTemplate(MD5),
-            hash::Algorithm::sha256 => SourceTemplate(SHA256),
+    pub(super) fn template(algorithm: hash::Algorithm) -> Option<SourceTemplate> {
+        match algorithm {
+            hash::Algorithm::md4 => Some(SourceTemplate(MD4)),
+            hash::Algorithm::md5 => Some(SourceTemplate(MD5)),
+            hash::Algorithm::sha1 => Some(SourceTemplate(SHA1)),
+            hash::Algorithm::sha256 => Some(SourceTemplate(SHA256)),
+            hash::Algorithm::sha384 => Some(SourceTemplate(SHA384)),
+            hash::Algorithm::sha512 => Some(SourceTemplate(SHA512)),
+            hash::Algorithm::sha3_256
+            | hash::Algorithm::keccak256
+            | hash::Algorithm::blake2b
+            | hash::Algorithm::blake3 => None,
         }
     }
 
     impl SourceTemplate {
-        pub(super) fn with_prefix(&self, salted_prefix: &str) -> Source {
+        // Bytes are written `width` bytes apart, as numbers so that any salt can be injected
+        pub(super) fn with_prefix(&self, salted_prefix: &str, width: usize) -> Source {
             let mut injected_code = String::new();
-            for (i, c) in salted_prefix.chars().enumerate() {
-                injected_code.push_str(format!("value.bytes[{}] = \'{}\';", i, c).as_str());
+            for (i, byte) in salted_prefix.bytes().enumerate() {
+                injected_code.push_str(format!("value.bytes[{}] = {};", i * width, byte).as_str());
             }
 
             let mut output = String::new();
@@ -289,41 +414,354 @@ mod source {
 
             Source(output)
         }
+    }
+
+    // The charsets of every position of the mask, concatenated, and their bounds
+    pub(super) fn mask_tables(mask: &options::Mask) -> String {
+        let mut charsets = Vec::new();
+        let mut offsets = Vec::with_capacity(mask.len());
+        let mut radixes = Vec::with_capacity(mask.len());
+
+        for position in mask.positions() {
+            offsets.push(charsets.len().to_string());
+            radixes.push(position.radix().to_string());
+            match position {
+                options::Position::Literal(literal) => charsets.push(literal.to_string()),
+                options::Position::Charset(charset) => {
+                    charsets.extend(charset.iter().map(u8::to_string));
+                }
+            }
+        }
+
+        format!(
+            "__constant unsigned char mask_charsets[{}] = {{{}}};\n\
+             __constant unsigned short mask_offsets[{}] = {{{}}};\n\
+             __constant unsigned short mask_radixes[{}] = {{{}}};\n",
+            charsets.len(),
+            charsets.join(", "),
+            offsets.len(),
+            offsets.join(", "),
+            radixes.len(),
+            radixes.join(", "),
+        )
+    }
+
+    // The weight of every position of the dates and the mask in the checksum, and the constant
+    // sum of the digits of the prefix and of the literals
+    pub(super) fn checksum_tables(
+        segment: &options::Segment,
+        checksum: options::Checksum,
+    ) -> (String, u32) {
+        let positions = segment.mask().positions();
+        let date_length = segment.dates().map_or(0, options::Dates::len);
+        let mut weights = vec![0; date_length + positions.len()];
+        let mut base = 0;
+
+        let mut digit = 0;
+        for (index, position) in positions.iter().enumerate().rev() {
+            match position {
+                options::Position::Literal(c) if c.is_ascii_digit() => {
+                    base += checksum.contribution(u32::from(c - b'0'), checksum.weight(digit));
+                    digit += 1;
+                }
+                options::Position::Literal(_) => {}
+                options::Position::Charset(_) => {
+                    weights[date_length + index] = checksum.weight(digit);
+                    digit += 1;
+                }
+            }
+        }
+        for weight in weights[..date_length].iter_mut().rev() {
+            *weight = checksum.weight(digit);
+            digit += 1;
+        }
+        for c in segment.prefix().bytes().rev().filter(u8::is_ascii_digit) {
+            base += checksum.contribution(u32::from(c - b'0'), checksum.weight(digit));
+            digit += 1;
+        }
+
+        // Empty arrays are not valid OpenCL
+        if weights.is_empty() {
+            weights.push(0);
+        }
+
+        (
+            format!(
+                "__constant unsigned char checksum_weights[{}] = {{{}}};\n",
+                weights.len(),
+                weights
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            base,
+        )
+    }
+
+    // The states of the hash once the inner and the outer padded keys are hashed, computed once so
+    // that the kernels only hash the value, then the inner hash, from these states
+    pub(super) fn hmac_states(algorithm: hash::Algorithm, key: &[u8]) -> Option<String> {
+        let (inner, outer) = match algorithm {
+            hash::Algorithm::md5 => {
+                let key = block_key::<md5::Md5>(key);
+                (md5_state(&xored(&key, 0x36)), md5_state(&xored(&key, 0x5c)))
+            }
+            hash::Algorithm::sha256 => {
+                let key = block_key::<sha2::Sha256>(key);
+                (
+                    sha256_state(&xored(&key, 0x36)),
+                    sha256_state(&xored(&key, 0x5c)),
+                )
+            }
+            _ => return None,
+        };
+
+        let initializer = |state: Vec<u32>| {
+            state
+                .iter()
+                .map(|word| format!("0x{:08x}", word))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        Some(format!(
+            "#define CONST_HMAC_INNER {{{}}}\n#define CONST_HMAC_OUTER {{{}}}\n",
+            initializer(inner),
+            initializer(outer),
+        ))
+    }
+
+    // Keys longer than a block are hashed first, then all are padded with zeros up to a block
+    fn block_key<D: digest::Digest>(key: &[u8]) -> [u8; 64] {
+        let mut block = [0; 64];
+        if key.len() > block.len() {
+            let hash = D::digest(key);
+            block[..hash.len()].copy_from_slice(&hash);
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        block
+    }
+
+    fn xored(block: &[u8; 64], pad: u8) -> [u8; 64] {
+        let mut xored = *block;
+        for byte in &mut xored {
+            *byte ^= pad;
+        }
+        xored
+    }
+
+    // The md5 crate does not expose its compression function
+    // Allowed because the registers are named as in RFC 1321
+    #[allow(clippy::many_single_char_names)]
+    fn md5_state(block: &[u8; 64]) -> Vec<u32> {
+        const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+        const CONSTANTS: [u32; 64] = [
+            0xd76a_a478,
+            0xe8c7_b756,
+            0x2420_70db,
+            0xc1bd_ceee,
+            0xf57c_0faf,
+            0x4787_c62a,
+            0xa830_4613,
+            0xfd46_9501,
+            0x6980_98d8,
+            0x8b44_f7af,
+            0xffff_5bb1,
+            0x895c_d7be,
+            0x6b90_1122,
+            0xfd98_7193,
+            0xa679_438e,
+            0x49b4_0821,
+            0xf61e_2562,
+            0xc040_b340,
+            0x265e_5a51,
+            0xe9b6_c7aa,
+            0xd62f_105d,
+            0x0244_1453,
+            0xd8a1_e681,
+            0xe7d3_fbc8,
+            0x21e1_cde6,
+            0xc337_07d6,
+            0xf4d5_0d87,
+            0x455a_14ed,
+            0xa9e3_e905,
+            0xfcef_a3f8,
+            0x676f_02d9,
+            0x8d2a_4c8a,
+            0xfffa_3942,
+            0x8771_f681,
+            0x6d9d_6122,
+            0xfde5_380c,
+            0xa4be_ea44,
+            0x4bde_cfa9,
+            0xf6bb_4b60,
+            0xbebf_bc70,
+            0x289b_7ec6,
+            0xeaa1_27fa,
+            0xd4ef_3085,
+            0x0488_1d05,
+            0xd9d4_d039,
+            0xe6db_99e5,
+            0x1fa2_7cf8,
+            0xc4ac_5665,
+            0xf429_2244,
+            0x432a_ff97,
+            0xab94_23a7,
+            0xfc93_a039,
+            0x655b_59c3,
+            0x8f0c_cc92,
+            0xffef_f47d,
+            0x8584_5dd1,
+            0x6fa8_7e4f,
+            0xfe2c_e6e0,
+            0xa301_4314,
+            0x4e08_11a1,
+            0xf753_7e82,
+            0xbd3a_f235,
+            0x2ad7_d2bb,
+            0xeb86_d391,
+        ];
 
-        pub(super) fn with_prefix_and_xor(
-            &self,
-            salted_prefix: &str,
-            salt_len: usize,
+        let mut input = [0; 16];
+        for (word, bytes) in input.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        let initial: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+        let [mut a, mut b, mut c, mut d] = initial;
+        for (i, constant) in CONSTANTS.iter().enumerate() {
+            let (f, index) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(*constant)
+                .wrapping_add(input[index])
+                .rotate_left(SHIFTS[(i / 16) * 4 + i % 4]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        vec![
+            initial[0].wrapping_add(a),
+            initial[1].wrapping_add(b),
+            initial[2].wrapping_add(c),
+            initial[3].wrapping_add(d),
+        ]
+    }
+
+    fn sha256_state(block: &[u8; 64]) -> Vec<u32> {
+        let mut state = [
+            0x6a09_e667,
+            0xbb67_ae85,
+            0x3c6e_f372,
+            0xa54f_f53a,
+            0x510e_527f,
+            0x9b05_688c,
+            0x1f83_d9ab,
+            0x5be0_cd19,
+        ];
+        sha2::compress256(
+            &mut state,
+            &[*digest::generic_array::GenericArray::from_slice(block)],
+        );
+        state.to_vec()
+    }
+
+    pub(super) fn checksum_definition(checksum: options::Checksum) -> i32 {
+        match checksum {
+            options::Checksum::Luhn => 1,
+            options::Checksum::Mod11 => 2,
+            options::Checksum::Mod11Cyclic => 3,
+            options::Checksum::Mod97 => 4,
+        }
+    }
+
+    impl Source {
+        // Applies every transform in order to the `length` bytes from `begin`. The transformed
+        // values must fit the buffer
+        pub(super) fn with_transforms(
+            self,
+            transforms: &options::Pipeline,
+            begin: usize,
             length: usize,
-            xor: &[u8],
-        ) -> Source {
-            let mut prefix_code = String::new();
-            for (i, c) in salted_prefix.chars().enumerate() {
-                prefix_code.push_str(format!("value.bytes[{}] = \'{}\';", i, c).as_str());
+        ) -> Self {
+            let mut injected_code = String::new();
+            let mut length = length;
+            for transform in transforms.transforms() {
+                match transform {
+                    options::Transform::Xor(mask) => {
+                        for (i, c) in mask.iter().take(length).enumerate() {
+                            injected_code
+                                .push_str(format!("value.bytes[{}] ^= {};", i + begin, c).as_str());
+                        }
+                    }
+                    options::Transform::Base64 | options::Transform::Base64Url => {
+                        let map = if *transform == options::Transform::Base64 {
+                            "base64_map"
+                        } else {
+                            "base64url_map"
+                        };
+                        injected_code.push_str(
+                            format!("to_base64(&value, {}, {}, {});", begin, length, map).as_str(),
+                        );
+                    }
+                    options::Transform::Hex => injected_code
+                        .push_str(format!("to_hex(&value, {}, {});", begin, length).as_str()),
+                    options::Transform::Reverse => injected_code
+                        .push_str(format!("reverse(&value, {}, {});", begin, length).as_str()),
+                    options::Transform::Upper => injected_code
+                        .push_str(format!("to_upper(&value, {}, {});", begin, length).as_str()),
+                    options::Transform::Lower => injected_code
+                        .push_str(format!("to_lower(&value, {}, {});", begin, length).as_str()),
+                    options::Transform::Pad(width, fill) => injected_code.push_str(
+                        format!("pad(&value, {}, {}, {}, {});", begin, length, width, fill)
+                            .as_str(),
+                    ),
+                }
+                length = transform.length(length).unwrap_or(length);
+            }
+
+            let mut output = String::new();
+            for line in self.0.lines() {
+                if line.ends_with("// %%TRANSFORM%%") {
+                    output.push_str(injected_code.as_str());
+                } else {
+                    output.push_str(line);
+                }
+                output.push('\n');
             }
 
-            let mut xor_code = String::new();
-            for (i, c) in xor.iter().take(length).enumerate() {
-                xor_code.push_str(format!("value.bytes[{}] ^= {};", i + salt_len, c).as_str());
+            Self(output)
+        }
+
+        // Writes the salted suffix from the character `begin`, once the value is complete
+        pub(super) fn with_suffix(self, salted_suffix: &str, begin: usize, width: usize) -> Self {
+            let mut injected_code = String::new();
+            for (i, byte) in salted_suffix.bytes().enumerate() {
+                injected_code
+                    .push_str(format!("value.bytes[{}] = {};", (i + begin) * width, byte).as_str());
             }
 
             let mut output = String::new();
             for line in self.0.lines() {
-                if line.ends_with("// %%PREFIX%%") {
-                    output.push_str(prefix_code.as_str());
-                } else if line.ends_with("// %%XOR%%") {
-                    output.push_str(xor_code.as_str());
+                if line.ends_with("// %%SUFFIX%%") {
+                    output.push_str(injected_code.as_str());
                 } else {
                     output.push_str(line);
-                };
+                }
                 output.push('\n');
             }
 
-            Source(output)
+            Self(output)
         }
-    }
 
-    impl Source {
         pub(super) fn to_string(&self) -> &String {
             &self.0
         }
@@ -332,6 +770,7 @@ mod source {
     #[cfg(test)]
     mod test {
         use super::SourceTemplate;
+        use crate::options::Transform;
 
         #[test]
         fn test_prefix_injection() {
@@ -345,12 +784,47 @@ Final line"#;
             let expected = r#"
 One line
 Another line
-value.bytes[0] = '0';value.bytes[1] = '1';value.bytes[2] = '2';
+value.bytes[0] = 48;value.bytes[1] = 49;value.bytes[2] = 50;
 // %%PREFIX%% 
 Final line
 "#;
 
-            let output = SourceTemplate(src).with_prefix("012");
+            let output = SourceTemplate(src).with_prefix("012", 1);
+            assert_eq!(output.to_string(), expected);
+        }
+
+        #[test]
+        fn test_utf16_injection() {
+            let src = r"
+// %%PREFIX%%
+// %%SUFFIX%%";
+
+            let expected = r"
+value.bytes[0] = 48;value.bytes[2] = 49;
+value.bytes[10] = 97;value.bytes[12] = 98;
+";
+
+            let output = SourceTemplate(src)
+                .with_prefix("01", 2)
+                .with_suffix("ab", 5, 2);
+            assert_eq!(output.to_string(), expected);
+        }
+
+        #[test]
+        fn test_salt_injection() {
+            let src = r"
+// %%PREFIX%%
+// %%SUFFIX%%";
+
+            // Quotes, backslashes and characters taking several bytes are injected byte by byte
+            let expected = r"
+value.bytes[0] = 39;value.bytes[1] = 92;value.bytes[2] = 195;value.bytes[3] = 169;
+value.bytes[4] = 34;
+";
+
+            let output = SourceTemplate(src)
+                .with_prefix("'\\é", 1)
+                .with_suffix("\"", 4, 1);
             assert_eq!(output.to_string(), expected);
         }
 
@@ -361,21 +835,130 @@ One line
 Another line
 // %%PREFIX%%
 // %%PREFIX%% 
-   // %%XOR%%
+   // %%TRANSFORM%%
 Final line"#;
 
             let expected = r#"
 One line
 Another line
-value.bytes[0] = '0';value.bytes[1] = '1';value.bytes[2] = '2';
+value.bytes[0] = 48;value.bytes[1] = 49;value.bytes[2] = 50;
 // %%PREFIX%% 
-value.bytes[1] ^= 0;value.bytes[2] ^= 1;
+value.bytes[1] ^= 0;value.bytes[2] ^= 1;to_base64(&value, 1, 2, base64_map);to_hex(&value, 1, 4);pad(&value, 1, 8, 10, 48);
 Final line
 "#;
 
-            let output = SourceTemplate(src).with_prefix_and_xor("012", 1, 2, &[0, 1, 2, 3]);
+            let transforms = crate::options::Pipeline::new(vec![
+                Transform::Xor(vec![0, 1, 2, 3]),
+                Transform::Base64,
+                Transform::Hex,
+                Transform::Pad(10, b'0'),
+            ])
+            .unwrap();
+            let output =
+                SourceTemplate(src)
+                    .with_prefix("012", 1)
+                    .with_transforms(&transforms, 1, 2);
             assert_eq!(output.to_string(), expected);
         }
+
+        #[test]
+        fn test_suffix_injection() {
+            let src = r#"
+One line
+// %%PREFIX%%
+  // %%SUFFIX%%
+Final line"#;
+
+            let expected = r#"
+One line
+value.bytes[0] = 48;
+value.bytes[5] = 97;value.bytes[6] = 98;
+Final line
+"#;
+
+            let output = SourceTemplate(src)
+                .with_prefix("0", 1)
+                .with_suffix("ab", 5, 1);
+            assert_eq!(output.to_string(), expected);
+
+            let output = SourceTemplate(src)
+                .with_prefix("0", 1)
+                .with_suffix("", 5, 1);
+            assert_eq!(
+                output.to_string(),
+                "\nOne line\nvalue.bytes[0] = 48;\n\nFinal line\n"
+            );
+        }
+
+        #[test]
+        fn test_hmac_states() {
+            // A lone padding block leaves the state of the hash of an empty value
+            let mut block = [0; 64];
+            block[0] = 0x80;
+            let md5 = super::md5_state(&block)
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect::<Vec<_>>();
+            assert_eq!(md5, <md5::Md5 as digest::Digest>::digest(b"").to_vec());
+            let sha256 = super::sha256_state(&block)
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                sha256,
+                <sha2::Sha256 as digest::Digest>::digest(b"").to_vec()
+            );
+
+            let states = super::hmac_states(crate::hash::Algorithm::md5, b"key").unwrap();
+            assert!(states.starts_with("#define CONST_HMAC_INNER {0x"));
+            assert!(states.contains("}\n#define CONST_HMAC_OUTER {0x"));
+            assert!(super::hmac_states(crate::hash::Algorithm::sha1, b"key").is_none());
+        }
+
+        #[test]
+        fn test_mask_tables() {
+            let mask = crate::options::Mask::parse("a?h?d", &[]).unwrap();
+            let expected = "__constant unsigned char mask_charsets[27] = {97, \
+                            48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 97, 98, 99, 100, 101, 102, \
+                            48, 49, 50, 51, 52, 53, 54, 55, 56, 57};\n\
+                            __constant unsigned short mask_offsets[3] = {0, 1, 17};\n\
+                            __constant unsigned short mask_radixes[3] = {1, 16, 10};\n";
+
+            assert_eq!(super::mask_tables(&mask), expected);
+        }
+
+        #[test]
+        fn test_checksum_tables() {
+            use crate::options::{Checksum, Mask, Segment};
+
+            let segment = Segment::new(String::from("79"), Mask::parse("9?d-?d?d", &[]).unwrap());
+            let (weights, base) = super::checksum_tables(&segment, Checksum::Luhn);
+            assert_eq!(
+                weights,
+                "__constant unsigned char checksum_weights[5] = {0, 2, 0, 1, 2};\n"
+            );
+            // The literal 9 as is, the doubled 9 of the prefix and the 7 of the prefix as is
+            assert_eq!(base, 9 + 9 + 7);
+
+            let segment = Segment::new(String::from("7"), Mask::decimal(2).unwrap());
+            let (weights, base) = super::checksum_tables(&segment, Checksum::Mod97);
+            assert_eq!(
+                weights,
+                "__constant unsigned char checksum_weights[2] = {30, 3};\n"
+            );
+            assert_eq!(base, 7 * 9);
+
+            let dates = crate::options::Dates::parse("YYMMDD", 2000, 2000).unwrap();
+            let segment = Segment::new(String::from("1"), Mask::decimal(1).unwrap())
+                .with_dates(Some(dates))
+                .unwrap();
+            let (weights, base) = super::checksum_tables(&segment, Checksum::Luhn);
+            assert_eq!(
+                weights,
+                "__constant unsigned char checksum_weights[7] = {2, 1, 2, 1, 2, 1, 2};\n"
+            );
+            assert_eq!(base, 1);
+        }
     }
 }
 
@@ -383,7 +966,8 @@ Final line
 mod test {
     #[test]
     fn base64_length() {
-        use super::calculate_base64_len;
+        let calculate_base64_len =
+            |length| crate::options::Transform::Base64.length(length).unwrap();
 
         assert_eq!(calculate_base64_len(0), 0);
         assert_eq!(calculate_base64_len(1), 4);
@@ -397,4 +981,60 @@ mod test {
         assert_eq!(calculate_base64_len(9), 12);
         assert_eq!(calculate_base64_len(10), 16);
     }
+
+    #[test]
+    fn value_length() {
+        use crate::hash;
+
+        assert_eq!(super::value_length::<hash::md4::Hash>(), 64);
+        assert_eq!(super::value_length::<hash::md5::Hash>(), 64);
+        assert_eq!(super::value_length::<hash::sha256::Hash>(), 64);
+        assert_eq!(super::value_length::<hash::sha384::Hash>(), 128);
+        assert_eq!(super::value_length::<hash::sha512::Hash>(), 128);
+    }
+
+    #[test]
+    fn kernel_parameters() {
+        use super::KernelParameters;
+        use crate::options::{Dates, Mask, Segment};
+
+        let decimal = Segment::new(String::from("1"), Mask::decimal(12).unwrap());
+        let parameters = KernelParameters::from(&decimal).unwrap();
+        assert_eq!(parameters.range, 10_000_000);
+        assert_eq!(parameters.cpu_iterations, 100_000);
+        assert_eq!(parameters.length_on_cpu_iterations, 5);
+
+        let short = Segment::new(String::new(), Mask::decimal(3).unwrap());
+        let parameters = KernelParameters::from(&short).unwrap();
+        assert_eq!(parameters.range, 1000);
+        assert_eq!(parameters.cpu_iterations, 1);
+        assert_eq!(parameters.length_on_cpu_iterations, 0);
+
+        let mask = Segment::new(String::new(), Mask::parse("?l?l?l?l?l?d", &[]).unwrap());
+        let parameters = KernelParameters::from(&mask).unwrap();
+        assert_eq!(parameters.range, 26 * 26 * 26 * 26 * 10);
+        assert_eq!(parameters.cpu_iterations, 26);
+        assert_eq!(parameters.length_on_cpu_iterations, 1);
+
+        let large = Segment::new(String::new(), Mask::decimal(17).unwrap());
+        assert!(KernelParameters::from(&large).is_err());
+
+        let dates = Dates::parse("YYYYMMDD", 1930, 2029).unwrap();
+        let day_count = dates.number_space();
+        let dates_on_kernel = Segment::new(String::new(), Mask::decimal(2).unwrap())
+            .with_dates(Some(dates.clone()))
+            .unwrap();
+        let parameters = KernelParameters::from(&dates_on_kernel).unwrap();
+        assert_eq!(u64::from(parameters.range), day_count * 100);
+        assert_eq!(parameters.cpu_iterations, 1);
+        assert_eq!(parameters.length_on_cpu_iterations, 0);
+
+        let dates_on_iterations = Segment::new(String::new(), Mask::decimal(4).unwrap())
+            .with_dates(Some(dates))
+            .unwrap();
+        let parameters = KernelParameters::from(&dates_on_iterations).unwrap();
+        assert_eq!(parameters.range, 10_000);
+        assert_eq!(u64::from(parameters.cpu_iterations), day_count);
+        assert_eq!(parameters.length_on_cpu_iterations, 0);
+    }
 }
@@ -0,0 +1,233 @@
+use crate::error;
+use crate::hash;
+use crate::kdf;
+
+/// What a hash to crack is hashed with, as told by the hash itself
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Format {
+    Hash(hash::Algorithm),
+    Kdf(kdf::Kdf),
+}
+
+impl Format {
+    /// Detects the format of a hash given on its own
+    ///
+    /// Hex hashes are told apart by their length only, so each length stands for the most common
+    /// algorithm giving it: md5, sha1, sha256, sha384 or sha512
+    pub fn detect(hash: &str) -> Option<Self> {
+        if let Some(kdf) = kdf::Kdf::detect(hash) {
+            return Some(Self::Kdf(kdf)).filter(|_| kdf.regex().is_match(hash));
+        }
+        if !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        algorithm(hash.len()).map(Self::Hash)
+    }
+
+    /// Every hash found in the line, wherever it is, along with its format
+    pub fn find_all(line: &str) -> Vec<(Self, &str)> {
+        use lazy_static::lazy_static;
+        lazy_static! {
+            static ref FIELDS: regex::Regex = regex::Regex::new(r"\$[^\s:$]+\$[^\s:]+")
+                .expect("Could not build regex for fields");
+        }
+
+        let mut found = Vec::new();
+        let mut last = 0;
+        for fields in FIELDS.find_iter(line) {
+            found.extend(find_hex(&line[last..fields.start()]));
+            last = fields.end();
+
+            if let Some(kdf) = kdf::Kdf::detect(fields.as_str()) {
+                found.extend(
+                    kdf.regex()
+                        .find_iter(fields.as_str())
+                        .map(|hash| (Self::Kdf(kdf), hash.as_str())),
+                );
+            }
+        }
+        found.extend(find_hex(&line[last..]));
+        found
+    }
+
+    /// Other algorithms giving hashes of the same length, which are only cracked when given
+    pub fn alternatives(self) -> &'static [hash::Algorithm] {
+        match self {
+            Self::Hash(hash::Algorithm::md5) => &[hash::Algorithm::md4],
+            Self::Hash(hash::Algorithm::sha256) => &[
+                hash::Algorithm::sha3_256,
+                hash::Algorithm::keccak256,
+                hash::Algorithm::blake3,
+            ],
+            Self::Hash(hash::Algorithm::sha512) => &[hash::Algorithm::blake2b],
+            _ => &[],
+        }
+    }
+
+    /// Matches the hashes of the format, as written in files
+    pub fn regex(self) -> &'static regex::Regex {
+        match self {
+            Self::Hash(algorithm) => algorithm.regex(),
+            Self::Kdf(kdf) => kdf.regex(),
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hash(algorithm) => algorithm.fmt(fmt),
+            Self::Kdf(kdf) => kdf.fmt(fmt),
+        }
+    }
+}
+
+/// Matches the hashes of any of the formats, so that files mixing them are written at once
+///
+/// The slow hashes are matched whole from their leading `$`, before any hex digits inside them
+pub fn regex(formats: &[Format]) -> Result<regex::Regex, error::Error> {
+    let alternatives = formats
+        .iter()
+        .map(|format| format!("(?:{})", format.regex().as_str()))
+        .collect::<Vec<_>>();
+    regex::Regex::new(&alternatives.join("|"))
+        .map_err(|e| error!(e; "Could not build regex for the detected formats"))
+}
+
+// Hex digits inside the fields of slow hashes are not hashes of their own, so they are skipped
+// before getting here
+fn find_hex(text: &str) -> impl Iterator<Item = (Format, &str)> {
+    use lazy_static::lazy_static;
+    lazy_static! {
+        static ref HEX: regex::Regex =
+            regex::Regex::new(r"\b[0-9a-fA-F]+\b").expect("Could not build regex for hex");
+    }
+
+    HEX.find_iter(text).filter_map(|hash| {
+        algorithm(hash.as_str().len()).map(|algorithm| (Format::Hash(algorithm), hash.as_str()))
+    })
+}
+
+// Algorithms sharing a length, such as md4 and md5, are only picked when given explicitly
+fn algorithm(hex_length: usize) -> Option<hash::Algorithm> {
+    match hex_length {
+        32 => Some(hash::Algorithm::md5),
+        40 => Some(hash::Algorithm::sha1),
+        64 => Some(hash::Algorithm::sha256),
+        96 => Some(hash::Algorithm::sha384),
+        128 => Some(hash::Algorithm::sha512),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Format;
+    use crate::hash;
+    use crate::kdf;
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            Format::detect("a1d0c6e83f027327d8461063f4ac58a6"),
+            Some(Format::Hash(hash::Algorithm::md5))
+        );
+        assert_eq!(
+            Format::detect("92CFCEB39D57D914ED8B14D0E37643DE0797AE56"),
+            Some(Format::Hash(hash::Algorithm::sha1))
+        );
+        assert_eq!(
+            Format::detect("73475cb40a568e8da8a045ced110137e159f890ac4da883b6b17dc651b3a8049"),
+            Some(Format::Hash(hash::Algorithm::sha256))
+        );
+        assert_eq!(
+            Format::detect("$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS"),
+            Some(Format::Kdf(kdf::Kdf::Bcrypt))
+        );
+        assert_eq!(
+            Format::detect("$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71"),
+            Some(Format::Kdf(kdf::Kdf::Md5Crypt))
+        );
+
+        assert_eq!(Format::detect("a1d0c6e83f027327d8461063f4ac58a"), None);
+        assert_eq!(Format::detect("g1d0c6e83f027327d8461063f4ac58a6"), None);
+        assert_eq!(Format::detect("$1$jvc0yf23$96eSePYQ"), None);
+        assert_eq!(Format::detect(""), None);
+    }
+
+    #[test]
+    fn alternatives() {
+        assert_eq!(
+            Format::Hash(hash::Algorithm::md5).alternatives(),
+            &[hash::Algorithm::md4]
+        );
+        assert_eq!(
+            Format::Hash(hash::Algorithm::sha512).alternatives(),
+            &[hash::Algorithm::blake2b]
+        );
+        assert!(Format::Hash(hash::Algorithm::sha1)
+            .alternatives()
+            .is_empty());
+        assert!(Format::Kdf(kdf::Kdf::Bcrypt).alternatives().is_empty());
+
+        // Every alternative has the length of the algorithm detected
+        for algorithm in hash::Algorithm::variants() {
+            let algorithm: hash::Algorithm = algorithm.parse().unwrap();
+            for alternative in Format::Hash(algorithm).alternatives() {
+                assert_eq!(alternative.regex().as_str(), algorithm.regex().as_str());
+            }
+        }
+    }
+
+    #[test]
+    fn find_all() {
+        let line =
+            "root:$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71:19000 a1d0c6e83f027327d8461063f4ac58a6,\
+                    $pbkdf2$1000$a1d0c6e83f027327d8461063f4ac58a6$f/23jm7Vh.CQNNLkJWGAUuM8Xw8 \
+                    92cfceb39d57d914ed8b14d0e37643de0797ae56 0123";
+        assert_eq!(
+            Format::find_all(line),
+            vec![
+                (
+                    Format::Kdf(kdf::Kdf::Md5Crypt),
+                    "$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71"
+                ),
+                (
+                    Format::Hash(hash::Algorithm::md5),
+                    "a1d0c6e83f027327d8461063f4ac58a6"
+                ),
+                (
+                    Format::Kdf(kdf::Kdf::Pbkdf2),
+                    "$pbkdf2$1000$a1d0c6e83f027327d8461063f4ac58a6$f/23jm7Vh.CQNNLkJWGAUuM8Xw8"
+                ),
+                (
+                    Format::Hash(hash::Algorithm::sha1),
+                    "92cfceb39d57d914ed8b14d0e37643de0797ae56"
+                ),
+            ]
+        );
+        assert!(Format::find_all("no hash here").is_empty());
+    }
+
+    #[test]
+    fn regex() {
+        let regex = super::regex(&[
+            Format::Hash(hash::Algorithm::md5),
+            Format::Kdf(kdf::Kdf::Pbkdf2),
+        ])
+        .unwrap();
+        let line = "a1d0c6e83f027327d8461063f4ac58a6 \
+                    $pbkdf2$1000$a1d0c6e83f027327d8461063f4ac58a6$f/23jm7Vh.CQNNLkJWGAUuM8Xw8 \
+                    92cfceb39d57d914ed8b14d0e37643de0797ae56";
+        assert_eq!(
+            regex
+                .find_iter(line)
+                .map(|hash| hash.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "a1d0c6e83f027327d8461063f4ac58a6",
+                "$pbkdf2$1000$a1d0c6e83f027327d8461063f4ac58a6$f/23jm7Vh.CQNNLkJWGAUuM8Xw8",
+            ]
+        );
+    }
+}
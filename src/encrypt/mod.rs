@@ -12,8 +12,168 @@ pub fn execute<H: hash::Hash>(
         if channel.should_terminate() {
             return false;
         }
-        channel.result(input, &format!("{:x}", H::digest(options.salt(), input)));
+        // Every value was checked to be transformable
+        if let Some(plain) = options.transforms().apply(input) {
+            let hash = options.digest::<H>(options.salt_prefix(), &plain, options.salt_suffix());
+            channel.result(input, &format!("{:x}", hash));
+        }
     }
 
     true
 }
+
+#[cfg(test)]
+mod test {
+    use super::{channel, execute, hash, options};
+    use crate::options::SharedAccessor;
+
+    struct Channel(std::sync::Mutex<Vec<(String, String)>>);
+
+    impl channel::Channel for Channel {
+        fn progress(&self, _: u8) {}
+        fn result(&self, input: &str, output: &str) {
+            if let Ok(mut results) = self.0.lock() {
+                results.push((String::from(input), String::from(output)));
+            }
+        }
+        fn should_terminate(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let options = options::Encrypt::<hash::sha256::Hash>::new(
+            std::iter::once(String::from("42")).collect(),
+            String::from("abc"),
+            options::SaltPosition::Prepend,
+            options::Pipeline::xor(vec![1, 2, 3, 4, 5, 6]),
+            None,
+            None,
+            None,
+            String::from("1"),
+            4,
+        )
+        .unwrap();
+
+        let channel = Channel(std::sync::Mutex::new(Vec::new()));
+        assert!(execute(&options, &channel));
+        let results = channel.0.into_inner().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "1042");
+
+        let options = options::DecryptBuilder::<hash::sha256::Hash>::new(
+            std::iter::once(<hash::sha256::Hash as std::convert::From<&str>>::from(
+                &results[0].1,
+            ))
+            .collect(),
+            vec![4],
+        )
+        .device(options::Device::Cpu)
+        .prefixes(vec![String::from("1")])
+        .salt(String::from("abc"))
+        .xor(vec![1, 2, 3, 4, 5, 6])
+        .build()
+        .unwrap();
+
+        let summary =
+            crate::decrypt::execute(&options, &Channel(std::sync::Mutex::new(Vec::new()))).unwrap();
+        assert_eq!(summary.results.len(), 1);
+        assert_eq!(summary.results[0].original(), "1042");
+    }
+
+    #[test]
+    fn test_padding() {
+        let options = options::Encrypt::<hash::md5::Hash>::new(
+            vec![String::from("7"), String::from("123")]
+                .into_iter()
+                .collect(),
+            String::new(),
+            options::SaltPosition::Prepend,
+            options::Pipeline::default(),
+            None,
+            None,
+            None,
+            String::from("ab"),
+            5,
+        )
+        .unwrap();
+
+        let mut input = options.input().iter().cloned().collect::<Vec<_>>();
+        input.sort();
+        assert_eq!(input, vec!["ab007", "ab123"]);
+
+        assert!(options::Encrypt::<hash::md5::Hash>::new(
+            std::iter::once(String::from("1234")).collect(),
+            String::new(),
+            options::SaltPosition::Prepend,
+            options::Pipeline::default(),
+            None,
+            None,
+            None,
+            String::from("ab"),
+            5,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_ntlm() {
+        let options = options::Encrypt::<hash::md4::Hash>::new(
+            std::iter::once(String::from("password")).collect(),
+            String::new(),
+            options::SaltPosition::Prepend,
+            options::Pipeline::default(),
+            options::Encoding::Utf16Le,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let channel = Channel(std::sync::Mutex::new(Vec::new()));
+        assert!(execute(&options, &channel));
+        let results = channel.0.into_inner().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1, "8846f7eaee8fb117ad06bdd830b7586c");
+    }
+
+    #[test]
+    fn test_chain() {
+        let options = options::Encrypt::<hash::sha256::Hash>::new(
+            std::iter::once(String::from("42")).collect(),
+            String::from("abc"),
+            options::SaltPosition::Prepend,
+            options::Pipeline::default(),
+            None,
+            None,
+            options::Chain::parse("sha256(md5($p.$s))", options::Intermediate::Hex).unwrap(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let channel = Channel(std::sync::Mutex::new(Vec::new()));
+        assert!(execute(&options, &channel));
+        let results = channel.0.into_inner().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].1,
+            "a62c9f885665276ddbbe6eab4fe460e53a45b0f62cb36c9e46d2b4ce5088a8fd"
+        );
+
+        assert!(options::Encrypt::<hash::md5::Hash>::new(
+            std::iter::once(String::from("42")).collect(),
+            String::new(),
+            options::SaltPosition::Prepend,
+            options::Pipeline::default(),
+            None,
+            None,
+            options::Chain::parse("sha256(md5($p))", options::Intermediate::Hex).unwrap(),
+            None,
+            None,
+        )
+        .is_err());
+    }
+}
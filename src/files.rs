@@ -1,5 +1,8 @@
+use crate::detect;
 use crate::error;
 use crate::hash;
+use crate::kdf;
+use crate::options;
 use crate::results;
 
 const LINEAR_SEARCH_THRESHOLD: usize = 64;
@@ -44,11 +47,327 @@ pub fn read_from_stream<H: hash::Hash>(
     Ok(())
 }
 
+pub fn read_targets(
+    input: &mut std::collections::HashSet<kdf::Target>,
+    kdf: kdf::Kdf,
+    path: &std::path::Path,
+) -> Result<(), error::Error> {
+    std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map_err(|e| error!(e; "Could not open file: {}", path.display()))
+        .and_then(|stream| read_targets_from_stream(input, kdf, stream))
+}
+
+/// Reads every hash of the slow function found in the stream, wherever it is on its line
+pub fn read_targets_from_stream(
+    input: &mut std::collections::HashSet<kdf::Target>,
+    kdf: kdf::Kdf,
+    stream: impl std::io::BufRead,
+) -> Result<(), error::Error> {
+    let regex = kdf.regex();
+    for line in stream.lines() {
+        let line = line.map_err(|e| error!(e; "Error while reading"))?;
+        for hash in regex.find_iter(&line) {
+            input.insert(kdf::Target::parse(kdf, hash.as_str())?);
+        }
+    }
+    Ok(())
+}
+
+pub fn read_salted<H: hash::Hash>(
+    input: &mut std::collections::HashMap<H, String>,
+    path: &std::path::Path,
+    format: options::SaltFormat,
+) -> Result<(), error::Error> {
+    std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map_err(|e| error!(e; "Could not open file: {}", path.display()))
+        .and_then(|stream| read_salted_from_stream(input, stream, format))
+}
+
+/// Reads one hash and its salt per line, skipping empty lines
+pub fn read_salted_from_stream<H: hash::Hash>(
+    input: &mut std::collections::HashMap<H, String>,
+    stream: impl std::io::BufRead,
+    format: options::SaltFormat,
+) -> Result<(), error::Error> {
+    read_lines(stream, "salted hash", |line| {
+        let (hash, salt) = parse_salted(line, format)?;
+        insert_salted(input, hash, salt)
+    })
+}
+
+/// Adds a hash along with its salt, a hash only being allowed one salt
+pub fn insert_salted<H: hash::Hash>(
+    input: &mut std::collections::HashMap<H, String>,
+    hash: H,
+    salt: String,
+) -> Result<(), error::Error> {
+    match input.entry(hash) {
+        std::collections::hash_map::Entry::Occupied(entry) if *entry.get() != salt => bail!(
+            "Hash {} is already given with the salt '{}'",
+            hash,
+            entry.get()
+        ),
+        std::collections::hash_map::Entry::Occupied(_) => {}
+        std::collections::hash_map::Entry::Vacant(entry) => {
+            entry.insert(salt);
+        }
+    }
+    Ok(())
+}
+
+/// Parses a hash along with its salt, which is kept verbatim apart from the line ending
+pub fn parse_salted<H: hash::Hash>(
+    line: &str,
+    format: options::SaltFormat,
+) -> Result<(H, String), error::Error> {
+    let line = line.trim_end_matches(&['\n', '\r'][..]);
+    let (hash, salt) = format
+        .split(line)
+        .ok_or_else(|| error!("Expected {} but found '{}'", format, line))?;
+    Ok((H::from_str(hash.trim())?, String::from(salt)))
+}
+
+/// Detects the format of the hash of a line with a salt, which can only be of a plain hash
+pub fn detect_salted(
+    line: &str,
+    format: options::SaltFormat,
+) -> Result<detect::Format, error::Error> {
+    let line = line.trim_end_matches(&['\n', '\r'][..]);
+    let (hash, _) = format
+        .split(line)
+        .ok_or_else(|| error!("Expected {} but found '{}'", format, line))?;
+    match detect::Format::detect(hash.trim()) {
+        Some(format @ detect::Format::Hash(_)) => Ok(format),
+        _ => bail!("Could not detect the algorithm of '{}'", hash.trim()),
+    }
+}
+
+pub fn read_detected(
+    input: &mut std::collections::BTreeMap<detect::Format, std::collections::HashSet<String>>,
+    path: &std::path::Path,
+    salted: Option<options::SaltFormat>,
+) -> Result<(), error::Error> {
+    std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map_err(|e| error!(e; "Could not open file: {}", path.display()))
+        .and_then(|stream| read_detected_from_stream(input, stream, salted))
+}
+
+/// Reads every hash whose format is detected, wherever it is on its line, grouped by format
+///
+/// With a salt format, every line is kept whole instead, as a hash and its salt
+pub fn read_detected_from_stream(
+    input: &mut std::collections::BTreeMap<detect::Format, std::collections::HashSet<String>>,
+    stream: impl std::io::BufRead,
+    salted: Option<options::SaltFormat>,
+) -> Result<(), error::Error> {
+    if let Some(format) = salted {
+        return read_lines(stream, "salted hash", |line| {
+            input
+                .entry(detect_salted(line, format)?)
+                .or_default()
+                .insert(String::from(line.trim_end_matches('\r')));
+            Ok(())
+        });
+    }
+
+    for line in stream.lines() {
+        let line = line.map_err(|e| error!(e; "Error while reading"))?;
+        for (format, hash) in detect::Format::find_all(&line) {
+            input.entry(format).or_default().insert(String::from(hash));
+        }
+    }
+    Ok(())
+}
+
+pub fn read_pairs<H: hash::Hash>(
+    input: &mut std::collections::HashMap<H, String>,
+    path: &std::path::Path,
+) -> Result<(), error::Error> {
+    std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map_err(|e| error!(e; "Could not open file: {}", path.display()))
+        .and_then(|stream| read_pairs_from_stream(input, stream))
+}
+
+/// Reads one value and its hash per line, skipping empty lines
+pub fn read_pairs_from_stream<H: hash::Hash>(
+    input: &mut std::collections::HashMap<H, String>,
+    stream: impl std::io::BufRead,
+) -> Result<(), error::Error> {
+    read_lines(stream, "pair", |line| {
+        let (hash, value) = parse_pair(line)?;
+        input.insert(hash, value);
+        Ok(())
+    })
+}
+
+/// Parses a value followed by its hash, as printed when hashing several values
+pub fn parse_pair<H: hash::Hash>(line: &str) -> Result<(H, String), error::Error> {
+    let (value, hash) = split_pair(line, "hash")?;
+    Ok((H::from_str(hash.trim())?, String::from(value)))
+}
+
+pub fn read_encoded_pairs(
+    input: &mut std::collections::HashMap<Vec<u8>, String>,
+    path: &std::path::Path,
+) -> Result<(), error::Error> {
+    std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map_err(|e| error!(e; "Could not open file: {}", path.display()))
+        .and_then(|stream| read_encoded_pairs_from_stream(input, stream))
+}
+
+/// Reads one value and its base64 encoded masked form per line, skipping empty lines
+pub fn read_encoded_pairs_from_stream(
+    input: &mut std::collections::HashMap<Vec<u8>, String>,
+    stream: impl std::io::BufRead,
+) -> Result<(), error::Error> {
+    read_lines(stream, "pair", |line| {
+        let (encoded, value) = parse_encoded_pair(line)?;
+        input.insert(encoded, value);
+        Ok(())
+    })
+}
+
+/// Parses a value followed by its base64 encoded masked form, which is decoded
+pub fn parse_encoded_pair(line: &str) -> Result<(Vec<u8>, String), error::Error> {
+    let (value, encoded) = split_pair(line, "encoded")?;
+    let encoded =
+        base64::decode(encoded.trim()).map_err(|e| error!(e; "Could not decode '{}'", encoded))?;
+    Ok((encoded, String::from(value)))
+}
+
+// Values may contain the separator, unlike what follows them
+fn split_pair<'a>(line: &'a str, right: &str) -> Result<(&'a str, &'a str), error::Error> {
+    let line = line.trim_end_matches(&['\n', '\r'][..]);
+    line.rsplit_once(':')
+        .ok_or_else(|| error!("Expected <value>:<{}> but found '{}'", right, line))
+}
+
+fn read_lines(
+    stream: impl std::io::BufRead,
+    kind: &str,
+    mut read: impl FnMut(&str) -> Result<(), error::Error>,
+) -> Result<(), error::Error> {
+    for (number, line) in stream.lines().enumerate() {
+        let line = line.map_err(|e| error!(e; "Error while reading"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        read(&line).map_err(|e| error!(e; "Invalid {} on line {}", kind, number + 1))?;
+    }
+    Ok(())
+}
+
+/// Streams the words of a wordlist, one per line, keeping track of how much of it was read
+pub struct Words {
+    stream: Box<dyn std::io::BufRead>,
+    size: Option<u64>,
+    read: u64,
+    buffer: Vec<u8>,
+}
+
+impl Words {
+    pub fn open(wordlist: &options::Wordlist) -> Result<Self, error::Error> {
+        match wordlist {
+            options::Wordlist::File(path) => {
+                let file = std::fs::File::open(path)
+                    .map_err(|e| error!(e; "Could not open wordlist: {}", path.display()))?;
+                let size = file.metadata().ok().map(|metadata| metadata.len());
+                Ok(Self::new(std::io::BufReader::new(file), size))
+            }
+            options::Wordlist::Stdin => {
+                Ok(Self::new(std::io::BufReader::new(std::io::stdin()), None))
+            }
+        }
+    }
+
+    pub fn new(stream: impl std::io::BufRead + 'static, size: Option<u64>) -> Self {
+        Self {
+            stream: Box::new(stream),
+            size,
+            read: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Percentage of the wordlist read so far, always 0 if its size is unknown
+    // Allowed because of division; value will stay in bound
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn progress(&self) -> u8 {
+        match self.size {
+            Some(size) if size > 0 => (std::cmp::min(self.read, size) * 100 / size) as u8,
+            _ => 0,
+        }
+    }
+
+    /// Reads up to `count` words, and none once the wordlist is exhausted
+    ///
+    /// Line endings are trimmed, while empty lines and lines that are not valid UTF-8 are skipped
+    pub fn next_batch(&mut self, count: usize) -> Result<Vec<String>, error::Error> {
+        let mut words = Vec::with_capacity(count);
+        while words.len() < count {
+            self.buffer.clear();
+            let bytes = self
+                .stream
+                .read_until(b'\n', &mut self.buffer)
+                .map_err(|e| error!(e; "Error while reading wordlist"))?;
+            if bytes == 0 {
+                break;
+            }
+            self.read += bytes as u64;
+
+            while let Some(b'\n' | b'\r') = self.buffer.last() {
+                self.buffer.pop();
+            }
+            if let Ok(word) = std::str::from_utf8(&self.buffer) {
+                if !word.is_empty() {
+                    words.push(String::from(word));
+                }
+            }
+        }
+        Ok(words)
+    }
+}
+
+pub fn read_rules(path: &std::path::Path) -> Result<Vec<options::Rule>, error::Error> {
+    std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map_err(|e| error!(e; "Could not open rules: {}", path.display()))
+        .and_then(read_rules_from_stream)
+}
+
+/// Reads one rule per line, skipping empty lines and comments starting with `#`
+pub fn read_rules_from_stream(
+    stream: impl std::io::BufRead,
+) -> Result<Vec<options::Rule>, error::Error> {
+    let mut rules = Vec::new();
+    for (number, line) in stream.lines().enumerate() {
+        let line = line.map_err(|e| error!(e; "Error while reading rules"))?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        rules.push(
+            options::Rule::parse(&line)
+                .map_err(|e| error!(e; "Invalid rule on line {}", number + 1))?,
+        );
+    }
+
+    if rules.is_empty() {
+        bail!("No rule found");
+    }
+    Ok(rules)
+}
+
 pub fn write(
     regex: &regex::Regex,
     path: &std::path::Path,
     output: Option<std::path::PathBuf>,
     results: &[results::Pair],
+    emit: options::Emit,
 ) -> Result<(), error::Error> {
     let input = std::fs::File::open(path)
         .map_err(|e| error!(e; "Could not open '{}' for translating", path.display()))?;
@@ -66,7 +385,7 @@ pub fn write(
         )
     })?;
 
-    write_output_file(regex, results, &input, &output, output_path)
+    write_output_file(regex, results, emit, &input, &output, output_path)
 }
 
 fn derive_output_file(input: &std::path::Path) -> Result<std::path::PathBuf, error::Error> {
@@ -106,20 +425,21 @@ impl Lookup for MapLookup<'_> {
     }
 }
 
-struct ListLookup<'a>(&'a [results::Pair]);
+struct ListLookup<'a>(&'a [results::Pair], options::Emit);
 
 impl Lookup for ListLookup<'_> {
     fn find(&self, hash: &str) -> Option<&str> {
         self.0
             .iter()
             .find(|pair| pair.hash == hash)
-            .map(|pair| pair.plain.as_str())
+            .map(|pair| pair.emitted(self.1))
     }
 }
 
 fn write_output_file(
     regex: &regex::Regex,
     results: &[results::Pair],
+    emit: options::Emit,
     input: &std::fs::File,
     output: &std::fs::File,
     output_path: std::path::PathBuf,
@@ -128,12 +448,12 @@ fn write_output_file(
         let lookup = MapLookup(
             results
                 .iter()
-                .map(|pair| (pair.hash.as_str(), pair.plain.as_str()))
+                .map(|pair| (pair.hash.as_str(), pair.emitted(emit)))
                 .collect(),
         );
         write_output_file_e(regex, &lookup, input, output)
     } else {
-        let lookup = ListLookup(results);
+        let lookup = ListLookup(results, emit);
         write_output_file_e(regex, &lookup, input, output)
     }
     .map_err(|e| {
@@ -162,18 +482,13 @@ fn write_output_file_e(
                     return Ok(());
                 }
 
-                let matches = regex
-                    .captures_iter(&buffer)
-                    .filter_map(|capture| capture.get(0).map(|group| group.as_str().to_owned()))
-                    .collect::<Vec<_>>();
-
-                for matched in &matches {
-                    if let Some(plain) = lookup.find(matched.as_str()) {
-                        buffer = buffer.replace(matched.as_str(), plain);
-                    }
-                }
+                // Only the matches are replaced, as the hash of one format may appear inside
+                // the hash of another one, such as a hex salt
+                let line = regex.replace_all(&buffer, |captures: &regex::Captures<'_>| {
+                    lookup.find(&captures[0]).unwrap_or(&captures[0]).to_owned()
+                });
 
-                if let Err(e) = writer.write_all(buffer.as_bytes()) {
+                if let Err(e) = writer.write_all(line.as_bytes()) {
                     bail!(e;  "Failed to write to file");
                 }
             }
@@ -183,3 +498,214 @@ fn write_output_file_e(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        read_detected_from_stream, read_pairs_from_stream, read_rules_from_stream,
+        read_salted_from_stream, read_targets_from_stream, write, Words,
+    };
+    use crate::detect::{self, Format};
+    use crate::hash::{self, Hash};
+    use crate::kdf;
+    use crate::options::SaltFormat;
+
+    #[test]
+    fn words() {
+        let data = b"first\nsecond\r\n\n\xff\xfe\nthird";
+        let mut words = Words::new(std::io::Cursor::new(&data[..]), Some(data.len() as u64));
+
+        assert_eq!(words.progress(), 0);
+        assert_eq!(words.next_batch(2).unwrap(), vec!["first", "second"]);
+        assert_eq!(words.next_batch(2).unwrap(), vec!["third"]);
+        assert_eq!(words.progress(), 100);
+        assert!(words.next_batch(2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rules() {
+        let data = "# Comment\n:\n\nc $1\r\nr\n";
+        let rules = read_rules_from_stream(std::io::Cursor::new(data)).unwrap();
+        let words = rules
+            .iter()
+            .filter_map(|rule| rule.apply("word"))
+            .collect::<Vec<_>>();
+        assert_eq!(words, vec!["word", "Word1", "drow"]);
+
+        assert!(read_rules_from_stream(std::io::Cursor::new(":\nw\n")).is_err());
+        assert!(read_rules_from_stream(std::io::Cursor::new("# Only comments\n")).is_err());
+    }
+
+    #[test]
+    fn salted() {
+        type Hash = crate::hash::md5::Hash;
+
+        let hash = Hash::digest("salt", "value", "");
+        let other = Hash::digest("with:colons", "value", "");
+        let data = format!(
+            "{:x}:salt\n\n{:x}:with:colons\r\n{:x}:salt\n",
+            hash, other, hash
+        );
+        let mut input = std::collections::HashMap::<Hash, _>::new();
+        read_salted_from_stream(&mut input, std::io::Cursor::new(data), SaltFormat::HashSalt)
+            .unwrap();
+        assert_eq!(input.len(), 2);
+        assert_eq!(input[&hash], "salt");
+        assert_eq!(input[&other], "with:colons");
+
+        // A hash given again with another salt would never be searched with the first one
+        let data = format!("{:x}:salt\n{:x}:other\n", hash, hash);
+        let mut input = std::collections::HashMap::<Hash, _>::new();
+        let err =
+            read_salted_from_stream(&mut input, std::io::Cursor::new(data), SaltFormat::HashSalt)
+                .unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+
+        let data = format!("with:colons:{:x}\n", other);
+        let mut input = std::collections::HashMap::<Hash, _>::new();
+        read_salted_from_stream(&mut input, std::io::Cursor::new(data), SaltFormat::SaltHash)
+            .unwrap();
+        assert_eq!(input[&other], "with:colons");
+
+        let mut input = std::collections::HashMap::<Hash, _>::new();
+        assert!(read_salted_from_stream(
+            &mut input,
+            std::io::Cursor::new("no separator\n"),
+            SaltFormat::HashSalt,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn targets() {
+        let hash = "$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS";
+        let data = format!("user:{}:1000\n\n{} {}\n", hash, hash, "$2b$04$short");
+        let mut input = std::collections::HashSet::new();
+        read_targets_from_stream(&mut input, kdf::Kdf::Bcrypt, std::io::Cursor::new(data)).unwrap();
+        assert_eq!(
+            input.into_iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+            vec![hash]
+        );
+
+        let mut input = std::collections::HashSet::new();
+        assert!(read_targets_from_stream(
+            &mut input,
+            kdf::Kdf::Bcrypt,
+            std::io::Cursor::new(hash.replace("$04$", "$99$")),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn detected() {
+        let md5 = "a1d0c6e83f027327d8461063f4ac58a6";
+        let bcrypt = "$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS";
+        let data = format!(
+            "user:{}:1000\n{} {}\nnothing\n{}\n",
+            bcrypt, md5, "abc", md5
+        );
+        let mut input = std::collections::BTreeMap::new();
+        read_detected_from_stream(&mut input, std::io::Cursor::new(data), None).unwrap();
+        assert_eq!(
+            input.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    Format::Hash(hash::Algorithm::md5),
+                    std::iter::once(String::from(md5)).collect()
+                ),
+                (
+                    Format::Kdf(kdf::Kdf::Bcrypt),
+                    std::iter::once(String::from(bcrypt)).collect()
+                ),
+            ]
+        );
+
+        let data = format!("{}:salt\r\n\n{}:salt\n", md5, md5.repeat(2));
+        let mut input = std::collections::BTreeMap::new();
+        read_detected_from_stream(
+            &mut input,
+            std::io::Cursor::new(data),
+            Some(SaltFormat::HashSalt),
+        )
+        .unwrap();
+        assert_eq!(input.len(), 2);
+        assert!(input[&Format::Hash(hash::Algorithm::md5)].contains(&format!("{}:salt", md5)));
+        assert!(input.contains_key(&Format::Hash(hash::Algorithm::sha256)));
+
+        let mut input = std::collections::BTreeMap::new();
+        assert!(read_detected_from_stream(
+            &mut input,
+            std::io::Cursor::new(format!("{}:salt\n", bcrypt)),
+            Some(SaltFormat::HashSalt),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn write_detected() {
+        use crate::options::Emit;
+        use crate::results::Pair;
+
+        let md5 = "a1d0c6e83f027327d8461063f4ac58a6";
+        let sha1 = "92cfceb39d57d914ed8b14d0e37643de0797ae56";
+        let md5crypt = "$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71";
+        let pbkdf2 = "$pbkdf2$1000$a1d0c6e83f027327d8461063f4ac58a6$f/23jm7Vh.CQNNLkJWGAUuM8Xw8";
+        let uncracked = "0cc175b9c0f1b6a831c399e269772661";
+
+        let input = std::env::temp_dir().join("hasher_files_write_detected.txt");
+        let output = std::env::temp_dir().join("hasher_files_write_detected.cracked.txt");
+        std::fs::write(
+            &input,
+            format!(
+                "root:{}:19000\n{},{} {}\n{}\n",
+                md5crypt, md5, sha1, pbkdf2, uncracked
+            ),
+        )
+        .unwrap();
+
+        let regex = detect::regex(&[
+            Format::Hash(hash::Algorithm::md5),
+            Format::Hash(hash::Algorithm::sha1),
+            Format::Kdf(kdf::Kdf::Md5Crypt),
+            Format::Kdf(kdf::Kdf::Pbkdf2),
+        ])
+        .unwrap();
+        let results = [
+            Pair::new(String::from(md5), String::from("123")),
+            Pair::new(String::from(sha1), String::from("42")),
+            Pair::new(String::from(md5crypt), String::from("password")),
+            Pair::new(String::from(pbkdf2), String::from("secret")),
+        ];
+        let written = write(
+            &regex,
+            &input,
+            Some(output.clone()),
+            &results,
+            Emit::Original,
+        );
+        let content = std::fs::read_to_string(&output);
+        let _ignored = std::fs::remove_file(&input);
+        let _ignored = std::fs::remove_file(&output);
+
+        written.unwrap();
+        assert_eq!(
+            content.unwrap(),
+            format!("root:password:19000\n123,42 secret\n{}\n", uncracked)
+        );
+    }
+
+    #[test]
+    fn pairs() {
+        type Hash = crate::hash::sha256::Hash;
+
+        let hash = Hash::digest("", "with:colons", "");
+        let data = format!("\nwith:colons:{:x}\r\n", hash);
+        let mut input = std::collections::HashMap::<Hash, _>::new();
+        read_pairs_from_stream(&mut input, std::io::Cursor::new(data)).unwrap();
+        assert_eq!(input.len(), 1);
+        assert_eq!(input[&hash], "with:colons");
+
+        let mut input = std::collections::HashMap::<Hash, _>::new();
+        assert!(read_pairs_from_stream(&mut input, std::io::Cursor::new("value:abc\n")).is_err());
+    }
+}
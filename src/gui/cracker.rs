@@ -27,12 +27,13 @@ pub struct Cracker {
             length: u8,
             customSalt: bool,
             salt: String,
-            useSha256: bool,
+            algorithm: String,
             autoDevice: bool,
             useGpu: bool,
             useMask: bool,
             customMask: bool,
             maskValue: String,
+            decodeMask: bool,
             input: qmetaobject::QVariantList,
             files: qmetaobject::QVariantList,
         ) -> usize
@@ -89,6 +90,7 @@ impl Cracker {
                 &std::path::PathBuf::from(input),
                 Some(std::path::PathBuf::from(output)),
                 &pairs,
+                options::Emit::Hashed,
             ) {
                 self.error(err.to_string());
             }
@@ -103,41 +105,54 @@ impl Cracker {
         length: u8,
         custom_salt: bool,
         salt: String,
-        use_sha256: bool,
+        algorithm: String,
         auto_device: bool,
         use_gpu: bool,
         use_mask: bool,
         custom_mask: bool,
         mask_value: String,
+        decode_mask: bool,
         input: qmetaobject::QVariantList,
         files: qmetaobject::QVariantList,
     ) -> usize {
-        if use_sha256 {
-            self.crack_algorithm::<hash::sha256::Hash>(
-                prefix,
-                length,
-                custom_salt,
-                salt,
-                auto_device,
-                use_gpu,
-                use_mask,
-                if custom_mask { Some(mask_value) } else { None },
-                input,
-                files,
-            )
+        let emit = if decode_mask {
+            options::Emit::Original
         } else {
-            self.crack_algorithm::<hash::md5::Hash>(
-                prefix,
-                length,
-                custom_salt,
-                salt,
-                auto_device,
-                use_gpu,
-                use_mask,
-                if custom_mask { Some(mask_value) } else { None },
-                input,
-                files,
-            )
+            options::Emit::Hashed
+        };
+        let mask = if custom_mask { Some(mask_value) } else { None };
+        macro_rules! crack {
+            ($hash:ty) => {
+                self.crack_algorithm::<$hash>(
+                    prefix,
+                    length,
+                    custom_salt,
+                    salt,
+                    auto_device,
+                    use_gpu,
+                    use_mask,
+                    mask,
+                    emit,
+                    input,
+                    files,
+                )
+            };
+        }
+        match algorithm.parse() {
+            Ok(hash::Algorithm::md4) => crack!(hash::md4::Hash),
+            Ok(hash::Algorithm::md5) => crack!(hash::md5::Hash),
+            Ok(hash::Algorithm::sha1) => crack!(hash::sha1::Hash),
+            Ok(hash::Algorithm::sha256) => crack!(hash::sha256::Hash),
+            Ok(hash::Algorithm::sha384) => crack!(hash::sha384::Hash),
+            Ok(hash::Algorithm::sha512) => crack!(hash::sha512::Hash),
+            Ok(hash::Algorithm::sha3_256) => crack!(hash::sha3_256::Hash),
+            Ok(hash::Algorithm::keccak256) => crack!(hash::keccak256::Hash),
+            Ok(hash::Algorithm::blake2b) => crack!(hash::blake2b::Hash),
+            Ok(hash::Algorithm::blake3) => crack!(hash::blake3::Hash),
+            Err(err) => {
+                self.error(err.to_string());
+                0
+            }
         }
     }
 
@@ -151,6 +166,7 @@ impl Cracker {
         use_gpu: bool,
         use_mask: bool,
         custom_mask: Option<String>,
+        emit: options::Emit,
         input: qmetaobject::QVariantList,
         files: qmetaobject::QVariantList,
     ) -> usize {
@@ -203,10 +219,11 @@ impl Cracker {
 
         // Allowed because we can't pass `&mut self` to both side of `map_or_else`
         #[allow(clippy::map_unwrap_or)]
-        options::DecryptBuilder::new(input, length)
+        options::DecryptBuilder::new(input, vec![length])
             .device(maybe_device)
+            .emit(emit)
             .files(files)
-            .prefix(prefix)
+            .prefixes(vec![prefix])
             .salt(salt)
             .xor(xor)
             .build()
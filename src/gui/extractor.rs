@@ -7,8 +7,8 @@ use crate::hash;
 #[derive(QObject, Default)]
 pub struct Extractor {
     base: qmetaobject::qt_base_class!(trait QSyntaxHighlighter),
-    useSha256: qmetaobject::qt_property!(bool; NOTIFY onUseSha256Changed),
-    onUseSha256Changed: qmetaobject::qt_signal!(),
+    algorithm: qmetaobject::qt_property!(String; NOTIFY onAlgorithmChanged),
+    onAlgorithmChanged: qmetaobject::qt_signal!(),
     color: qmetaobject::qt_property!(qmetaobject::QColor),
     hashes: qmetaobject::qt_method!(fn(&self, text: String) -> qmetaobject::QVariantList),
 }
@@ -26,10 +26,17 @@ impl QSyntaxHighlighter for Extractor {
 impl Extractor {
     #[inline]
     fn regex(&self) -> &regex::Regex {
-        if self.useSha256 {
-            <hash::sha256::Hash as hash::Hash>::regex()
-        } else {
-            <hash::md5::Hash as hash::Hash>::regex()
+        match self.algorithm.parse() {
+            Ok(hash::Algorithm::md4) => <hash::md4::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::md5) => <hash::md5::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::sha1) => <hash::sha1::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::sha384) => <hash::sha384::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::sha512) => <hash::sha512::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::sha3_256) => <hash::sha3_256::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::keccak256) => <hash::keccak256::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::blake2b) => <hash::blake2b::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::blake3) => <hash::blake3::Hash as hash::Hash>::regex(),
+            Ok(hash::Algorithm::sha256) | Err(_) => <hash::sha256::Hash as hash::Hash>::regex(),
         }
     }
 
@@ -7,7 +7,7 @@ macro_rules! byte_size_of {
 macro_rules! hash {
     ($($name:ident: $size:literal from $algorithm:ty),+) => {
         #[allow(non_camel_case_types)]
-        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
         pub enum Algorithm {
             $($name),*
         }
@@ -16,6 +16,33 @@ macro_rules! hash {
             pub fn variants() -> &'static [&'static str] {
                 &[$(stringify!($name)),*]
             }
+
+            /// Hash of the bytes, of the size of the algorithm
+            pub fn digest(self, bytes: &[u8]) -> Vec<u8> {
+                match self {
+                    $(Self::$name => $name::digest(bytes),)*
+                }
+            }
+
+            /// Matches the hashes of the algorithm, as written in files
+            pub fn regex(self) -> &'static regex::Regex {
+                use $crate::hash::Hash;
+                match self {
+                    $(Self::$name => $name::Hash::regex(),)*
+                }
+            }
+        }
+
+        impl std::str::FromStr for Algorithm {
+            type Err = $crate::error::Error;
+
+            /// Parses an algorithm by its name, regardless of the case
+            fn from_str(string: &str) -> Result<Self, Self::Err> {
+                $(if string.eq_ignore_ascii_case(stringify!($name)) {
+                    return Ok(Self::$name);
+                })*
+                bail!("possible values are [{}]", Self::variants().join(", "))
+            }
         }
 
         impl std::fmt::Display for Algorithm {
@@ -33,20 +60,50 @@ macro_rules! hash {
             unsafe impl ocl::OclPrm for Hash {}
             impl $crate::Input for Hash {}
 
+            pub(super) fn digest(bytes: &[u8]) -> Vec<u8> {
+                <$algorithm as digest::Digest>::digest(bytes).to_vec()
+            }
+
             impl $crate::hash::Hash for Hash {
                 fn algorithm() -> super::Algorithm {
                     super::Algorithm::$name
                 }
 
-                fn digest(salted_prefix: &str, number: &str) -> Self {
+                fn digest(salted_prefix: &str, number: &str, salted_suffix: &str) -> Self {
                     use digest::Digest;
                     let mut digest = <$algorithm>::new();
                     digest.update(salted_prefix.as_bytes());
                     digest.update(number.as_bytes());
+                    digest.update(salted_suffix.as_bytes());
                     let result = digest.finalize();
                     Self::from_array(result)
                 }
 
+                fn digest_encoded(
+                    salted_prefix: &str,
+                    number: &str,
+                    salted_suffix: &str,
+                    encoding: $crate::options::Encoding,
+                ) -> Self {
+                    let mut digest = <$algorithm as digest::Digest>::new();
+                    super::update(&mut digest, salted_prefix, number, salted_suffix, encoding);
+                    Self::from_array(digest::Digest::finalize(digest))
+                }
+
+                fn digest_hmac(
+                    key: &[u8],
+                    salted_prefix: &str,
+                    number: &str,
+                    salted_suffix: &str,
+                    encoding: $crate::options::Encoding,
+                ) -> Self {
+                    use hmac::Mac;
+                    let mut mac = <hmac::SimpleHmac<$algorithm> as Mac>::new_from_slice(key)
+                        .expect("HMAC takes keys of any length");
+                    super::update(&mut mac, salted_prefix, number, salted_suffix, encoding);
+                    Self::from_array(mac.finalize().into_bytes())
+                }
+
                 fn from_array<N: digest::generic_array::ArrayLength<u8>>(
                     bytes: digest::generic_array::GenericArray<u8, N>,
                 ) -> Self {
@@ -57,6 +114,12 @@ macro_rules! hash {
                     Self(data)
                 }
 
+                fn from_slice(bytes: &[u8]) -> Self {
+                    let mut data = [0; byte_size_of!($size)];
+                    data.copy_from_slice(bytes);
+                    Self(data)
+                }
+
                 fn from_str(string: &str) -> Result<Self, $crate::error::Error> {
                     if string.len() != $size >> 2 {
                         bail!("String does not fit into hash: '{}'", &string);
@@ -236,22 +299,78 @@ macro_rules! hash {
                 fn digestion() {
                     use $crate::hash::Hash;
                     use digest::Digest;
-                    let hash = super::Hash::digest("123", "abc");
+                    let hash = super::Hash::digest("123", "abc", "456");
+
+                    let mut expected_hash = <$algorithm>::new();
+                    expected_hash.update("123abc456".as_bytes());
+
+                    assert_eq!(
+                        format!("{:x}", hash),
+                        format!("{:x}", expected_hash.finalize())
+                    );
+                }
+
+                #[test]
+                fn utf16_digestion() {
+                    use $crate::hash::Hash;
+                    use digest::Digest;
+                    let hash = super::Hash::digest_encoded(
+                        "123",
+                        "abc",
+                        "456",
+                        $crate::options::Encoding::Utf16Le,
+                    );
 
                     let mut expected_hash = <$algorithm>::new();
-                    expected_hash.update("123".as_bytes());
-                    expected_hash.update("abc".as_bytes());
+                    expected_hash.update(b"1\x002\x003\x00a\x00b\x00c\x004\x005\x006\x00");
 
                     assert_eq!(
                         format!("{:x}", hash),
                         format!("{:x}", expected_hash.finalize())
                     );
+                    assert_eq!(
+                        super::Hash::digest_encoded("123", "abc", "456", $crate::options::Encoding::Utf8),
+                        super::Hash::digest("123", "abc", "456")
+                    );
+                }
+
+                #[test]
+                fn hmac_digestion() {
+                    use $crate::hash::Hash;
+                    use hmac::Mac;
+                    let hash = super::Hash::digest_hmac(
+                        b"key",
+                        "123",
+                        "abc",
+                        "456",
+                        $crate::options::Encoding::Utf8,
+                    );
+
+                    let mut expected_hash =
+                        <hmac::SimpleHmac<$algorithm> as Mac>::new_from_slice(b"key").unwrap();
+                    expected_hash.update("123abc456".as_bytes());
+
+                    assert_eq!(
+                        format!("{:x}", hash),
+                        format!("{:x}", expected_hash.finalize().into_bytes())
+                    );
+                }
+
+                #[test]
+                fn algorithm_digestion() {
+                    use $crate::hash::Hash;
+                    let bytes = super::super::Algorithm::$name.digest(b"123abc456");
+
+                    assert_eq!(
+                        super::Hash::from_slice(&bytes),
+                        super::Hash::digest("123", "abc", "456")
+                    );
                 }
 
                 #[test]
                 fn regex() {
                     use $crate::hash::Hash;
-                    let hash = super::Hash::digest("123", "abc");
+                    let hash = super::Hash::digest("123", "abc", "");
                     let regex = super::Hash::regex();
 
                     assert!(regex.is_match(&hash.to_string()));
@@ -267,10 +386,25 @@ macro_rules! hash {
 }
 
 pub trait Hash: ocl::OclPrm + std::fmt::LowerHex + std::fmt::Binary + crate::Input {
-    fn digest(salted_prefix: &str, number: &str) -> Self;
+    fn digest(salted_prefix: &str, number: &str, salted_suffix: &str) -> Self;
+    fn digest_encoded(
+        salted_prefix: &str,
+        number: &str,
+        salted_suffix: &str,
+        encoding: crate::options::Encoding,
+    ) -> Self;
+    fn digest_hmac(
+        key: &[u8],
+        salted_prefix: &str,
+        number: &str,
+        salted_suffix: &str,
+        encoding: crate::options::Encoding,
+    ) -> Self;
     fn from_array<N: digest::generic_array::ArrayLength<u8>>(
         bytes: digest::generic_array::GenericArray<u8, N>,
     ) -> Self;
+    /// Panics if the bytes are not of the size of the hash
+    fn from_slice(bytes: &[u8]) -> Self;
     fn from_str(string: &str) -> Result<Self, crate::error::Error>;
     fn regex() -> &'static regex::Regex;
     fn name() -> &'static str;
@@ -278,4 +412,41 @@ pub trait Hash: ocl::OclPrm + std::fmt::LowerHex + std::fmt::Binary + crate::Inp
     fn algorithm() -> Algorithm;
 }
 
-hash!(md5: 128 from md5::Md5, sha256: 256 from sha2::Sha256);
+// Feeds the salted value to the hasher, every character taking two bytes with UTF-16LE
+fn update(
+    hasher: &mut impl digest::Update,
+    salted_prefix: &str,
+    number: &str,
+    salted_suffix: &str,
+    encoding: crate::options::Encoding,
+) {
+    match encoding {
+        crate::options::Encoding::Utf8 => {
+            hasher.update(salted_prefix.as_bytes());
+            hasher.update(number.as_bytes());
+            hasher.update(salted_suffix.as_bytes());
+        }
+        crate::options::Encoding::Utf16Le => {
+            for unit in salted_prefix
+                .encode_utf16()
+                .chain(number.encode_utf16())
+                .chain(salted_suffix.encode_utf16())
+            {
+                hasher.update(&unit.to_le_bytes());
+            }
+        }
+    }
+}
+
+hash!(
+    md4: 128 from md4::Md4,
+    md5: 128 from md5::Md5,
+    sha1: 160 from sha1::Sha1,
+    sha256: 256 from sha2::Sha256,
+    sha384: 384 from sha2::Sha384,
+    sha512: 512 from sha2::Sha512,
+    sha3_256: 256 from sha3::Sha3_256,
+    keccak256: 256 from sha3::Keccak256,
+    blake2b: 512 from blake2::Blake2b512,
+    blake3: 256 from blake3::Hasher
+);
@@ -0,0 +1,206 @@
+/// Alphabet of the checksums of crypt, which is not the one of base64
+const ITOA64: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Order in which the bytes of the hashes are encoded, three bytes at a time
+const MD5_ORDER: [usize; 16] = [0, 6, 12, 1, 7, 13, 2, 8, 14, 3, 9, 15, 4, 10, 5, 11];
+const SHA256_ORDER: [usize; 32] = [
+    0, 10, 20, 21, 1, 11, 12, 22, 2, 3, 13, 23, 24, 4, 14, 15, 25, 5, 6, 16, 26, 27, 7, 17, 18, 28,
+    8, 9, 19, 29, 31, 30,
+];
+const SHA512_ORDER: [usize; 64] = [
+    0, 21, 42, 22, 43, 1, 44, 2, 23, 3, 24, 45, 25, 46, 4, 47, 5, 26, 6, 27, 48, 28, 49, 7, 50, 8,
+    29, 9, 30, 51, 31, 52, 10, 53, 11, 32, 12, 33, 54, 34, 55, 13, 56, 14, 35, 15, 36, 57, 37, 58,
+    16, 59, 17, 38, 18, 39, 60, 40, 61, 19, 62, 20, 41, 63,
+];
+
+/// Rounds of sha256crypt and sha512crypt when the hash does not give them
+pub const DEFAULT_ROUNDS: u32 = 5000;
+pub const MIN_ROUNDS: u32 = 1000;
+pub const MAX_ROUNDS: u32 = 999_999_999;
+
+/// Checksum of md5crypt, as written after the salt
+pub fn md5(value: &[u8], salt: &[u8]) -> Vec<u8> {
+    use digest::Digest;
+
+    let alternate = md5::Md5::new()
+        .chain_update(value)
+        .chain_update(salt)
+        .chain_update(value)
+        .finalize();
+
+    let mut digest = md5::Md5::new()
+        .chain_update(value)
+        .chain_update(b"$1$")
+        .chain_update(salt);
+    for chunk in value.chunks(alternate.len()) {
+        digest.update(&alternate[..chunk.len()]);
+    }
+    let mut length = value.len();
+    while length > 0 {
+        if length & 1 == 0 {
+            digest.update(&value[..1]);
+        } else {
+            digest.update([0]);
+        }
+        length >>= 1;
+    }
+    let mut hash = digest.finalize();
+
+    for round in 0..1000 {
+        let mut digest = md5::Md5::new();
+        if round & 1 == 0 {
+            digest.update(hash);
+        } else {
+            digest.update(value);
+        }
+        if round % 3 != 0 {
+            digest.update(salt);
+        }
+        if round % 7 != 0 {
+            digest.update(value);
+        }
+        if round & 1 == 0 {
+            digest.update(value);
+        } else {
+            digest.update(hash);
+        }
+        hash = digest.finalize();
+    }
+
+    encode(&hash, &MD5_ORDER)
+}
+
+/// Checksum of sha256crypt, as written after the salt
+pub fn sha256(value: &[u8], salt: &[u8], rounds: u32) -> Vec<u8> {
+    encode(&sha::<sha2::Sha256>(value, salt, rounds), &SHA256_ORDER)
+}
+
+/// Checksum of sha512crypt, as written after the salt
+pub fn sha512(value: &[u8], salt: &[u8], rounds: u32) -> Vec<u8> {
+    encode(&sha::<sha2::Sha512>(value, salt, rounds), &SHA512_ORDER)
+}
+
+// The construction shared by sha256crypt and sha512crypt, only differing by their hash
+fn sha<D: digest::Digest>(value: &[u8], salt: &[u8], rounds: u32) -> Vec<u8> {
+    let alternate = D::new()
+        .chain_update(value)
+        .chain_update(salt)
+        .chain_update(value)
+        .finalize();
+
+    let mut digest = D::new().chain_update(value).chain_update(salt);
+    for chunk in value.chunks(alternate.len()) {
+        digest.update(&alternate[..chunk.len()]);
+    }
+    let mut length = value.len();
+    while length > 0 {
+        if length & 1 == 0 {
+            digest.update(value);
+        } else {
+            digest.update(&alternate);
+        }
+        length >>= 1;
+    }
+    let mut hash = digest.finalize().to_vec();
+
+    let mut digest = D::new();
+    for _ in 0..value.len() {
+        digest.update(value);
+    }
+    let value = repeat(&digest.finalize(), value.len());
+
+    let mut digest = D::new();
+    for _ in 0..16 + usize::from(hash[0]) {
+        digest.update(salt);
+    }
+    let salt = repeat(&digest.finalize(), salt.len());
+
+    for round in 0..rounds {
+        let mut digest = D::new();
+        if round & 1 == 0 {
+            digest.update(&hash);
+        } else {
+            digest.update(&value);
+        }
+        if round % 3 != 0 {
+            digest.update(&salt);
+        }
+        if round % 7 != 0 {
+            digest.update(&value);
+        }
+        if round & 1 == 0 {
+            digest.update(&value);
+        } else {
+            digest.update(&hash);
+        }
+        hash = digest.finalize().to_vec();
+    }
+    hash
+}
+
+// The bytes repeated up to the length, the last repetition being cut short
+fn repeat(bytes: &[u8], length: usize) -> Vec<u8> {
+    bytes.iter().copied().cycle().take(length).collect()
+}
+
+// Every three bytes become four characters, starting with the lowest six bits of the last byte,
+// while the one or two bytes left at the end take one character more than their count
+fn encode(hash: &[u8], order: &[usize]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(order.len() * 4 / 3 + 1);
+    for group in order.chunks(3) {
+        let mut bits = group
+            .iter()
+            .fold(0_u32, |bits, index| (bits << 8) | u32::from(hash[*index]));
+        for _ in 0..=group.len() {
+            encoded.push(ITOA64[(bits & 0x3f) as usize]);
+            bits >>= 6;
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    fn check(checksum: Vec<u8>, expected: &str) {
+        assert_eq!(String::from_utf8(checksum).unwrap(), expected);
+    }
+
+    #[test]
+    fn md5() {
+        check(super::md5(b"42", b"saltstri"), "9k85FFygDK/X4ENwCRkhP1");
+        check(
+            super::md5(b"Hello world!", b"saltstri"),
+            "YMyguxXMBpd2TEZ.vS/3q1",
+        );
+        check(super::md5(b"", b"saltstri"), "ciR2otLVXV8I9sOPWbLTc1");
+        check(super::md5(b"0042", b""), "0Xri7EuVpyFw.aYLr2cdM.");
+    }
+
+    #[test]
+    fn sha256() {
+        check(
+            super::sha256(b"Hello world!", b"saltstring", 5000),
+            "5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5",
+        );
+        check(
+            super::sha256(b"42", b"roundstoolow", 1000),
+            "GnDj00W.0UsmwZxoFkUMJeuECOTDXGsNHJyUCLD8qp7",
+        );
+        check(
+            super::sha256(b"", b"saltstring", 5000),
+            "FdNfA4gXqvCeO6iZs7G/.wwwoywYZqo0l1pwmfWaBA7",
+        );
+    }
+
+    #[test]
+    fn sha512() {
+        check(
+            super::sha512(b"Hello world!", b"saltstring", 5000),
+            "svn8UoSVapNtMuq1ukKS4tPQd8iKwSMHWjl/O817G3uBnIFNjnQJuesI68u4OTLiBFdcbYEdFCoEOfaS35inz1",
+        );
+        check(
+            super::sha512(b"42", b"jvc0yf23", 1000),
+            "ELhFT6xTgyNv4a2/f38OvnR7aoxzy4seprYlWmOg8xaRcx6/NKDmUEOdD3hevgR51cRQslYktxofhcE16EHIJ.",
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use crate::channel;
+use crate::decrypt;
+use crate::error;
+use crate::files;
+use crate::options;
+use crate::results;
+
+mod crypt;
+mod target;
+
+pub use target::{Kdf, Target};
+
+use decrypt::Sender;
+
+/// Slow hashes computed by every thread between two progress reports
+const HASHES_PER_THREAD: u64 = 16;
+
+/// Hashes every candidate with the salt and the cost of every target, on the CPU
+pub fn execute(
+    options: &options::KdfDecrypt,
+    channel: &impl channel::Channel,
+) -> Result<results::Summary, error::Error> {
+    let time = std::time::Instant::now();
+
+    let count = std::sync::atomic::AtomicUsize::new(options.targets().len());
+    let cracked = options
+        .targets()
+        .iter()
+        .map(|_| std::sync::atomic::AtomicBool::new(false))
+        .collect::<Vec<_>>();
+    let mut candidates = Candidates::open(options)?;
+
+    // Every candidate is hashed for every target
+    // Allowed because the batch size is far below usize::MAX
+    #[allow(clippy::cast_possible_truncation)]
+    let batch_size = std::cmp::max(
+        u64::from(options.threads()) * HASHES_PER_THREAD / (options.targets().len() as u64),
+        1,
+    ) as usize;
+
+    let mut hash_count = 0;
+    let mut results = Vec::with_capacity(options.targets().len());
+
+    channel.progress(0);
+    while !channel.should_terminate() && count.load(std::sync::atomic::Ordering::Acquire) > 0 {
+        let batch = candidates.next_batch(batch_size)?;
+        if batch.is_empty() {
+            break;
+        }
+
+        let (batch_count, batch_results) =
+            execute_candidates(options, &batch, &count, &cracked, channel)?;
+        hash_count += batch_count;
+        results.extend(batch_results);
+        channel.progress(candidates.progress());
+    }
+
+    Ok(results::Summary {
+        total_count: options.targets().len(),
+        duration: time.elapsed(),
+        hash_count,
+        threads: u32::from(options.threads()),
+        results,
+    })
+}
+
+/// Candidates of the keyspace following every word of the wordlist, or following nothing without
+/// a wordlist, read in batches
+struct Candidates<'a> {
+    options: &'a options::KdfDecrypt,
+    words: Option<files::Words>,
+    // Words mangled by the rules, the first of which is followed by the keyspace being read
+    pending: std::collections::VecDeque<String>,
+    segment: usize,
+    index: u64,
+    read: u64,
+}
+
+impl<'a> Candidates<'a> {
+    fn open(options: &'a options::KdfDecrypt) -> Result<Self, error::Error> {
+        let (words, pending) = match options.wordlist() {
+            Some(wordlist) => (
+                Some(files::Words::open(wordlist)?),
+                std::collections::VecDeque::new(),
+            ),
+            None => (None, std::iter::once(String::new()).collect()),
+        };
+
+        Ok(Self {
+            options,
+            words,
+            pending,
+            segment: 0,
+            index: 0,
+            read: 0,
+        })
+    }
+
+    fn next_batch(&mut self, count: usize) -> Result<Vec<String>, error::Error> {
+        let mut batch = Vec::with_capacity(count);
+        while batch.len() < count {
+            let word = match (self.pending.front(), &mut self.words) {
+                (Some(word), _) => word,
+                (None, Some(words)) => {
+                    let words = words.next_batch(count)?;
+                    if words.is_empty() {
+                        break;
+                    }
+                    let options = self.options;
+                    self.pending
+                        .extend(words.iter().flat_map(|word| options.candidates(word)));
+                    continue;
+                }
+                (None, None) => break,
+            };
+
+            let segment = &self.options.segments()[self.segment];
+            batch.push(if word.is_empty() {
+                format!("{}{}", segment.prefix(), segment.candidate(self.index))
+            } else {
+                let segment = segment.with_word(word);
+                format!("{}{}", segment.prefix(), segment.candidate(self.index))
+            });
+
+            self.read += 1;
+            self.index += 1;
+            if self.index == segment.number_space() {
+                self.index = 0;
+                self.segment += 1;
+                if self.segment == self.options.segments().len() {
+                    self.segment = 0;
+                    self.pending.pop_front();
+                }
+            }
+        }
+        Ok(batch)
+    }
+
+    // Allowed because of division; value will stay in bound
+    #[allow(clippy::cast_possible_truncation)]
+    fn progress(&self) -> u8 {
+        match &self.words {
+            Some(words) => words.progress(),
+            None => (self.read * 100 / std::cmp::max(self.options.number_space(), 1)) as u8,
+        }
+    }
+}
+
+// Every candidate is hashed for every target, and these combinations are split between the
+// threads
+// Allowed because the `Sender` needs a sized type to point to
+#[allow(clippy::ptr_arg)]
+fn execute_candidates(
+    options: &options::KdfDecrypt,
+    candidates: &Vec<String>,
+    count: &std::sync::atomic::AtomicUsize,
+    cracked: &Vec<std::sync::atomic::AtomicBool>,
+    channel: &impl channel::Channel,
+) -> Result<(u64, Vec<results::Pair>), error::Error> {
+    let targets = options.targets().len();
+    let space = candidates.len() * targets;
+    let thread_count = usize::from(options.threads());
+    let thread_space = (space + thread_count - 1) / thread_count;
+    let mut threads = Vec::<_>::with_capacity(thread_count);
+
+    for t in 0..thread_count {
+        let options_sender = Sender(options);
+        let candidates_sender = Sender(candidates);
+        let count_sender = Sender(count);
+        let cracked_sender = Sender(cracked);
+        let channel_sender = Sender(channel);
+
+        let first = std::cmp::min(t * thread_space, space);
+        let last = std::cmp::min(first + thread_space, space);
+
+        threads.push(std::thread::spawn(move || {
+            let options = options_sender;
+            let candidates = candidates_sender;
+            let count = count_sender;
+            let cracked = cracked_sender;
+            let channel = channel_sender;
+            let mut hash_count = 0;
+            let mut decrypted = Vec::new();
+
+            for unit in first..last {
+                // Every hash is slow enough to check for termination before it
+                if channel.should_terminate()
+                    || count.load(std::sync::atomic::Ordering::Relaxed) == 0
+                {
+                    return (hash_count, decrypted);
+                }
+
+                let index = unit % targets;
+                if cracked[index].load(std::sync::atomic::Ordering::Relaxed) {
+                    continue;
+                }
+
+                let original = &candidates[unit / targets];
+                let plain = match options.transforms().apply(original) {
+                    Some(plain) => plain,
+                    None => continue,
+                };
+
+                hash_count += 1;
+                let target = &options.targets()[index];
+                if !target.verify(&options.encoding().encode(&plain)) {
+                    continue;
+                }
+
+                // Several candidates may reproduce a hash, each is only reported once
+                if cracked[index].swap(true, std::sync::atomic::Ordering::AcqRel) {
+                    continue;
+                }
+
+                let remaining = count.fetch_sub(1, std::sync::atomic::Ordering::Release);
+                let pair = results::Pair::transformed(target.to_string(), plain, original.clone());
+                channel.result(&pair.hash, pair.emitted(options.emit()));
+                decrypted.push(pair);
+                if remaining == 1 {
+                    return (hash_count, decrypted);
+                }
+            }
+            (hash_count, decrypted)
+        }));
+    }
+
+    threads
+        .into_iter()
+        .map(|t| t.join().map_err(error::on_join))
+        .fold(Ok((0, Vec::new())), |acc, curr| {
+            if let Ok(mut acc) = acc {
+                curr.map(|(count, results)| {
+                    (acc.0 + count, {
+                        acc.1.extend(results);
+                        acc.1
+                    })
+                })
+            } else {
+                acc
+            }
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{channel, execute, options, results, Kdf, Target};
+
+    #[derive(Copy, Clone)]
+    struct Channel;
+
+    impl channel::Channel for Channel {
+        fn progress(&self, _: u8) {}
+        fn result(&self, _: &str, _: &str) {}
+        fn should_terminate(&self) -> bool {
+            false
+        }
+    }
+
+    fn targets(kdf: Kdf, hashes: &[&str]) -> std::collections::HashSet<Target> {
+        hashes
+            .iter()
+            .map(|hash| Target::parse(kdf, hash).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_pbkdf2() {
+        let hashes = [
+            "$pbkdf2-sha256$1000$jvc.yf23op8BxA$7l5zJlaZdBaDYlCYN0En0pi13wYEcr8cESw613deTKg",
+            "$pbkdf2-sha512$i=500,l=64$jvc+yf23op8BxA$1IpEbQwuEuleYoWRxfcR4Xike+fCxkgHH9qCrNcVZ0hcpK8Afm14hwomaqL7surriRZmWqKIJ5yFsm5+hBdTkw",
+        ];
+        let options =
+            options::KdfDecryptBuilder::new(Kdf::Pbkdf2, targets(Kdf::Pbkdf2, &hashes), vec![2, 4])
+                .threads(4)
+                .build()
+                .unwrap();
+
+        let summary = execute(&options, &Channel).unwrap();
+        let mut results = summary.results;
+        results.sort();
+
+        assert_eq!(summary.total_count, 2);
+        assert_eq!(
+            results,
+            vec![
+                results::Pair::new(String::from(hashes[0]), String::from("0042")),
+                results::Pair::new(String::from(hashes[1]), String::from("31")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bcrypt() {
+        let hash = "$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS";
+        let options =
+            options::KdfDecryptBuilder::new(Kdf::Bcrypt, targets(Kdf::Bcrypt, &[hash]), vec![])
+                .masks(vec![options::Mask::parse("4?d", &[]).unwrap()])
+                .threads(2)
+                .build()
+                .unwrap();
+
+        assert_eq!(
+            execute(&options, &Channel).unwrap().results,
+            vec![results::Pair::new(String::from(hash), String::from("42"))]
+        );
+    }
+
+    #[test]
+    fn test_scrypt_transformed() {
+        // Hash of "42" once reversed
+        let hash =
+            "$scrypt$ln=4,r=8,p=1$jvc+yf23op8BxA$RkAYzWIejgda+MGKsJ85VDKbdV/Ve8Lt3URr1hmjDzQ";
+        let options =
+            options::KdfDecryptBuilder::new(Kdf::Scrypt, targets(Kdf::Scrypt, &[hash]), vec![2])
+                .transforms(vec![options::Transform::Reverse])
+                .threads(4)
+                .build()
+                .unwrap();
+
+        assert_eq!(
+            execute(&options, &Channel).unwrap().results,
+            vec![results::Pair::transformed(
+                String::from(hash),
+                String::from("42"),
+                String::from("24")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_argon2_wordlist() {
+        let hash =
+            "$argon2id$v=19$m=64,t=1,p=1$c2FsdHNhbHQ$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY";
+        let path = std::env::temp_dir().join("hasher_kdf_argon2_wordlist.txt");
+        std::fs::write(&path, "1\n2\n3\n4\n5\n").unwrap();
+
+        let options =
+            options::KdfDecryptBuilder::new(Kdf::Argon2, targets(Kdf::Argon2, &[hash]), vec![])
+                .masks(vec![options::Mask::parse("?d", &[]).unwrap()])
+                .wordlist(options::Wordlist::File(path.clone()))
+                .threads(4)
+                .build()
+                .unwrap();
+        let results = execute(&options, &Channel).unwrap().results;
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            results,
+            vec![results::Pair::new(String::from(hash), String::from("42"))]
+        );
+    }
+
+    #[test]
+    fn test_crypt() {
+        let hashes = [
+            "$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71",
+            "$5$rounds=1000$roundstoolow$GnDj00W.0UsmwZxoFkUMJeuECOTDXGsNHJyUCLD8qp7",
+            "$6$rounds=1000$jvc0yf23$ELhFT6xTgyNv4a2/f38OvnR7aoxzy4seprYlWmOg8xaRcx6/NKDmUEOdD3hevgR51cRQslYktxofhcE16EHIJ.",
+        ];
+        let crack = |kdf, hash: &str, lengths| {
+            let options = options::KdfDecryptBuilder::new(kdf, targets(kdf, &[hash]), lengths)
+                .threads(4)
+                .build()
+                .unwrap();
+            execute(&options, &Channel).unwrap().results
+        };
+
+        assert_eq!(
+            crack(Kdf::Md5Crypt, hashes[0], vec![2]),
+            vec![results::Pair::new(
+                String::from(hashes[0]),
+                String::from("42")
+            )]
+        );
+        assert_eq!(
+            crack(Kdf::Sha256Crypt, hashes[1], vec![2]),
+            vec![results::Pair::new(
+                String::from(hashes[1]),
+                String::from("42")
+            )]
+        );
+        assert_eq!(
+            crack(Kdf::Sha512Crypt, hashes[2], vec![2]),
+            vec![results::Pair::new(
+                String::from(hashes[2]),
+                String::from("42")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_not_found() {
+        let hash = "$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS";
+        let options =
+            options::KdfDecryptBuilder::new(Kdf::Bcrypt, targets(Kdf::Bcrypt, &[hash]), vec![2])
+                .prefixes(vec![String::from("5")])
+                .threads(4)
+                .build()
+                .unwrap();
+
+        let summary = execute(&options, &Channel).unwrap();
+
+        assert!(summary.results.is_empty());
+        assert_eq!(summary.hash_count, 10);
+    }
+}
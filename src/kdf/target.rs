@@ -0,0 +1,663 @@
+use crate::error;
+
+use super::crypt;
+
+/// Slow password hashing functions, whose hashes carry their own salt and cost
+#[derive(PartialEq, Eq, Ord, PartialOrd, Hash, Debug, Copy, Clone)]
+pub enum Kdf {
+    Pbkdf2,
+    Bcrypt,
+    Scrypt,
+    Argon2,
+    Md5Crypt,
+    Sha256Crypt,
+    Sha512Crypt,
+}
+
+impl Kdf {
+    pub fn variants() -> &'static [&'static str] {
+        &[
+            "pbkdf2",
+            "bcrypt",
+            "scrypt",
+            "argon2",
+            "md5crypt",
+            "sha256crypt",
+            "sha512crypt",
+        ]
+    }
+
+    /// The function whose hashes are identified as the hash is, by their first field
+    pub fn detect(hash: &str) -> Option<Self> {
+        match hash.strip_prefix('$')?.split('$').next()? {
+            "1" => Some(Self::Md5Crypt),
+            "2a" | "2b" | "2x" | "2y" => Some(Self::Bcrypt),
+            "5" => Some(Self::Sha256Crypt),
+            "6" => Some(Self::Sha512Crypt),
+            "scrypt" => Some(Self::Scrypt),
+            id if id.starts_with("pbkdf2") => Some(Self::Pbkdf2),
+            id if id.starts_with("argon2") => Some(Self::Argon2),
+            _ => None,
+        }
+    }
+
+    /// Matches the hashes of the function, as modular crypt or PHC strings
+    pub fn regex(self) -> &'static regex::Regex {
+        use lazy_static::lazy_static;
+        lazy_static! {
+            static ref PBKDF2: regex::Regex = regex::Regex::new(
+                r"\$pbkdf2(?:-sha(?:1|256|512))?\$(?:\d+|i=\d+(?:,l=\d+)?)\$[./+A-Za-z0-9]+\$[./+A-Za-z0-9]+"
+            )
+            .expect("Could not build regex for pbkdf2");
+            static ref BCRYPT: regex::Regex =
+                regex::Regex::new(r"\$2[abxy]\$\d{2}\$[./A-Za-z0-9]{53}")
+                    .expect("Could not build regex for bcrypt");
+            static ref SCRYPT: regex::Regex = regex::Regex::new(
+                r"\$scrypt\$ln=\d+,r=\d+,p=\d+\$[./+A-Za-z0-9]+\$[./+A-Za-z0-9]+"
+            )
+            .expect("Could not build regex for scrypt");
+            static ref ARGON2: regex::Regex = regex::Regex::new(
+                r"\$argon2(?:id|i|d)\$(?:v=\d+\$)?m=\d+,t=\d+,p=\d+\$[+/A-Za-z0-9]+\$[+/A-Za-z0-9]+"
+            )
+            .expect("Could not build regex for argon2");
+            static ref MD5_CRYPT: regex::Regex =
+                regex::Regex::new(r"\$1\$[./0-9A-Za-z]{0,8}\$[./0-9A-Za-z]{22}")
+                    .expect("Could not build regex for md5crypt");
+            static ref SHA256_CRYPT: regex::Regex = regex::Regex::new(
+                r"\$5\$(?:rounds=\d+\$)?[./0-9A-Za-z]{0,16}\$[./0-9A-Za-z]{43}"
+            )
+            .expect("Could not build regex for sha256crypt");
+            static ref SHA512_CRYPT: regex::Regex = regex::Regex::new(
+                r"\$6\$(?:rounds=\d+\$)?[./0-9A-Za-z]{0,16}\$[./0-9A-Za-z]{86}"
+            )
+            .expect("Could not build regex for sha512crypt");
+        }
+
+        match self {
+            Self::Pbkdf2 => &PBKDF2,
+            Self::Bcrypt => &BCRYPT,
+            Self::Scrypt => &SCRYPT,
+            Self::Argon2 => &ARGON2,
+            Self::Md5Crypt => &MD5_CRYPT,
+            Self::Sha256Crypt => &SHA256_CRYPT,
+            Self::Sha512Crypt => &SHA512_CRYPT,
+        }
+    }
+}
+
+impl std::str::FromStr for Kdf {
+    type Err = error::Error;
+
+    /// Parses a function by its name, regardless of the case
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string.to_lowercase().as_str() {
+            "pbkdf2" => Ok(Self::Pbkdf2),
+            "bcrypt" => Ok(Self::Bcrypt),
+            "scrypt" => Ok(Self::Scrypt),
+            "argon2" => Ok(Self::Argon2),
+            "md5crypt" => Ok(Self::Md5Crypt),
+            "sha256crypt" => Ok(Self::Sha256Crypt),
+            "sha512crypt" => Ok(Self::Sha512Crypt),
+            _ => bail!("possible values are [{}]", Self::variants().join(", ")),
+        }
+    }
+}
+
+impl std::fmt::Display for Kdf {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pbkdf2 => write!(fmt, "pbkdf2"),
+            Self::Bcrypt => write!(fmt, "bcrypt"),
+            Self::Scrypt => write!(fmt, "scrypt"),
+            Self::Argon2 => write!(fmt, "argon2"),
+            Self::Md5Crypt => write!(fmt, "md5crypt"),
+            Self::Sha256Crypt => write!(fmt, "sha256crypt"),
+            Self::Sha512Crypt => write!(fmt, "sha512crypt"),
+        }
+    }
+}
+
+/// Pseudorandom function of PBKDF2, named after the hash of its HMAC
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum Prf {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// How the key of a hash is derived from the value, along with the cost
+#[derive(Clone, Debug)]
+enum Function {
+    Pbkdf2(Prf, u32),
+    Bcrypt(u32),
+    Scrypt(scrypt::Params),
+    Argon2(argon2::Algorithm, argon2::Version, argon2::Params),
+    Md5Crypt,
+    Sha256Crypt(u32),
+    Sha512Crypt(u32),
+}
+
+/// A hash of a slow function, along with the salt and the cost its key was derived with
+///
+/// The keys of the crypt formats are kept as the checksums written in the hashes, and their salts
+/// as written as well. Hashes are compared as the strings they were parsed from
+#[derive(Clone, Debug)]
+pub struct Target {
+    hash: String,
+    function: Function,
+    salt: Vec<u8>,
+    key: Vec<u8>,
+}
+
+impl Target {
+    /// Parses a hash of the function, as `$<id>$<cost>$<salt>$<key>`, the salt and the key being
+    /// encoded in base64 without padding
+    ///
+    /// Bcrypt hashes are `$2b$<cost>$<salt><key>` instead, while argon2 hashes carry their version
+    /// as well. The crypt formats are `$1$<salt>$<checksum>`, `$5$[rounds=<rounds>$]<salt>$<checksum>`
+    /// and the same with `$6$`
+    pub fn parse(kdf: Kdf, hash: &str) -> Result<Self, error::Error> {
+        let fields = hash.split('$').collect::<Vec<_>>();
+        let parsed = match (kdf, fields.as_slice()) {
+            (Kdf::Pbkdf2, ["", id, rounds, salt, key]) => {
+                pbkdf2(id, rounds).and_then(|function| Ok((function, decode(salt)?, decode(key)?)))
+            }
+            (Kdf::Bcrypt, ["", "2a" | "2b" | "2x" | "2y", cost, encoded])
+                if encoded.len() == 53 && encoded.is_ascii() =>
+            {
+                let (salt, key) = encoded.split_at(22);
+                bcrypt(cost).and_then(|function| {
+                    Ok((function, decode_bcrypt(salt, 16)?, decode_bcrypt(key, 23)?))
+                })
+            }
+            (Kdf::Scrypt, ["", "scrypt", params, salt, key]) => {
+                scrypt(params).and_then(|function| Ok((function, decode(salt)?, decode(key)?)))
+            }
+            (Kdf::Argon2, ["", id, version, params, salt, key]) => {
+                argon2(id, Some(version), params, salt, key)
+            }
+            (Kdf::Argon2, ["", id, params, salt, key]) => argon2(id, None, params, salt, key),
+            (Kdf::Md5Crypt, ["", "1", salt, checksum]) => crypt_fields(salt, checksum, 8, 22)
+                .map(|(salt, key)| (Function::Md5Crypt, salt, key)),
+            (Kdf::Sha256Crypt, ["", "5", fields @ ..]) => sha_crypt(fields, 43)
+                .map(|(rounds, salt, key)| (Function::Sha256Crypt(rounds), salt, key)),
+            (Kdf::Sha512Crypt, ["", "6", fields @ ..]) => sha_crypt(fields, 86)
+                .map(|(rounds, salt, key)| (Function::Sha512Crypt(rounds), salt, key)),
+            _ => bail!("'{}' is not a {} hash", hash, kdf),
+        };
+
+        let (function, salt, key) =
+            parsed.map_err(|err| error!(err; "Invalid {} hash '{}'", kdf, hash))?;
+        if key.is_empty() {
+            bail!("Invalid {} hash '{}': empty key", kdf, hash);
+        }
+
+        Ok(Self {
+            hash: String::from(hash),
+            function,
+            salt,
+            key,
+        })
+    }
+
+    /// Whether the key of the hash is derived from the value
+    pub fn verify(&self, value: &[u8]) -> bool {
+        let mut key = vec![0; self.key.len()];
+        let derived = match &self.function {
+            Function::Pbkdf2(Prf::Sha1, rounds) => {
+                pbkdf2::pbkdf2::<hmac::Hmac<sha1::Sha1>>(value, &self.salt, *rounds, &mut key);
+                true
+            }
+            Function::Pbkdf2(Prf::Sha256, rounds) => {
+                pbkdf2::pbkdf2::<hmac::Hmac<sha2::Sha256>>(value, &self.salt, *rounds, &mut key);
+                true
+            }
+            Function::Pbkdf2(Prf::Sha512, rounds) => {
+                pbkdf2::pbkdf2::<hmac::Hmac<sha2::Sha512>>(value, &self.salt, *rounds, &mut key);
+                true
+            }
+            Function::Bcrypt(cost) => {
+                // The value is hashed along with its terminating null byte, up to 72 bytes
+                let password = value
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(0))
+                    .take(72)
+                    .collect::<Vec<_>>();
+                let mut salt = [0; 16];
+                salt.copy_from_slice(&self.salt);
+                key.copy_from_slice(&bcrypt::bcrypt(*cost, salt, &password)[..23]);
+                true
+            }
+            Function::Scrypt(params) => scrypt::scrypt(value, &self.salt, params, &mut key).is_ok(),
+            Function::Argon2(algorithm, version, params) => {
+                argon2::Argon2::new(*algorithm, *version, params.clone())
+                    .hash_password_into(value, &self.salt, &mut key)
+                    .is_ok()
+            }
+            Function::Md5Crypt => {
+                key = crypt::md5(value, &self.salt);
+                true
+            }
+            Function::Sha256Crypt(rounds) => {
+                key = crypt::sha256(value, &self.salt, *rounds);
+                true
+            }
+            Function::Sha512Crypt(rounds) => {
+                key = crypt::sha512(value, &self.salt, *rounds);
+                true
+            }
+        };
+        derived && key == self.key
+    }
+}
+
+impl PartialEq for Target {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl Eq for Target {}
+
+impl std::hash::Hash for Target {
+    fn hash<S: std::hash::Hasher>(&self, state: &mut S) {
+        self.hash.hash(state);
+    }
+}
+
+impl PartialOrd for Target {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Target {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.hash.cmp(&other.hash)
+    }
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.hash.fmt(fmt)
+    }
+}
+
+// Base64 without padding, in which passlib writes '.' instead of '+'
+fn decode(field: &str) -> Result<Vec<u8>, error::Error> {
+    base64::decode_config(field.replace('.', "+"), base64::STANDARD_NO_PAD)
+        .map_err(|err| error!(err; "could not decode '{}'", field))
+}
+
+// The alphabet of bcrypt leaves unused bits after the last byte
+fn decode_bcrypt(field: &str, length: usize) -> Result<Vec<u8>, error::Error> {
+    match base64::decode_config(field, base64::BCRYPT.decode_allow_trailing_bits(true)) {
+        Ok(bytes) if bytes.len() == length => Ok(bytes),
+        Ok(_) => bail!("'{}' is not {} bytes long", field, length),
+        Err(err) => bail!(err; "could not decode '{}'", field),
+    }
+}
+
+// The value of a `<name>=<value>` parameter, among parameters separated by ','
+fn parameter(params: &str, name: &str) -> Result<u32, error::Error> {
+    let value = params
+        .split(',')
+        .find_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+        .ok_or_else(|| error!("missing parameter '{}'", name))?;
+    value
+        .parse::<u32>()
+        .map_err(|err| error!(err; "invalid parameter '{}'", name))
+}
+
+// Rounds are given alone by passlib, or as the `i` parameter
+fn pbkdf2(id: &str, rounds: &str) -> Result<Function, error::Error> {
+    let prf = match id {
+        "pbkdf2" | "pbkdf2-sha1" => Prf::Sha1,
+        "pbkdf2-sha256" => Prf::Sha256,
+        "pbkdf2-sha512" => Prf::Sha512,
+        _ => bail!("unknown function '{}'", id),
+    };
+    let rounds = if rounds.starts_with("i=") {
+        parameter(rounds, "i")?
+    } else {
+        rounds
+            .parse::<u32>()
+            .map_err(|err| error!(err; "invalid rounds '{}'", rounds))?
+    };
+    if rounds == 0 {
+        bail!("rounds must be positive");
+    }
+    Ok(Function::Pbkdf2(prf, rounds))
+}
+
+fn bcrypt(cost: &str) -> Result<Function, error::Error> {
+    match cost.parse::<u32>() {
+        Ok(cost) if (4..=31).contains(&cost) => Ok(Function::Bcrypt(cost)),
+        _ => bail!("invalid cost '{}'", cost),
+    }
+}
+
+fn scrypt(params: &str) -> Result<Function, error::Error> {
+    let log_n: u8 = std::convert::TryFrom::try_from(parameter(params, "ln")?)
+        .map_err(|err| error!(err; "invalid parameter 'ln'"))?;
+    scrypt::Params::new(log_n, parameter(params, "r")?, parameter(params, "p")?)
+        .map(Function::Scrypt)
+        .map_err(|err| error!(err; "invalid parameters '{}'", params))
+}
+
+// Hashes of the first version do not give it
+fn argon2(
+    id: &str,
+    version: Option<&str>,
+    params: &str,
+    salt: &str,
+    key: &str,
+) -> Result<(Function, Vec<u8>, Vec<u8>), error::Error> {
+    let algorithm = id
+        .parse::<argon2::Algorithm>()
+        .map_err(|err| error!(err; "unknown function '{}'", id))?;
+    let version = match version {
+        Some(version) => {
+            let number = parameter(version, "v")?;
+            std::convert::TryFrom::try_from(number)
+                .map_err(|err| error!(err; "unknown version {}", number))?
+        }
+        None => argon2::Version::V0x10,
+    };
+
+    let salt = decode(salt)?;
+    if salt.len() < argon2::MIN_SALT_LEN {
+        bail!("salt is shorter than {} bytes", argon2::MIN_SALT_LEN);
+    }
+    let key = decode(key)?;
+    let params = argon2::Params::new(
+        parameter(params, "m")?,
+        parameter(params, "t")?,
+        parameter(params, "p")?,
+        Some(key.len()),
+    )
+    .map_err(|err| error!(err; "invalid parameters '{}'", params))?;
+
+    Ok((Function::Argon2(algorithm, version, params), salt, key))
+}
+
+// The salt and the checksum of a crypt format, as written
+fn crypt_fields(
+    salt: &str,
+    checksum: &str,
+    salt_length: usize,
+    checksum_length: usize,
+) -> Result<(Vec<u8>, Vec<u8>), error::Error> {
+    let is_crypt64 = |field: &str| {
+        field
+            .bytes()
+            .all(|byte| byte == b'.' || byte == b'/' || byte.is_ascii_alphanumeric())
+    };
+    if salt.len() > salt_length || !is_crypt64(salt) {
+        bail!("invalid salt '{}'", salt);
+    }
+    if checksum.len() != checksum_length || !is_crypt64(checksum) {
+        bail!("invalid checksum '{}'", checksum);
+    }
+    Ok((salt.as_bytes().to_vec(), checksum.as_bytes().to_vec()))
+}
+
+// Rounds are only given when they are not the default ones
+fn sha_crypt(
+    fields: &[&str],
+    checksum_length: usize,
+) -> Result<(u32, Vec<u8>, Vec<u8>), error::Error> {
+    let (rounds, salt, checksum) = match fields {
+        [salt, checksum] => (crypt::DEFAULT_ROUNDS, salt, checksum),
+        [rounds, salt, checksum] => (parameter(rounds, "rounds")?, salt, checksum),
+        _ => bail!("expected an optional rounds parameter, a salt and a checksum"),
+    };
+    if !(crypt::MIN_ROUNDS..=crypt::MAX_ROUNDS).contains(&rounds) {
+        bail!(
+            "rounds must be between {} and {}",
+            crypt::MIN_ROUNDS,
+            crypt::MAX_ROUNDS
+        );
+    }
+    let (salt, key) = crypt_fields(salt, checksum, 16, checksum_length)?;
+    Ok((rounds, salt, key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Kdf, Target};
+
+    fn verify(kdf: Kdf, hash: &str, value: &str) -> bool {
+        Target::parse(kdf, hash).unwrap().verify(value.as_bytes())
+    }
+
+    #[test]
+    fn pbkdf2() {
+        let hash = "$pbkdf2-sha256$1000$jvc.yf23op8BxA$7l5zJlaZdBaDYlCYN0En0pi13wYEcr8cESw613deTKg";
+        assert!(verify(Kdf::Pbkdf2, hash, "0042"));
+        assert!(!verify(Kdf::Pbkdf2, hash, "0043"));
+        assert!(verify(
+            Kdf::Pbkdf2,
+            "$pbkdf2$1000$jvc.yf23op8BxA$f/23jm7Vh.CQNNLkJWGAUuM8Xw8",
+            "0042"
+        ));
+        assert!(verify(
+            Kdf::Pbkdf2,
+            "$pbkdf2-sha512$i=500,l=64$jvc+yf23op8BxA$1IpEbQwuEuleYoWRxfcR4Xike+fCxkgHH9qCrNcVZ0hcpK8Afm14hwomaqL7surriRZmWqKIJ5yFsm5+hBdTkw",
+            "31"
+        ));
+
+        assert!(Target::parse(Kdf::Pbkdf2, "$pbkdf2-md5$1000$jvc.yf23op8BxA$f/23jm7Vh").is_err());
+        assert!(Target::parse(Kdf::Pbkdf2, "$pbkdf2$0$jvc.yf23op8BxA$f/23jm7Vh").is_err());
+        assert!(Target::parse(Kdf::Pbkdf2, "$pbkdf2$1000$jvc.yf23op8BxA$").is_err());
+        assert!(Target::parse(Kdf::Pbkdf2, "$pbkdf2$1000$jvc.yf23op8BxA").is_err());
+    }
+
+    #[test]
+    fn bcrypt() {
+        assert!(verify(
+            Kdf::Bcrypt,
+            "$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS",
+            "42"
+        ));
+        assert!(!verify(
+            Kdf::Bcrypt,
+            "$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS",
+            "43"
+        ));
+        assert!(verify(
+            Kdf::Bcrypt,
+            "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW",
+            "U*U"
+        ));
+
+        assert!(Target::parse(
+            Kdf::Bcrypt,
+            "$2b$03$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS"
+        )
+        .is_err());
+        assert!(Target::parse(
+            Kdf::Bcrypt,
+            "$2c$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS"
+        )
+        .is_err());
+        assert!(Target::parse(
+            Kdf::Bcrypt,
+            "$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWch"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn scrypt() {
+        let hash =
+            "$scrypt$ln=4,r=8,p=1$jvc+yf23op8BxA$RkAYzWIejgda+MGKsJ85VDKbdV/Ve8Lt3URr1hmjDzQ";
+        assert!(verify(Kdf::Scrypt, hash, "42"));
+        assert!(!verify(Kdf::Scrypt, hash, "24"));
+
+        assert!(
+            Target::parse(Kdf::Scrypt, "$scrypt$ln=4,r=8$jvc+yf23op8BxA$RkAYzWIejgda").is_err()
+        );
+        assert!(Target::parse(
+            Kdf::Scrypt,
+            "$scrypt$ln=x,r=8,p=1$jvc+yf23op8BxA$RkAYzWIejgda"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn argon2() {
+        assert!(verify(
+            Kdf::Argon2,
+            "$argon2id$v=19$m=64,t=1,p=1$c2FsdHNhbHQ$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY",
+            "42"
+        ));
+        assert!(verify(
+            Kdf::Argon2,
+            "$argon2d$v=19$m=64,t=1,p=1$c2FsdHNhbHQ$QODC4sQkDy2EK8TCejI7225R5Ee5EwLS2OzTQTDbdSg",
+            "42"
+        ));
+        assert!(verify(
+            Kdf::Argon2,
+            "$argon2i$v=19$m=256,t=2,p=2$c29tZXNhbHQ$T/XOJ2mh1/TIpJHfCdQan76Q5esCFVoT5MAeIM1Oq2E",
+            "password"
+        ));
+        assert!(!verify(
+            Kdf::Argon2,
+            "$argon2id$v=19$m=64,t=1,p=1$c2FsdHNhbHQ$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY",
+            "24"
+        ));
+
+        assert!(Target::parse(
+            Kdf::Argon2,
+            "$argon2x$v=19$m=64,t=1,p=1$c2FsdHNhbHQ$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY"
+        )
+        .is_err());
+        assert!(Target::parse(
+            Kdf::Argon2,
+            "$argon2id$v=20$m=64,t=1,p=1$c2FsdHNhbHQ$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY"
+        )
+        .is_err());
+        assert!(Target::parse(
+            Kdf::Argon2,
+            "$argon2id$v=19$m=64,t=1,p=1$c2FsdA$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn crypt() {
+        assert!(verify(
+            Kdf::Md5Crypt,
+            "$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71",
+            "42"
+        ));
+        assert!(!verify(
+            Kdf::Md5Crypt,
+            "$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71",
+            "24"
+        ));
+        assert!(verify(Kdf::Md5Crypt, "$1$$0Xri7EuVpyFw.aYLr2cdM.", "0042"));
+        assert!(verify(
+            Kdf::Sha256Crypt,
+            "$5$jvc0yf23op8BxA12$JMOutf54B2ZdhEWzylK5su1ilh7VcV.3HUGJSjkKZZ7",
+            "42"
+        ));
+        assert!(verify(
+            Kdf::Sha256Crypt,
+            "$5$rounds=1000$roundstoolow$GuiV66hogEDtCh/eKPj39bCCl162v3k9pDc8L6HsSxD",
+            "0042"
+        ));
+        assert!(verify(
+            Kdf::Sha512Crypt,
+            "$6$rounds=1000$jvc0yf23$ELhFT6xTgyNv4a2/f38OvnR7aoxzy4seprYlWmOg8xaRcx6/NKDmUEOdD3hevgR51cRQslYktxofhcE16EHIJ.",
+            "42"
+        ));
+        assert!(verify(
+            Kdf::Sha512Crypt,
+            "$6$saltstring$vjvMH4KlnHOZx.rt.Mkjk0YsCqu9C6R.ucS5vr557uVBykmrXh5Pu806/AjHGs/nVKAqtPjta97j8P.kVhL6z0",
+            "0042"
+        ));
+
+        assert!(Target::parse(Kdf::Md5Crypt, "$1$jvc0yf23x$96eSePYQhMHSNx6K0RyJ71").is_err());
+        assert!(Target::parse(Kdf::Md5Crypt, "$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ7").is_err());
+        assert!(Target::parse(Kdf::Md5Crypt, "$5$jvc0yf23$96eSePYQhMHSNx6K0RyJ71").is_err());
+        assert!(Target::parse(
+            Kdf::Sha256Crypt,
+            "$5$rounds=999$roundstoolow$GuiV66hogEDtCh/eKPj39bCCl162v3k9pDc8L6HsSxD"
+        )
+        .is_err());
+        assert!(Target::parse(
+            Kdf::Sha256Crypt,
+            "$5$rounds=x$roundstoolow$GuiV66hogEDtCh/eKPj39bCCl162v3k9pDc8L6HsSxD"
+        )
+        .is_err());
+        assert!(Target::parse(
+            Kdf::Sha256Crypt,
+            "$5$saltstring$vjvMH4KlnHOZx.rt.Mkjk0YsCqu9C6R.ucS5vr557uVBykmrXh5Pu806/AjHGs/nVKAqtPjta97j8P.kVhL6z0"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            Kdf::detect(
+                "$pbkdf2-sha256$1000$jvc.yf23op8BxA$7l5zJlaZdBaDYlCYN0En0pi13wYEcr8cESw613deTKg"
+            ),
+            Some(Kdf::Pbkdf2)
+        );
+        assert_eq!(
+            Kdf::detect("$2y$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS"),
+            Some(Kdf::Bcrypt)
+        );
+        assert_eq!(Kdf::detect("$argon2id$v=19$m=64"), Some(Kdf::Argon2));
+        assert_eq!(Kdf::detect("$scrypt$ln=4"), Some(Kdf::Scrypt));
+        assert_eq!(Kdf::detect("$1$jvc0yf23$96eSePYQ"), Some(Kdf::Md5Crypt));
+        assert_eq!(Kdf::detect("$5$rounds=1000$x$y"), Some(Kdf::Sha256Crypt));
+        assert_eq!(Kdf::detect("$6$x$y"), Some(Kdf::Sha512Crypt));
+        assert_eq!(Kdf::detect("$7$x$y"), None);
+        assert_eq!(Kdf::detect("1$x$y"), None);
+    }
+
+    #[test]
+    fn regex() {
+        let line = "1:$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS:x \
+                    $argon2id$v=19$m=64,t=1,p=1$c2FsdHNhbHQ$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY";
+        let matches = |kdf: Kdf| {
+            kdf.regex()
+                .find_iter(line)
+                .map(|m| m.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            matches(Kdf::Bcrypt),
+            vec!["$2b$04$WUHhXETkX0fnYkrqZU3ta.88yl28ENudZ8CfDGP4wkvYbIH5UWchS"]
+        );
+        assert_eq!(
+            matches(Kdf::Argon2),
+            vec!["$argon2id$v=19$m=64,t=1,p=1$c2FsdHNhbHQ$5mZz759NuleLMMAvtb8z3wEggJWYRx0CSpAkAPwlvdY"]
+        );
+        assert!(matches(Kdf::Pbkdf2).is_empty());
+        assert!(matches(Kdf::Scrypt).is_empty());
+        assert!(Kdf::Pbkdf2.regex().is_match(
+            "$pbkdf2-sha256$1000$jvc.yf23op8BxA$7l5zJlaZdBaDYlCYN0En0pi13wYEcr8cESw613deTKg"
+        ));
+        assert!(Kdf::Scrypt.regex().is_match(
+            "$scrypt$ln=4,r=8,p=1$jvc+yf23op8BxA$RkAYzWIejgda+MGKsJ85VDKbdV/Ve8Lt3URr1hmjDzQ"
+        ));
+
+        let shadow = "root:$5$rounds=1000$roundstoolow$GuiV66hogEDtCh/eKPj39bCCl162v3k9pDc8L6HsSxD:19000:0:99999:7:::";
+        assert_eq!(
+            Kdf::Sha256Crypt.regex().find(shadow).map(|m| m.as_str()),
+            Some("$5$rounds=1000$roundstoolow$GuiV66hogEDtCh/eKPj39bCCl162v3k9pDc8L6HsSxD")
+        );
+        assert!(Kdf::Md5Crypt.regex().find(shadow).is_none());
+        assert!(Kdf::Sha512Crypt.regex().find(shadow).is_none());
+        assert!(Kdf::Md5Crypt
+            .regex()
+            .is_match("user:$1$jvc0yf23$96eSePYQhMHSNx6K0RyJ71:19000::::::"));
+    }
+}
@@ -7,12 +7,16 @@ mod error;
 mod channel;
 mod cli;
 mod decrypt;
+mod detect;
 mod encrypt;
 mod files;
 mod hash;
+mod kdf;
 mod options;
 mod results;
+mod salt;
 mod secrets;
+mod xor;
 
 #[cfg(feature = "qml")]
 mod gui;
@@ -0,0 +1,371 @@
+use crate::error;
+use crate::hash;
+
+use super::{Encoding, SaltPosition};
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// How the hashes inside a chain are hashed by the hash enclosing them
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Intermediate {
+    /// As lowercase hex digits
+    Hex,
+    /// As the bytes of the hash
+    Raw,
+}
+
+impl Intermediate {
+    pub fn variants() -> &'static [&'static str] {
+        &["hex", "raw"]
+    }
+
+    fn encode(self, hash: Vec<u8>) -> Vec<u8> {
+        match self {
+            Self::Hex => {
+                let mut hex = Vec::with_capacity(hash.len() * 2);
+                for byte in hash {
+                    hex.push(HEX[usize::from(byte >> 4)]);
+                    hex.push(HEX[usize::from(byte & 0xf)]);
+                }
+                hex
+            }
+            Self::Raw => hash,
+        }
+    }
+}
+
+impl std::fmt::Display for Intermediate {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hex => write!(fmt, "hex"),
+            Self::Raw => write!(fmt, "raw"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Term {
+    /// `$p`
+    Value,
+    /// `$s`
+    Salt,
+    Hash(Call),
+}
+
+impl std::fmt::Display for Term {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Value => write!(fmt, "$p"),
+            Self::Salt => write!(fmt, "$s"),
+            Self::Hash(call) => call.fmt(fmt),
+        }
+    }
+}
+
+/// Hash of the concatenated terms, hashed again by the same algorithm for every extra round
+#[derive(Clone, Debug, PartialEq)]
+struct Call {
+    algorithm: hash::Algorithm,
+    rounds: u32,
+    terms: Vec<Term>,
+}
+
+impl Call {
+    fn evaluate(&self, value: &[u8], salt: &[u8], intermediate: Intermediate) -> Vec<u8> {
+        let mut input = Vec::new();
+        for term in &self.terms {
+            match term {
+                Term::Value => input.extend_from_slice(value),
+                Term::Salt => input.extend_from_slice(salt),
+                Term::Hash(call) => {
+                    input.extend(intermediate.encode(call.evaluate(value, salt, intermediate)));
+                }
+            }
+        }
+
+        let mut hash = self.algorithm.digest(&input);
+        for _ in 1..self.rounds {
+            hash = self.algorithm.digest(&intermediate.encode(hash));
+        }
+        hash
+    }
+}
+
+impl std::fmt::Display for Call {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.algorithm.fmt(fmt)?;
+        if self.rounds > 1 {
+            write!(fmt, "^{}", self.rounds)?;
+        }
+        let terms = self
+            .terms
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        write!(fmt, "({})", terms.join("."))
+    }
+}
+
+/// Hashes of hashes of the values, as in `sha256(md5($p.$s))`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chain {
+    call: Call,
+    intermediate: Intermediate,
+}
+
+impl Chain {
+    /// Parses nested hashes of `$p`, the value, and `$s`, the salt, concatenated with `.`
+    ///
+    /// A hash may be repeated on its own hash, as in `sha256^1000($p)`
+    pub fn parse(spec: &str, intermediate: Intermediate) -> Result<Self, error::Error> {
+        let mut parser = Parser {
+            spec,
+            chars: spec.chars().peekable(),
+        };
+        let call = parser.call()?;
+        if let Some(c) = parser.next() {
+            bail!(
+                "Chain '{}' has an unexpected '{}' after its last hash",
+                spec,
+                c
+            );
+        }
+
+        Ok(Self { call, intermediate })
+    }
+
+    /// The outermost algorithm, giving the hashes of the chain
+    pub fn algorithm(&self) -> hash::Algorithm {
+        self.call.algorithm
+    }
+
+    pub fn intermediate(&self) -> Intermediate {
+        self.intermediate
+    }
+
+    /// Number of hashes of a chain only hashing the same algorithm over the salted value, as
+    /// hashed by the kernels
+    pub fn rounds(&self) -> Option<u32> {
+        self.kernel_layout().map(|(rounds, _)| rounds)
+    }
+
+    /// Where the salt is hashed around the value, if the chain is hashed by the kernels
+    pub fn salt_position(&self) -> Option<SaltPosition> {
+        self.kernel_layout().and_then(|(_, position)| position)
+    }
+
+    /// Hash of the value by the whole chain, with the value and the salt encoded as given
+    pub fn digest<H: hash::Hash>(&self, value: &str, salt: &str, encoding: Encoding) -> H {
+        H::from_slice(&self.call.evaluate(
+            &encoding.encode(value),
+            &encoding.encode(salt),
+            self.intermediate,
+        ))
+    }
+
+    // The number of hashes and the position of the salt, if the chain only nests hashes of the
+    // same algorithm down to the value, salted or not
+    fn kernel_layout(&self) -> Option<(u32, Option<SaltPosition>)> {
+        let mut call = &self.call;
+        let mut rounds = call.rounds;
+        loop {
+            let position = match call.terms.as_slice() {
+                [Term::Hash(inner)] if inner.algorithm == call.algorithm => {
+                    rounds = rounds.checked_add(inner.rounds)?;
+                    call = inner;
+                    continue;
+                }
+                [Term::Value] => None,
+                [Term::Salt, Term::Value] => Some(SaltPosition::Prepend),
+                [Term::Value, Term::Salt] => Some(SaltPosition::Append),
+                [Term::Salt, Term::Value, Term::Salt] => Some(SaltPosition::Wrap),
+                _ => return None,
+            };
+            return Some((rounds, position));
+        }
+    }
+}
+
+impl std::fmt::Display for Chain {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.call.fmt(fmt)
+    }
+}
+
+struct Parser<'a> {
+    spec: &'a str,
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    // The next character, skipping spaces
+    fn next(&mut self) -> Option<char> {
+        self.chars.find(|c| !c.is_whitespace())
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), error::Error> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => bail!(
+                "Chain '{}' has an unexpected '{}' instead of '{}'",
+                self.spec,
+                c,
+                expected
+            ),
+            None => bail!("Chain '{}' ends before a '{}'", self.spec, expected),
+        }
+    }
+
+    fn call(&mut self) -> Result<Call, error::Error> {
+        self.peek();
+        let mut name = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            name.push(c);
+        }
+        if name.is_empty() {
+            bail!("Chain '{}' is missing a hash", self.spec);
+        }
+        let algorithm = name.parse::<hash::Algorithm>().map_err(
+            |err| error!(err; "Chain '{}' has an unknown algorithm '{}'", self.spec, name),
+        )?;
+
+        let rounds = if self.peek() == Some('^') {
+            self.chars.next();
+            self.peek();
+            let mut digits = String::new();
+            while let Some(c) = self.chars.next_if(char::is_ascii_digit) {
+                digits.push(c);
+            }
+            match digits.parse::<u32>() {
+                Ok(rounds) if rounds > 0 => rounds,
+                _ => bail!(
+                    "Chain '{}' has an invalid number of rounds for {}",
+                    self.spec,
+                    algorithm
+                ),
+            }
+        } else {
+            1
+        };
+
+        self.expect('(')?;
+        let mut terms = vec![self.term()?];
+        while self.peek() == Some('.') {
+            self.chars.next();
+            terms.push(self.term()?);
+        }
+        self.expect(')')?;
+
+        Ok(Call {
+            algorithm,
+            rounds,
+            terms,
+        })
+    }
+
+    fn term(&mut self) -> Result<Term, error::Error> {
+        if self.peek() != Some('$') {
+            return self.call().map(Term::Hash);
+        }
+
+        self.chars.next();
+        match self.chars.next() {
+            Some('p') => Ok(Term::Value),
+            Some('s') => Ok(Term::Salt),
+            _ => bail!(
+                "Chain '{}' has an unknown variable, expected $p or $s",
+                self.spec
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Chain, Intermediate};
+    use crate::hash;
+    use crate::options::{Encoding, SaltPosition};
+
+    fn digest(spec: &str, intermediate: Intermediate) -> String {
+        Chain::parse(spec, intermediate)
+            .unwrap()
+            .digest::<hash::md5::Hash>("abc", "xyz", Encoding::Utf8)
+            .to_string()
+    }
+
+    #[test]
+    fn parse() {
+        let chain = Chain::parse(" sha256 ( md5($p . $s) )", Intermediate::Hex).unwrap();
+        assert_eq!(chain.to_string(), "sha256(md5($p.$s))");
+        assert_eq!(chain.algorithm(), hash::Algorithm::sha256);
+        assert_eq!(
+            Chain::parse("SHA1^3(sha1($p))", Intermediate::Raw)
+                .unwrap()
+                .to_string(),
+            "sha1^3(sha1($p))"
+        );
+
+        assert!(Chain::parse("", Intermediate::Hex).is_err());
+        assert!(Chain::parse("$p", Intermediate::Hex).is_err());
+        assert!(Chain::parse("md6($p)", Intermediate::Hex).is_err());
+        assert!(Chain::parse("md5($p", Intermediate::Hex).is_err());
+        assert!(Chain::parse("md5($p))", Intermediate::Hex).is_err());
+        assert!(Chain::parse("md5($x)", Intermediate::Hex).is_err());
+        assert!(Chain::parse("md5($p.)", Intermediate::Hex).is_err());
+        assert!(Chain::parse("md5^0($p)", Intermediate::Hex).is_err());
+        assert!(Chain::parse("md5^($p)", Intermediate::Hex).is_err());
+    }
+
+    #[test]
+    fn kernel_layout() {
+        let layout = |spec| {
+            let chain = Chain::parse(spec, Intermediate::Hex).unwrap();
+            (chain.rounds(), chain.salt_position())
+        };
+        assert_eq!(layout("md5($p)"), (Some(1), None));
+        assert_eq!(
+            layout("md5(md5^2($s.$p))"),
+            (Some(3), Some(SaltPosition::Prepend))
+        );
+        assert_eq!(
+            layout("sha256^5($p.$s)"),
+            (Some(5), Some(SaltPosition::Append))
+        );
+        assert_eq!(layout("md5($s.$p.$s)"), (Some(1), Some(SaltPosition::Wrap)));
+        assert_eq!(layout("md5(sha1($p))"), (None, None));
+        assert_eq!(layout("md5($p.md5($p))"), (None, None));
+        assert_eq!(layout("md5($s.$s.$p)"), (None, None));
+    }
+
+    #[test]
+    fn digestion() {
+        assert_eq!(
+            digest("md5($p)", Intermediate::Hex),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            digest("md5(md5($p))", Intermediate::Hex),
+            "ec0405c5aef93e771cd80e0db180b88b"
+        );
+        assert_eq!(
+            digest("md5^2($p)", Intermediate::Hex),
+            digest("md5(md5($p))", Intermediate::Hex)
+        );
+        assert_eq!(
+            digest("md5(md5($p))", Intermediate::Raw),
+            "af5da9f45af7a300e3aded972f8ff687"
+        );
+        assert_eq!(
+            digest("md5(sha256($s.$p).$s)", Intermediate::Hex),
+            "aefdb59f273c62d5c2b7fd10beffa0cd"
+        );
+    }
+}
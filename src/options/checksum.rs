@@ -0,0 +1,135 @@
+/// Check digit algorithms appended to the end of the candidates
+///
+/// Only the payload is enumerated and the check digits are derived from it. Every digit of the
+/// prefix and of the variable part is part of the payload, while other characters are skipped
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Checksum {
+    /// Luhn (mod 10), as in card numbers and IMEIs
+    Luhn,
+    /// Weights increasing from 2 at the rightmost payload digit, with 10 written as `X`
+    Mod11,
+    /// Weights cycling from 2 to 7 from the rightmost payload digit, with 10 and 11 written as `0`
+    Mod11Cyclic,
+    /// ISO 7064 MOD 97-10, with two check digits
+    Mod97,
+}
+
+impl Checksum {
+    pub fn variants() -> &'static [&'static str] {
+        &["luhn", "mod11", "mod11-cyclic", "mod97"]
+    }
+
+    /// Number of check digits
+    pub fn length(self) -> u8 {
+        match self {
+            Self::Luhn | Self::Mod11 | Self::Mod11Cyclic => 1,
+            Self::Mod97 => 2,
+        }
+    }
+
+    /// Weight of the payload digit at `position`, counted from the rightmost payload digit
+    pub fn weight(self, position: usize) -> u32 {
+        match self {
+            Self::Luhn => {
+                if position & 1 == 0 {
+                    2
+                } else {
+                    1
+                }
+            }
+            // Allowed because the candidates are always shorter than the OpenCL value buffer
+            #[allow(clippy::cast_possible_truncation)]
+            Self::Mod11 => position as u32 + 2,
+            // Allowed because the remainder is less than 6
+            #[allow(clippy::cast_possible_truncation)]
+            Self::Mod11Cyclic => (position % 6) as u32 + 2,
+            Self::Mod97 => (0..position + 2).fold(1, |acc, _| acc * 10 % 97),
+        }
+    }
+
+    /// Contribution of a payload digit with the given weight to the sum
+    pub fn contribution(self, digit: u32, weight: u32) -> u32 {
+        let value = digit * weight;
+        if self == Self::Luhn && value > 9 {
+            value - 9
+        } else {
+            value
+        }
+    }
+
+    /// Check digits for the weighted sum of the payload
+    pub fn finalize(self, sum: u32) -> String {
+        match self {
+            Self::Luhn => ((10 - sum % 10) % 10).to_string(),
+            Self::Mod11 => match (11 - sum % 11) % 11 {
+                10 => String::from("X"),
+                check => check.to_string(),
+            },
+            Self::Mod11Cyclic => match 11 - sum % 11 {
+                10 | 11 => String::from("0"),
+                check => check.to_string(),
+            },
+            Self::Mod97 => format!("{:02}", 98 - sum % 97),
+        }
+    }
+
+    /// Check digits for a payload, skipping characters that are not digits
+    pub fn compute(self, payload: &[u8]) -> String {
+        let sum = payload
+            .iter()
+            .rev()
+            .filter(|c| c.is_ascii_digit())
+            .enumerate()
+            .map(|(position, c)| self.contribution(u32::from(c - b'0'), self.weight(position)))
+            .sum();
+        self.finalize(sum)
+    }
+}
+
+impl std::fmt::Display for Checksum {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Luhn => write!(fmt, "luhn"),
+            Self::Mod11 => write!(fmt, "mod11"),
+            Self::Mod11Cyclic => write!(fmt, "mod11-cyclic"),
+            Self::Mod97 => write!(fmt, "mod97"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Checksum;
+
+    #[test]
+    fn luhn() {
+        assert_eq!(Checksum::Luhn.compute(b"7992739871"), "3");
+        assert_eq!(Checksum::Luhn.compute(b"453201511283036"), "6");
+        assert_eq!(Checksum::Luhn.compute(b"4532-0151-1283-036"), "6");
+        assert_eq!(Checksum::Luhn.compute(b"35145120624162"), "2");
+    }
+
+    #[test]
+    fn mod11() {
+        assert_eq!(Checksum::Mod11.compute(b"030640615"), "2");
+        assert_eq!(Checksum::Mod11.compute(b"0-19-853453"), "1");
+        assert_eq!(Checksum::Mod11.compute(b"080442957"), "X");
+    }
+
+    #[test]
+    fn mod11_cyclic() {
+        assert_eq!(Checksum::Mod11Cyclic.compute(b"1234567"), "4");
+        assert_eq!(Checksum::Mod11Cyclic.compute(b"261533"), "9");
+        assert_eq!(Checksum::Mod11Cyclic.compute(b"0"), "0");
+    }
+
+    #[test]
+    fn mod97() {
+        assert_eq!(Checksum::Mod97.compute(b"794"), "44");
+        assert_eq!(
+            Checksum::Mod97.compute(b"3214282912345698765432161182"),
+            "95"
+        );
+        assert_eq!(Checksum::Mod97.length(), 2);
+    }
+}
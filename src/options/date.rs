@@ -0,0 +1,215 @@
+use crate::error;
+
+/// Every day of a range of years, written in a numeric format such as `YYYYMMDD`
+///
+/// Each date is addressed by an index in `0..number_space()`, in chronological order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dates {
+    format: String,
+    year: usize,
+    year_length: usize,
+    month: usize,
+    day: usize,
+    first_year: u16,
+    last_year: u16,
+    first_day: u32,
+    number_space: u64,
+}
+
+impl Dates {
+    /// Parses a format made of `YYYY` or `YY`, `MM` and `DD` in any order
+    pub fn parse(format: &str, first_year: u16, last_year: u16) -> Result<Self, error::Error> {
+        let invalid = || {
+            error!(
+                "Date format '{}' must have YYYY or YY, MM and DD once",
+                format
+            )
+        };
+
+        let mut year = None;
+        let mut month = None;
+        let mut day = None;
+        let bytes = format.to_uppercase().into_bytes();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let run = bytes[offset..]
+                .iter()
+                .take_while(|&&c| c == bytes[offset])
+                .count();
+            let field = match (bytes[offset], run) {
+                (b'Y', 2 | 4) => &mut year,
+                (b'M', 2) => &mut month,
+                (b'D', 2) => &mut day,
+                _ => return Err(invalid()),
+            };
+            if field.replace((offset, run)).is_some() {
+                return Err(invalid());
+            }
+            offset += run;
+        }
+        let (year, year_length) = year.ok_or_else(invalid)?;
+        let (month, _) = month.ok_or_else(invalid)?;
+        let (day, _) = day.ok_or_else(invalid)?;
+
+        if first_year == 0 || first_year > last_year || last_year > 9999 {
+            bail!("Invalid year range {}-{}", first_year, last_year);
+        }
+        if year_length == 2 && last_year - first_year >= 100 {
+            bail!(
+                "Year range {}-{} is too wide for two-digit years",
+                first_year,
+                last_year
+            );
+        }
+
+        let first_day = days(u32::from(first_year), 1, 1);
+        let number_space = u64::from(days(u32::from(last_year) + 1, 1, 1) - first_day);
+
+        Ok(Self {
+            format: format.to_uppercase(),
+            year,
+            year_length,
+            month,
+            day,
+            first_year,
+            last_year,
+            first_day,
+            number_space,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.format.len()
+    }
+
+    pub fn number_space(&self) -> u64 {
+        self.number_space
+    }
+
+    /// Days from 0000-03-01 to the first day of the range
+    pub fn first_day(&self) -> u32 {
+        self.first_day
+    }
+
+    /// Offsets of the year, month and day fields, and the number of digits of the year
+    pub fn layout(&self) -> (usize, usize, usize, usize) {
+        (self.year, self.year_length, self.month, self.day)
+    }
+
+    /// Writes the date at `index` into `output`, which must be `len()` bytes long
+    // Allowed because the index is always less than the number of days of the range
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn write(&self, index: u64, output: &mut [u8]) {
+        let (year, month, day) = civil(self.first_day + index as u32);
+
+        write_digits(year, &mut output[self.year..self.year + self.year_length]);
+        write_digits(month, &mut output[self.month..self.month + 2]);
+        write_digits(day, &mut output[self.day..self.day + 2]);
+    }
+
+    pub fn candidate(&self, index: u64) -> String {
+        let mut bytes = vec![0; self.len()];
+        self.write(index, &mut bytes);
+        bytes.into_iter().map(char::from).collect()
+    }
+}
+
+impl std::fmt::Display for Dates {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            fmt,
+            "{} ({}-{})",
+            self.format, self.first_year, self.last_year
+        )
+    }
+}
+
+// Days from 0000-03-01, for dates after it
+fn days(year: u32, month: u32, day: u32) -> u32 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era
+}
+
+// Inverse of `days`, the same conversion is done by the OpenCL kernels
+fn civil(days: u32) -> (u32, u32, u32) {
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u32::from(month <= 2);
+    (year, month, day)
+}
+
+// Writes the lowest digits of `value`, as many as `output` is long
+// Allowed because the remainder is always a single digit
+#[allow(clippy::cast_possible_truncation)]
+fn write_digits(mut value: u32, output: &mut [u8]) {
+    for byte in output.iter_mut().rev() {
+        *byte = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Dates;
+
+    #[test]
+    fn formats() {
+        let dates = Dates::parse("YYYYMMDD", 2000, 2000).unwrap();
+        assert_eq!(dates.len(), 8);
+        assert_eq!(dates.number_space(), 366);
+        assert_eq!(dates.candidate(0), "20000101");
+        assert_eq!(dates.candidate(59), "20000229");
+        assert_eq!(dates.candidate(365), "20001231");
+
+        let dates = Dates::parse("ddmmyyyy", 1999, 2000).unwrap();
+        assert_eq!(dates.number_space(), 365 + 366);
+        assert_eq!(dates.candidate(364), "31121999");
+        assert_eq!(dates.candidate(365), "01012000");
+
+        let dates = Dates::parse("YYMMDD", 1900, 1900).unwrap();
+        assert_eq!(dates.len(), 6);
+        assert_eq!(dates.number_space(), 365);
+        assert_eq!(dates.candidate(59), "000301");
+
+        let dates = Dates::parse("MMDDYY", 1930, 2029).unwrap();
+        assert_eq!(dates.candidate(dates.number_space() - 1), "123129");
+    }
+
+    #[test]
+    fn chronological() {
+        let dates = Dates::parse("YYYYMMDD", 1600, 2400).unwrap();
+        let all = (0..dates.number_space())
+            .map(|index| dates.candidate(index))
+            .collect::<Vec<_>>();
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(all.first().unwrap(), "16000101");
+        assert_eq!(all.last().unwrap(), "24001231");
+        assert!(all.contains(&String::from("20000229")));
+        assert!(!all.contains(&String::from("19000229")));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Dates::parse("YYYMMDD", 2000, 2000).is_err());
+        assert!(Dates::parse("YYYYMMDDDD", 2000, 2000).is_err());
+        assert!(Dates::parse("YYYYMM", 2000, 2000).is_err());
+        assert!(Dates::parse("YYYY-MM-DD", 2000, 2000).is_err());
+        assert!(Dates::parse("YYYYMMDD", 2001, 2000).is_err());
+        assert!(Dates::parse("YYYYMMDD", 0, 2000).is_err());
+        assert!(Dates::parse("YYMMDD", 1900, 2000).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use crate::error;
+
+const DIGITS: &[u8] = b"0123456789";
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const HEX: &[u8] = b"0123456789abcdef";
+
+const CUSTOM_CHARSETS: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Position {
+    Literal(u8),
+    Charset(Vec<u8>),
+}
+
+impl Position {
+    pub fn radix(&self) -> u64 {
+        match self {
+            Self::Literal(_) => 1,
+            Self::Charset(charset) => charset.len() as u64,
+        }
+    }
+
+    fn is_digit(&self) -> bool {
+        matches!(self, Self::Charset(charset) if charset == DIGITS)
+    }
+}
+
+/// Sequence of positions in a candidate value
+///
+/// Each candidate is addressed by an index in `0..number_space()`, where the last position
+/// varies the fastest
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    positions: Vec<Position>,
+    number_space: u64,
+}
+
+impl Mask {
+    /// Parses a hashcat-style mask
+    ///
+    /// Supported placeholders are `?d`, `?l`, `?u`, `?h`, the custom charsets `?1` to `?4` and
+    /// `??` for a literal `?`. Every other character is taken as a literal
+    pub fn parse(mask: &str, custom_charsets: &[Option<String>]) -> Result<Self, error::Error> {
+        let custom_charsets = custom_charsets
+            .iter()
+            .take(CUSTOM_CHARSETS)
+            .map(|charset| charset.as_deref().map(parse_charset).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut positions = Vec::with_capacity(mask.len());
+        let mut chars = mask.chars();
+        while let Some(c) = chars.next() {
+            if c == '?' {
+                let placeholder = chars
+                    .next()
+                    .ok_or_else(|| error!("Mask '{}' ends with an incomplete placeholder", mask))?;
+                positions.push(match placeholder {
+                    '?' => Position::Literal(b'?'),
+                    '1'..='4' => {
+                        let index = placeholder as usize - '1' as usize;
+                        match custom_charsets.get(index) {
+                            Some(Some(charset)) => Position::Charset(charset.clone()),
+                            _ => bail!("Custom charset ?{} is not defined", placeholder),
+                        }
+                    }
+                    c => Position::Charset(builtin(c)?.to_vec()),
+                });
+            } else {
+                positions.push(Position::Literal(ascii(c)?));
+            }
+        }
+
+        if positions.is_empty() {
+            bail!("Mask is empty");
+        }
+
+        Self::new(positions)
+    }
+
+    /// Parses a fixed-format template, where `#` marks a digit and every other character is a
+    /// literal, e.g. `###-##-####`
+    pub fn template(template: &str) -> Result<Self, error::Error> {
+        let positions = template
+            .chars()
+            .map(|c| {
+                if c == '#' {
+                    Ok(Position::Charset(DIGITS.to_vec()))
+                } else {
+                    ascii(c).map(Position::Literal)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !positions.iter().any(Position::is_digit) {
+            bail!("Template '{}' has no digits", template);
+        }
+
+        Self::new(positions)
+    }
+
+    /// A mask of `length` digits
+    pub fn decimal(length: u8) -> Result<Self, error::Error> {
+        Self::new(
+            (0..length)
+                .map(|_| Position::Charset(DIGITS.to_vec()))
+                .collect(),
+        )
+    }
+
+    fn new(positions: Vec<Position>) -> Result<Self, error::Error> {
+        let number_space = positions
+            .iter()
+            .try_fold(1_u64, |acc, position| acc.checked_mul(position.radix()))
+            .ok_or_else(|| error!("Mask keyspace is too large"))?;
+
+        Ok(Self {
+            positions,
+            number_space,
+        })
+    }
+
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn number_space(&self) -> u64 {
+        self.number_space
+    }
+
+    /// If the mask is made only of digits, it can use the optimized decimal enumeration
+    pub fn is_decimal(&self) -> bool {
+        self.positions.iter().all(Position::is_digit)
+    }
+
+    /// If every position is either a digit or a literal, as required by checksums
+    pub fn is_numeric(&self) -> bool {
+        self.positions
+            .iter()
+            .all(|position| matches!(position, Position::Literal(_)) || position.is_digit())
+    }
+
+    /// Writes the candidate at `index` into `output`, which must be `len()` bytes long
+    pub fn write(&self, mut index: u64, output: &mut [u8]) {
+        for (byte, position) in output.iter_mut().zip(self.positions.iter()).rev() {
+            match position {
+                Position::Literal(literal) => *byte = *literal,
+                Position::Charset(charset) => {
+                    let radix = charset.len() as u64;
+                    // Allowed because the remainder is always less than the charset length
+                    #[allow(clippy::cast_possible_truncation)]
+                    let offset = (index % radix) as usize;
+                    *byte = charset[offset];
+                    index /= radix;
+                }
+            }
+        }
+    }
+
+    pub fn candidate(&self, index: u64) -> String {
+        let mut bytes = vec![0; self.len()];
+        self.write(index, &mut bytes);
+        bytes.into_iter().map(char::from).collect()
+    }
+}
+
+impl std::fmt::Display for Mask {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for position in &self.positions {
+            match position {
+                Position::Literal(b'?') => write!(fmt, "??")?,
+                Position::Literal(literal) => write!(fmt, "{}", char::from(*literal))?,
+                Position::Charset(charset) => match charset.as_slice() {
+                    DIGITS => write!(fmt, "?d")?,
+                    LOWERCASE => write!(fmt, "?l")?,
+                    UPPERCASE => write!(fmt, "?u")?,
+                    HEX => write!(fmt, "?h")?,
+                    charset => write!(
+                        fmt,
+                        "[{}]",
+                        charset.iter().copied().map(char::from).collect::<String>()
+                    )?,
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+fn builtin(placeholder: char) -> Result<&'static [u8], error::Error> {
+    match placeholder {
+        'd' => Ok(DIGITS),
+        'l' => Ok(LOWERCASE),
+        'u' => Ok(UPPERCASE),
+        'h' => Ok(HEX),
+        c => bail!("Invalid mask placeholder ?{}", c),
+    }
+}
+
+fn ascii(c: char) -> Result<u8, error::Error> {
+    if c.is_ascii() {
+        Ok(c as u8)
+    } else {
+        bail!("Mask character '{}' is not ASCII", c)
+    }
+}
+
+// Custom charsets accept builtin placeholders and literals. Duplicates are dropped
+fn parse_charset(charset: &str) -> Result<Vec<u8>, error::Error> {
+    let mut output = Vec::with_capacity(charset.len());
+    let mut push = |c: u8| {
+        if !output.contains(&c) {
+            output.push(c);
+        }
+    };
+
+    let mut chars = charset.chars();
+    while let Some(c) = chars.next() {
+        if c == '?' {
+            match chars.next() {
+                Some('?') => push(b'?'),
+                Some(c) => builtin(c)?.iter().copied().for_each(&mut push),
+                None => bail!("Charset '{}' ends with an incomplete placeholder", charset),
+            }
+        } else {
+            push(ascii(c)?);
+        }
+    }
+
+    if output.is_empty() {
+        bail!("Custom charset is empty");
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Mask, Position};
+
+    #[test]
+    fn builtin_placeholders() {
+        let mask = Mask::parse("?d?l?u?h", &[]).unwrap();
+        assert_eq!(mask.len(), 4);
+        assert_eq!(mask.number_space(), 10 * 26 * 26 * 16);
+        assert_eq!(mask.to_string(), "?d?l?u?h");
+    }
+
+    #[test]
+    fn literals() {
+        let mask = Mask::parse("a-??b", &[]).unwrap();
+        assert_eq!(
+            mask.positions(),
+            &[
+                Position::Literal(b'a'),
+                Position::Literal(b'-'),
+                Position::Literal(b'?'),
+                Position::Literal(b'b'),
+            ]
+        );
+        assert_eq!(mask.number_space(), 1);
+        assert_eq!(mask.candidate(0), "a-?b");
+        assert_eq!(mask.to_string(), "a-??b");
+    }
+
+    #[test]
+    fn custom_charsets() {
+        let custom = [Some(String::from("abc")), None, Some(String::from("?dx"))];
+        let mask = Mask::parse("?1?3", &custom).unwrap();
+        assert_eq!(mask.number_space(), 3 * 11);
+        assert_eq!(mask.candidate(0), "a0");
+        assert_eq!(mask.candidate(10), "ax");
+        assert_eq!(mask.candidate(11), "b0");
+        assert_eq!(mask.candidate(32), "cx");
+    }
+
+    #[test]
+    fn duplicate_charset_characters() {
+        let custom = [Some(String::from("?daa1"))];
+        let mask = Mask::parse("?1", &custom).unwrap();
+        assert_eq!(mask.number_space(), 11);
+    }
+
+    #[test]
+    fn enumeration() {
+        let mask = Mask::parse("x?d?l", &[]).unwrap();
+        assert_eq!(mask.candidate(0), "x0a");
+        assert_eq!(mask.candidate(1), "x0b");
+        assert_eq!(mask.candidate(26), "x1a");
+        assert_eq!(mask.candidate(mask.number_space() - 1), "x9z");
+    }
+
+    #[test]
+    fn decimal() {
+        let mask = Mask::decimal(3).unwrap();
+        assert!(mask.is_decimal());
+        assert_eq!(mask.number_space(), 1000);
+        assert_eq!(mask.candidate(7), "007");
+        assert_eq!(mask, Mask::parse("?d?d?d", &[]).unwrap());
+        assert!(!Mask::parse("?d1", &[]).unwrap().is_decimal());
+    }
+
+    #[test]
+    fn template() {
+        let mask = Mask::template("###-##-####").unwrap();
+        assert_eq!(mask.len(), 11);
+        assert_eq!(mask.number_space(), 1_000_000_000);
+        assert!(!mask.is_decimal());
+        assert_eq!(mask.candidate(0), "000-00-0000");
+        assert_eq!(mask.candidate(123_456_789), "123-45-6789");
+        assert_eq!(mask.to_string(), "?d?d?d-?d?d-?d?d?d?d");
+        assert_eq!(mask, Mask::parse("?d?d?d-?d?d-?d?d?d?d", &[]).unwrap());
+
+        assert!(Mask::template("####").unwrap().is_decimal());
+        assert!(mask.is_numeric());
+        assert!(!Mask::parse("?d?l", &[]).unwrap().is_numeric());
+        assert!(Mask::template("").is_err());
+        assert!(Mask::template("--").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Mask::parse("", &[]).is_err());
+        assert!(Mask::parse("?", &[]).is_err());
+        assert!(Mask::parse("?x", &[]).is_err());
+        assert!(Mask::parse("?1", &[]).is_err());
+        assert!(Mask::parse("?2", &[Some(String::from("a"))]).is_err());
+        assert!(Mask::parse("?1", &[Some(String::new())]).is_err());
+        assert!(Mask::parse("é", &[]).is_err());
+        assert!(Mask::parse(&"?l".repeat(14), &[]).is_err());
+    }
+}
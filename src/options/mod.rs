@@ -0,0 +1,1428 @@
+use crate::decrypt;
+use crate::error;
+use crate::hash;
+use crate::kdf;
+use crate::Input;
+
+mod chain;
+mod checksum;
+mod date;
+mod mask;
+mod rule;
+mod transform;
+
+pub use chain::{Chain, Intermediate};
+pub use checksum::Checksum;
+pub use date::Dates;
+pub use mask::{Mask, Position};
+pub use rule::Rule;
+pub use transform::{Pipeline, Transform};
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Device {
+    Cpu,
+    Gpu,
+}
+
+impl Device {
+    pub fn variants() -> &'static [&'static str] {
+        &["cpu", "gpu"]
+    }
+}
+
+impl std::fmt::Display for Device {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cpu => write!(fmt, "CPU"),
+            Self::Gpu => write!(fmt, "GPU"),
+        }
+    }
+}
+
+/// Which value of a cracked hash is reported, when it was transformed before hashing
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Emit {
+    Original,
+    Hashed,
+}
+
+impl Emit {
+    pub fn variants() -> &'static [&'static str] {
+        &["original", "hashed"]
+    }
+}
+
+impl std::fmt::Display for Emit {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Original => write!(fmt, "original"),
+            Self::Hashed => write!(fmt, "hashed"),
+        }
+    }
+}
+
+/// How the salted values are encoded into the bytes being hashed
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Encoding {
+    Utf8,
+    /// Two bytes per character, as hashed by NTLM
+    Utf16Le,
+}
+
+impl Encoding {
+    pub fn variants() -> &'static [&'static str] {
+        &["utf8", "utf16le"]
+    }
+
+    /// The bytes of the text, as hashed
+    pub fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            Self::Utf8 => text.as_bytes().to_vec(),
+            Self::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utf8 => write!(fmt, "utf8"),
+            Self::Utf16Le => write!(fmt, "utf16le"),
+        }
+    }
+}
+
+/// Where the salt is added to the values before hashing
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum SaltPosition {
+    Prepend,
+    Append,
+    Wrap,
+}
+
+impl SaltPosition {
+    pub fn variants() -> &'static [&'static str] {
+        &["prepend", "append", "wrap"]
+    }
+
+    /// The salt hashed before the value, if any
+    pub fn prefix(self, salt: &str) -> &str {
+        match self {
+            Self::Prepend | Self::Wrap => salt,
+            Self::Append => "",
+        }
+    }
+
+    /// The salt hashed after the value, if any
+    pub fn suffix(self, salt: &str) -> &str {
+        match self {
+            Self::Append | Self::Wrap => salt,
+            Self::Prepend => "",
+        }
+    }
+}
+
+impl std::fmt::Display for SaltPosition {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Prepend => write!(fmt, "prepend"),
+            Self::Append => write!(fmt, "append"),
+            Self::Wrap => write!(fmt, "wrap"),
+        }
+    }
+}
+
+/// Layout of input lines carrying the salt of their hash, separated by a ':'
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum SaltFormat {
+    HashSalt,
+    SaltHash,
+}
+
+impl SaltFormat {
+    pub fn variants() -> &'static [&'static str] {
+        &["hash:salt", "salt:hash"]
+    }
+
+    /// Splits a line into its hash and its salt, if it has a separator
+    ///
+    /// Salts may contain the separator, unlike hashes
+    pub fn split(self, line: &str) -> Option<(&str, &str)> {
+        match self {
+            Self::HashSalt => line.split_once(':'),
+            Self::SaltHash => line.rsplit_once(':').map(|(salt, hash)| (hash, salt)),
+        }
+    }
+}
+
+impl std::fmt::Display for SaltFormat {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HashSalt => write!(fmt, "hash:salt"),
+            Self::SaltHash => write!(fmt, "salt:hash"),
+        }
+    }
+}
+
+/// Source of the words of a wordlist attack
+#[derive(PartialEq, Debug, Clone)]
+pub enum Wordlist {
+    File(std::path::PathBuf),
+    Stdin,
+}
+
+impl std::fmt::Display for Wordlist {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(fmt, "{}", path.display()),
+            Self::Stdin => write!(fmt, "stdin"),
+        }
+    }
+}
+
+pub struct Shared<T: Input> {
+    input: std::collections::HashSet<T>,
+    salt: String,
+    salt_position: SaltPosition,
+    transforms: Pipeline,
+    encoding: Encoding,
+    hmac_key: Option<String>,
+    chain: Option<Chain>,
+}
+
+impl<T: Input> Shared<T> {
+    // Allowed because every parameter is an independent option of the hashing
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        input: std::collections::HashSet<T>,
+        algorithm: hash::Algorithm,
+        salt: String,
+        salt_position: SaltPosition,
+        transforms: Pipeline,
+        encoding: Encoding,
+        hmac_key: Option<String>,
+        chain: Option<Chain>,
+    ) -> Result<Self, error::Error> {
+        if input.is_empty() {
+            bail!("No valid input provided");
+        }
+        if let Some(chain) = &chain {
+            if chain.algorithm() != algorithm {
+                bail!("Chain '{}' does not end with {}", chain, algorithm);
+            }
+            if hmac_key.is_some() {
+                bail!("An HMAC key cannot be given along with a chain");
+            }
+        }
+
+        Ok(Self {
+            input,
+            salt,
+            salt_position,
+            transforms,
+            encoding,
+            hmac_key,
+            chain,
+        })
+    }
+}
+
+pub trait SharedAccessor<T: Input> {
+    fn shared(&self) -> &Shared<T>;
+
+    fn input(&self) -> &std::collections::HashSet<T> {
+        &self.shared().input
+    }
+
+    fn salt(&self) -> &str {
+        &self.shared().salt
+    }
+
+    fn salt_position(&self) -> SaltPosition {
+        self.shared().salt_position
+    }
+
+    /// The salt hashed before the values, according to its position or to the innermost hash of
+    /// the chain
+    fn salt_prefix(&self) -> &str {
+        match self.chain() {
+            Some(chain) => chain
+                .salt_position()
+                .map_or("", |position| position.prefix(self.salt())),
+            None => self.salt_position().prefix(self.salt()),
+        }
+    }
+
+    /// The salt hashed after the values, according to its position or to the innermost hash of
+    /// the chain
+    fn salt_suffix(&self) -> &str {
+        match self.chain() {
+            Some(chain) => chain
+                .salt_position()
+                .map_or("", |position| position.suffix(self.salt())),
+            None => self.salt_position().suffix(self.salt()),
+        }
+    }
+
+    /// Transforms applied to the values before they are salted and hashed
+    fn transforms(&self) -> &Pipeline {
+        &self.shared().transforms
+    }
+
+    /// Encoding of the salted values once transformed
+    fn encoding(&self) -> Encoding {
+        self.shared().encoding
+    }
+
+    /// Key of the HMAC of the salted values, instead of their plain hash
+    fn hmac_key(&self) -> Option<&str> {
+        self.shared().hmac_key.as_deref()
+    }
+
+    /// Hashes of hashes of the values, instead of their plain hash
+    fn chain(&self) -> Option<&Chain> {
+        self.shared().chain.as_ref()
+    }
+
+    /// Hash of the salted value, encoded, keyed or chained as given
+    fn digest<H: hash::Hash>(&self, salted_prefix: &str, number: &str, salted_suffix: &str) -> H {
+        if let Some(chain) = self.chain() {
+            // The chain adds the salt itself
+            let value = format!(
+                "{}{}{}",
+                &salted_prefix[self.salt_prefix().len()..],
+                number,
+                &salted_suffix[..salted_suffix.len() - self.salt_suffix().len()]
+            );
+            return chain.digest(&value, self.salt(), self.encoding());
+        }
+
+        match self.hmac_key() {
+            Some(key) => H::digest_hmac(
+                key.as_bytes(),
+                salted_prefix,
+                number,
+                salted_suffix,
+                self.encoding(),
+            ),
+            None => H::digest_encoded(salted_prefix, number, salted_suffix, self.encoding()),
+        }
+    }
+}
+
+pub struct Encrypt<H: hash::Hash> {
+    shared: Shared<String>,
+    length: Option<u8>,
+    prefix: String,
+    _phantom: std::marker::PhantomData<H>,
+}
+
+impl<H: hash::Hash> Encrypt<H> {
+    /// Values follow the prefix and are padded with leading zeros up to the length, prefix
+    /// included, the way the cracker enumerates them
+    // Allowed because every parameter is an independent option of the hashing
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input: std::collections::HashSet<String>,
+        salt: String,
+        salt_position: SaltPosition,
+        transforms: Pipeline,
+        encoding: impl Into<Option<Encoding>>,
+        hmac_key: impl Into<Option<String>>,
+        chain: impl Into<Option<Chain>>,
+        prefix: impl Into<Option<String>>,
+        length: impl Into<Option<u8>>,
+    ) -> Result<Self, error::Error> {
+        let prefix = prefix.into().unwrap_or_default();
+        let length = length.into();
+        if let Some(length) = length {
+            if prefix.len() > usize::from(length) {
+                bail!("Prefix '{}' is too long for length {}", prefix, length);
+            }
+        }
+
+        let input = input
+            .into_iter()
+            .map(|value| {
+                let width = length.map_or(0, |length| usize::from(length) - prefix.len());
+                if value.len() > width && length.is_some() {
+                    bail!(
+                        "'{}{}' is too long for length {}",
+                        prefix,
+                        value,
+                        width + prefix.len()
+                    );
+                }
+                Ok(format!("{}{:0>2$}", prefix, value, width))
+            })
+            .collect::<Result<std::collections::HashSet<_>, _>>()?;
+
+        if let Some(value) = input.iter().find(|value| transforms.apply(value).is_none()) {
+            bail!(
+                "'{}' cannot be transformed, the XOR mask is not long enough",
+                value
+            );
+        }
+
+        Ok(Self {
+            shared: Shared::new(
+                input,
+                H::algorithm(),
+                salt,
+                salt_position,
+                transforms,
+                encoding.into().unwrap_or(Encoding::Utf8),
+                hmac_key.into(),
+                chain.into(),
+            )?,
+            length,
+            prefix,
+            _phantom: std::marker::PhantomData::<H>::default(),
+        })
+    }
+
+    /// Length of the values, prefix included, if they are padded
+    pub fn length(&self) -> Option<u8> {
+        self.length
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+impl<H: hash::Hash> SharedAccessor<String> for Encrypt<H> {
+    fn shared(&self) -> &Shared<String> {
+        &self.shared
+    }
+}
+
+#[derive(Clone)]
+pub struct Segment {
+    prefix: String,
+    dates: Option<Dates>,
+    mask: Mask,
+    checksum: Option<Checksum>,
+    number_space: u64,
+}
+
+impl Segment {
+    pub fn new(prefix: String, mask: Mask) -> Self {
+        Self {
+            prefix,
+            dates: None,
+            number_space: mask.number_space(),
+            mask,
+            checksum: None,
+        }
+    }
+
+    fn decimal(
+        prefix: String,
+        length: u8,
+        dates: Option<&Dates>,
+        checksum: Option<Checksum>,
+    ) -> Result<Self, error::Error> {
+        let date_length = dates.map_or(0, Dates::len);
+        let check_length = checksum.map_or(0, Checksum::length);
+        let reserved = prefix.len() + date_length + usize::from(check_length);
+        if reserved > usize::from(length) {
+            if dates.is_none() && checksum.is_none() {
+                bail!("Prefix '{}' is too long for length {}", prefix, length);
+            }
+            bail!(
+                "Length {} cannot hold the prefix '{}', the {} digit date and {} check digits",
+                length,
+                prefix,
+                date_length,
+                check_length
+            );
+        }
+
+        // Allowed because the length was checked for overflow
+        #[allow(clippy::cast_possible_truncation)]
+        let variable_length = length - reserved as u8;
+
+        Self::new(prefix, Mask::decimal(variable_length)?)
+            .with_dates(dates.cloned())?
+            .with_checksum(checksum)
+    }
+
+    /// Inserts dates between the prefix and the mask, which becomes their suffix
+    pub fn with_dates(mut self, dates: Option<Dates>) -> Result<Self, error::Error> {
+        self.number_space = dates
+            .as_ref()
+            .map_or(Some(1), |dates| Some(dates.number_space()))
+            .and_then(|space| space.checked_mul(self.mask.number_space()))
+            .ok_or_else(|| error!("Date keyspace is too large"))?;
+        self.dates = dates;
+        Ok(self)
+    }
+
+    /// Appends check digits derived from the digits of the prefix, the dates and the mask
+    pub fn with_checksum(mut self, checksum: Option<Checksum>) -> Result<Self, error::Error> {
+        if checksum.is_some() && !self.mask.is_numeric() {
+            bail!(
+                "Mask '{}' cannot have a checksum, as it is not only digits and literals",
+                self.mask
+            );
+        }
+        self.checksum = checksum;
+        Ok(self)
+    }
+
+    pub fn checksum(&self) -> Option<Checksum> {
+        self.checksum
+    }
+
+    pub fn dates(&self) -> Option<&Dates> {
+        self.dates.as_ref()
+    }
+
+    /// The same keyspace, following a word appended to the prefix
+    pub fn with_word(&self, word: &str) -> Self {
+        let mut segment = self.clone();
+        segment.prefix.push_str(word);
+        segment
+    }
+
+    /// Length of the candidates without the prefix, including the dates and the check digits
+    // Allowed because the mask is always shorter than the OpenCL value buffer
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn length(&self) -> u8 {
+        (self.dates.as_ref().map_or(0, Dates::len) + self.mask.len()) as u8
+            + self.checksum.map_or(0, Checksum::length)
+    }
+
+    pub fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    pub fn number_space(&self) -> u64 {
+        self.number_space
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    // Allowed because prefix is always less than total_size
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn prefix_length(&self) -> u8 {
+        self.prefix.len() as u8
+    }
+
+    /// The variable part of the candidate at `index`, without the prefix
+    ///
+    /// The dates vary the slowest, then the mask
+    pub fn candidate(&self, index: u64) -> String {
+        let mask_space = self.mask.number_space();
+        let mut candidate = self
+            .dates
+            .as_ref()
+            .map_or_else(String::new, |dates| dates.candidate(index / mask_space));
+        candidate.push_str(&self.mask.candidate(index % mask_space));
+        if let Some(checksum) = self.checksum {
+            let payload = format!("{}{}", self.prefix, candidate);
+            candidate.push_str(&checksum.compute(payload.as_bytes()));
+        }
+        candidate
+    }
+}
+
+pub struct Decrypt<H: hash::Hash> {
+    shared: Shared<H>,
+    device: Device,
+    emit: Emit,
+    files: std::collections::HashSet<std::path::PathBuf>,
+    number_space: u64,
+    rules: Vec<Rule>,
+    salts: std::collections::HashMap<H, String>,
+    segments: Vec<Segment>,
+    threads: u8,
+    wordlist: Option<Wordlist>,
+}
+
+impl<H: hash::Hash> SharedAccessor<H> for Decrypt<H> {
+    fn shared(&self) -> &Shared<H> {
+        &self.shared
+    }
+}
+
+impl<H: hash::Hash> Decrypt<H> {
+    pub fn device(&self) -> Device {
+        self.device
+    }
+
+    /// Which value of the cracked hashes is reported
+    pub fn emit(&self) -> Emit {
+        self.emit
+    }
+
+    pub fn files(&self) -> &std::collections::HashSet<std::path::PathBuf> {
+        &self.files
+    }
+
+    /// Number of candidates, for every word with a wordlist
+    pub fn number_space(&self) -> u64 {
+        self.number_space
+    }
+
+    /// Rules mangling every word of the wordlist
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Salts given along with their hashes, replacing the shared salt for them
+    pub fn salts(&self) -> &std::collections::HashMap<H, String> {
+        &self.salts
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn threads(&self) -> u8 {
+        self.threads
+    }
+
+    /// Words to insert after the prefix of every segment, before its keyspace
+    pub fn wordlist(&self) -> Option<&Wordlist> {
+        self.wordlist.as_ref()
+    }
+
+    /// Candidates derived from a word by every rule, or the word alone without rules
+    pub fn candidates(&self, word: &str) -> Vec<String> {
+        candidates(&self.rules, word)
+    }
+
+    /// Options searching every group of targets sharing the same salt, ordered by salt
+    pub fn salt_groups(&self) -> Vec<Self> {
+        let mut groups = std::collections::BTreeMap::<&str, std::collections::HashSet<H>>::new();
+        for hash in &self.shared.input {
+            let salt = self.salts.get(hash).map_or(self.salt(), String::as_str);
+            groups.entry(salt).or_default().insert(*hash);
+        }
+
+        groups
+            .into_iter()
+            .map(|(salt, input)| Self {
+                shared: Shared {
+                    input,
+                    salt: String::from(salt),
+                    salt_position: self.salt_position(),
+                    transforms: self.transforms().clone(),
+                    encoding: self.encoding(),
+                    hmac_key: self.shared.hmac_key.clone(),
+                    chain: self.shared.chain.clone(),
+                },
+                device: self.device,
+                emit: self.emit,
+                files: self.files.clone(),
+                number_space: self.number_space,
+                rules: self.rules.clone(),
+                salts: std::collections::HashMap::with_capacity(0),
+                segments: self.segments.clone(),
+                threads: self.threads,
+                wordlist: self.wordlist.clone(),
+            })
+            .collect()
+    }
+
+    pub fn input_as_eytzinger(&self) -> Vec<H> {
+        use eytzinger::{permutation::InplacePermutator, SliceExt};
+        let mut data = self
+            .shared
+            .input
+            .iter()
+            .map(Clone::clone)
+            .collect::<Vec<_>>();
+        data.sort_unstable();
+        data.as_mut_slice().eytzingerize(&mut InplacePermutator);
+        data
+    }
+}
+
+pub struct DecryptBuilder<H: hash::Hash> {
+    input: std::collections::HashSet<H>,
+    lengths: Vec<u8>,
+    chain: Option<Chain>,
+    checksum: Option<Checksum>,
+    dates: Option<Dates>,
+    device: Option<Device>,
+    emit: Option<Emit>,
+    encoding: Option<Encoding>,
+    files: Option<std::collections::HashSet<std::path::PathBuf>>,
+    hmac_key: Option<String>,
+    masks: Option<Vec<Mask>>,
+    prefixes: Option<Vec<String>>,
+    rules: Option<Vec<Rule>>,
+    salt: Option<String>,
+    salt_position: Option<SaltPosition>,
+    salts: Option<std::collections::HashMap<H, String>>,
+    threads: Option<u8>,
+    transforms: Option<Vec<Transform>>,
+    wordlist: Option<Wordlist>,
+    xor: Option<Vec<u8>>,
+}
+
+impl<H: hash::Hash> DecryptBuilder<H> {
+    pub fn new(input: std::collections::HashSet<H>, lengths: Vec<u8>) -> Self {
+        Self {
+            input,
+            lengths,
+            chain: None,
+            checksum: None,
+            dates: None,
+            device: None,
+            emit: None,
+            encoding: None,
+            files: None,
+            hmac_key: None,
+            masks: None,
+            prefixes: None,
+            rules: None,
+            salt: None,
+            salt_position: None,
+            salts: None,
+            threads: None,
+            transforms: None,
+            wordlist: None,
+            xor: None,
+        }
+    }
+
+    /// Hashes of hashes of the candidates, which replace their plain hash
+    pub fn chain(mut self, chain: impl Into<Option<Chain>>) -> Self {
+        self.chain = chain.into();
+        self
+    }
+
+    pub fn checksum(mut self, checksum: impl Into<Option<Checksum>>) -> Self {
+        self.checksum = checksum.into();
+        self
+    }
+
+    pub fn dates(mut self, dates: impl Into<Option<Dates>>) -> Self {
+        self.dates = dates.into();
+        self
+    }
+
+    pub fn device(mut self, device: impl Into<Option<Device>>) -> Self {
+        self.device = device.into();
+        self
+    }
+
+    pub fn emit(mut self, emit: impl Into<Option<Emit>>) -> Self {
+        self.emit = emit.into();
+        self
+    }
+
+    pub fn encoding(mut self, encoding: impl Into<Option<Encoding>>) -> Self {
+        self.encoding = encoding.into();
+        self
+    }
+
+    pub fn files(
+        mut self,
+        files: impl Into<Option<std::collections::HashSet<std::path::PathBuf>>>,
+    ) -> Self {
+        self.files = files.into();
+        self
+    }
+
+    /// Key of the HMAC of the candidates, which replaces their plain hash
+    pub fn hmac_key(mut self, hmac_key: impl Into<Option<String>>) -> Self {
+        self.hmac_key = hmac_key.into();
+        self
+    }
+
+    pub fn masks(mut self, masks: impl Into<Option<Vec<Mask>>>) -> Self {
+        self.masks = masks.into();
+        self
+    }
+
+    pub fn prefixes(mut self, prefixes: impl Into<Option<Vec<String>>>) -> Self {
+        self.prefixes = prefixes.into();
+        self
+    }
+
+    pub fn rules(mut self, rules: impl Into<Option<Vec<Rule>>>) -> Self {
+        self.rules = rules.into();
+        self
+    }
+
+    pub fn salt(mut self, salt: impl Into<Option<String>>) -> Self {
+        self.salt = salt.into();
+        self
+    }
+
+    pub fn salt_position(mut self, salt_position: impl Into<Option<SaltPosition>>) -> Self {
+        self.salt_position = salt_position.into();
+        self
+    }
+
+    /// Salts of some of the targets, which are added to the input
+    pub fn salts(mut self, salts: impl Into<Option<std::collections::HashMap<H, String>>>) -> Self {
+        self.salts = salts.into();
+        self
+    }
+
+    pub fn threads(mut self, threads: impl Into<Option<u8>>) -> Self {
+        self.threads = threads.into();
+        self
+    }
+
+    /// Transforms applied in order to every candidate before it is hashed
+    pub fn transforms(mut self, transforms: impl Into<Option<Vec<Transform>>>) -> Self {
+        self.transforms = transforms.into();
+        self
+    }
+
+    pub fn wordlist(mut self, wordlist: impl Into<Option<Wordlist>>) -> Self {
+        self.wordlist = wordlist.into();
+        self
+    }
+
+    /// Shorthand for the transforms masking candidates with the XOR mask, then encoding them in base64
+    pub fn xor(mut self, xor: impl Into<Option<Vec<u8>>>) -> Self {
+        self.xor = xor.into();
+        self
+    }
+
+    pub fn build(mut self) -> Result<Decrypt<H>, error::Error> {
+        let salts = self.salts.take().unwrap_or_default();
+        self.input.extend(salts.keys().copied());
+        let salt = self.salt.take().unwrap_or_default();
+        // The wordlist is searched again for every salt
+        if self.wordlist == Some(Wordlist::Stdin) {
+            let mut group_salts = self
+                .input
+                .iter()
+                .map(|hash| salts.get(hash).unwrap_or(&salt));
+            let first = group_salts.next();
+            if group_salts.any(|group_salt| Some(group_salt) != first) {
+                bail!("A wordlist from stdin cannot be searched for more than one salt");
+            }
+        }
+
+        let segments = self.build_segments()?;
+        let transforms = pipeline(self.transforms.take(), self.xor.take(), &segments)?;
+
+        let number_space = number_space(&segments)?;
+        // The size of wordlists is not known in advance
+        let threads = if self.wordlist.is_some() {
+            threads(self.threads, u64::MAX)
+        } else {
+            threads(self.threads, number_space)
+        };
+        let device = self.derive_device(&transforms, number_space, threads)?;
+
+        Ok(Decrypt {
+            shared: Shared::new(
+                self.input,
+                H::algorithm(),
+                salt,
+                self.salt_position.unwrap_or(SaltPosition::Prepend),
+                transforms,
+                self.encoding.unwrap_or(Encoding::Utf8),
+                self.hmac_key,
+                self.chain,
+            )?,
+            device,
+            emit: self.emit.unwrap_or(Emit::Original),
+            files: self
+                .files
+                .unwrap_or_else(|| std::collections::HashSet::with_capacity(0)),
+            number_space,
+            rules: self.rules.unwrap_or_default(),
+            salts,
+            segments,
+            threads,
+            wordlist: self.wordlist,
+        })
+    }
+
+    fn build_segments(&self) -> Result<Vec<Segment>, error::Error> {
+        if self.wordlist.is_none() && self.rules.as_ref().map_or(false, |rules| !rules.is_empty()) {
+            bail!("Rules can only be applied to a wordlist");
+        }
+
+        segments(
+            &self.lengths,
+            self.masks.as_deref().unwrap_or_default(),
+            self.prefixes.as_deref(),
+            self.dates.as_ref(),
+            self.checksum,
+            self.wordlist.is_some(),
+        )
+    }
+
+    // The reason for candidates not to be hashed by the OpenCL kernels, if any. Transformed values
+    // encoded in UTF-16LE are only hashed on the CPU, since the kernels transform them byte by byte
+    fn cpu_only(&self, transforms: &Pipeline) -> Option<String> {
+        if !decrypt::has_kernel(H::algorithm()) {
+            Some(format!("{} can only be cracked on the CPU", H::algorithm()))
+        } else if self.hmac_key.is_some() && !decrypt::has_hmac_kernel(H::algorithm()) {
+            Some(format!(
+                "HMAC of {} can only be cracked on the CPU",
+                H::algorithm()
+            ))
+        } else if let Some(chain) = self
+            .chain
+            .as_ref()
+            .filter(|chain| !decrypt::has_chain_kernel(chain))
+        {
+            Some(format!("Chain {} can only be cracked on the CPU", chain))
+        } else if self.encoding == Some(Encoding::Utf16Le) && !transforms.is_empty() {
+            Some(String::from(
+                "Transformed values can only be encoded in utf16le on the CPU",
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn derive_device(
+        &self,
+        transforms: &Pipeline,
+        number_space: u64,
+        threads: u8,
+    ) -> Result<Device, error::Error> {
+        let cpu_only = self.cpu_only(transforms);
+        Ok(match self.device {
+            Some(Device::Gpu) => match cpu_only {
+                Some(reason) => bail!("{}", reason),
+                None => Device::Gpu,
+            },
+            Some(device) => device,
+            None if self.wordlist.is_some() || cpu_only.is_some() => Device::Cpu,
+            None if number_space > u64::from(threads) * decrypt::OPTIMAL_HASHES_PER_THREAD => {
+                Device::Gpu
+            }
+            None => Device::Cpu,
+        })
+    }
+}
+
+/// Hashes of a slow function, each cracked with its own salt and cost on the CPU
+pub struct KdfDecrypt {
+    kdf: kdf::Kdf,
+    targets: Vec<kdf::Target>,
+    emit: Emit,
+    encoding: Encoding,
+    files: std::collections::HashSet<std::path::PathBuf>,
+    number_space: u64,
+    rules: Vec<Rule>,
+    segments: Vec<Segment>,
+    threads: u8,
+    transforms: Pipeline,
+    wordlist: Option<Wordlist>,
+}
+
+impl KdfDecrypt {
+    pub fn kdf(&self) -> kdf::Kdf {
+        self.kdf
+    }
+
+    pub fn targets(&self) -> &[kdf::Target] {
+        &self.targets
+    }
+
+    /// Which value of the cracked hashes is reported
+    pub fn emit(&self) -> Emit {
+        self.emit
+    }
+
+    /// Encoding of the values once transformed
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn files(&self) -> &std::collections::HashSet<std::path::PathBuf> {
+        &self.files
+    }
+
+    /// Number of candidates, for every word with a wordlist
+    pub fn number_space(&self) -> u64 {
+        self.number_space
+    }
+
+    /// Rules mangling every word of the wordlist
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn threads(&self) -> u8 {
+        self.threads
+    }
+
+    /// Transforms applied to the values before they are hashed
+    pub fn transforms(&self) -> &Pipeline {
+        &self.transforms
+    }
+
+    /// Words to insert after the prefix of every segment, before its keyspace
+    pub fn wordlist(&self) -> Option<&Wordlist> {
+        self.wordlist.as_ref()
+    }
+
+    /// Candidates derived from a word by every rule, or the word alone without rules
+    pub fn candidates(&self, word: &str) -> Vec<String> {
+        candidates(&self.rules, word)
+    }
+}
+
+pub struct KdfDecryptBuilder {
+    kdf: kdf::Kdf,
+    targets: std::collections::HashSet<kdf::Target>,
+    lengths: Vec<u8>,
+    checksum: Option<Checksum>,
+    dates: Option<Dates>,
+    emit: Option<Emit>,
+    encoding: Option<Encoding>,
+    files: Option<std::collections::HashSet<std::path::PathBuf>>,
+    masks: Option<Vec<Mask>>,
+    prefixes: Option<Vec<String>>,
+    rules: Option<Vec<Rule>>,
+    threads: Option<u8>,
+    transforms: Option<Vec<Transform>>,
+    wordlist: Option<Wordlist>,
+    xor: Option<Vec<u8>>,
+}
+
+impl KdfDecryptBuilder {
+    pub fn new(
+        kdf: kdf::Kdf,
+        targets: std::collections::HashSet<kdf::Target>,
+        lengths: Vec<u8>,
+    ) -> Self {
+        Self {
+            kdf,
+            targets,
+            lengths,
+            checksum: None,
+            dates: None,
+            emit: None,
+            encoding: None,
+            files: None,
+            masks: None,
+            prefixes: None,
+            rules: None,
+            threads: None,
+            transforms: None,
+            wordlist: None,
+            xor: None,
+        }
+    }
+
+    pub fn checksum(mut self, checksum: impl Into<Option<Checksum>>) -> Self {
+        self.checksum = checksum.into();
+        self
+    }
+
+    pub fn dates(mut self, dates: impl Into<Option<Dates>>) -> Self {
+        self.dates = dates.into();
+        self
+    }
+
+    pub fn emit(mut self, emit: impl Into<Option<Emit>>) -> Self {
+        self.emit = emit.into();
+        self
+    }
+
+    pub fn encoding(mut self, encoding: impl Into<Option<Encoding>>) -> Self {
+        self.encoding = encoding.into();
+        self
+    }
+
+    pub fn files(
+        mut self,
+        files: impl Into<Option<std::collections::HashSet<std::path::PathBuf>>>,
+    ) -> Self {
+        self.files = files.into();
+        self
+    }
+
+    pub fn masks(mut self, masks: impl Into<Option<Vec<Mask>>>) -> Self {
+        self.masks = masks.into();
+        self
+    }
+
+    pub fn prefixes(mut self, prefixes: impl Into<Option<Vec<String>>>) -> Self {
+        self.prefixes = prefixes.into();
+        self
+    }
+
+    pub fn rules(mut self, rules: impl Into<Option<Vec<Rule>>>) -> Self {
+        self.rules = rules.into();
+        self
+    }
+
+    pub fn threads(mut self, threads: impl Into<Option<u8>>) -> Self {
+        self.threads = threads.into();
+        self
+    }
+
+    /// Transforms applied in order to every candidate before it is hashed
+    pub fn transforms(mut self, transforms: impl Into<Option<Vec<Transform>>>) -> Self {
+        self.transforms = transforms.into();
+        self
+    }
+
+    pub fn wordlist(mut self, wordlist: impl Into<Option<Wordlist>>) -> Self {
+        self.wordlist = wordlist.into();
+        self
+    }
+
+    /// Shorthand for the transforms masking candidates with the XOR mask, then encoding them in base64
+    pub fn xor(mut self, xor: impl Into<Option<Vec<u8>>>) -> Self {
+        self.xor = xor.into();
+        self
+    }
+
+    pub fn build(self) -> Result<KdfDecrypt, error::Error> {
+        if self.targets.is_empty() {
+            bail!("No valid input provided");
+        }
+        if self.wordlist.is_none() && self.rules.as_ref().map_or(false, |rules| !rules.is_empty()) {
+            bail!("Rules can only be applied to a wordlist");
+        }
+
+        let segments = segments(
+            &self.lengths,
+            self.masks.as_deref().unwrap_or_default(),
+            self.prefixes.as_deref(),
+            self.dates.as_ref(),
+            self.checksum,
+            self.wordlist.is_some(),
+        )?;
+        let transforms = pipeline(self.transforms, self.xor, &segments)?;
+
+        let mut targets = self.targets.into_iter().collect::<Vec<_>>();
+        targets.sort_unstable();
+
+        Ok(KdfDecrypt {
+            kdf: self.kdf,
+            targets,
+            emit: self.emit.unwrap_or(Emit::Original),
+            encoding: self.encoding.unwrap_or(Encoding::Utf8),
+            files: self
+                .files
+                .unwrap_or_else(|| std::collections::HashSet::with_capacity(0)),
+            number_space: number_space(&segments)?,
+            rules: self.rules.unwrap_or_default(),
+            segments,
+            // Every slow hash is worth a thread, whatever the size of the keyspace
+            threads: threads(self.threads, u64::MAX),
+            transforms,
+            wordlist: self.wordlist,
+        })
+    }
+}
+
+/// Source of the candidate salts of a salt discovery
+pub enum SaltCandidates {
+    List(Vec<String>),
+    Mask(Mask),
+    Wordlist(Wordlist),
+}
+
+impl std::fmt::Display for SaltCandidates {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::List(salts) => write!(fmt, "{} given", salts.len()),
+            Self::Mask(mask) => write!(fmt, "mask {}", mask),
+            Self::Wordlist(wordlist) => write!(fmt, "wordlist {}", wordlist),
+        }
+    }
+}
+
+/// Known pairs of values and hashes, for which the salt is searched
+pub struct SaltFind<H: hash::Hash> {
+    pairs: Vec<(H, String)>,
+    candidates: SaltCandidates,
+    positions: Vec<SaltPosition>,
+    threads: u8,
+}
+
+impl<H: hash::Hash> SaltFind<H> {
+    /// Every salt position is tried if none is given
+    pub fn new(
+        pairs: std::collections::HashMap<H, String>,
+        candidates: SaltCandidates,
+        position: impl Into<Option<SaltPosition>>,
+        requested_threads: impl Into<Option<u8>>,
+    ) -> Result<Self, error::Error> {
+        if pairs.is_empty() {
+            bail!("No valid input provided");
+        }
+
+        let requested_threads = requested_threads.into();
+        let positions = position.into().map_or_else(
+            || {
+                vec![
+                    SaltPosition::Prepend,
+                    SaltPosition::Append,
+                    SaltPosition::Wrap,
+                ]
+            },
+            |position| vec![position],
+        );
+        let threads = match &candidates {
+            SaltCandidates::List(salts) if salts.is_empty() => bail!("No candidate salt provided"),
+            SaltCandidates::List(salts) => threads(
+                requested_threads,
+                (salts.len() * positions.len() * pairs.len()) as u64,
+            ),
+            SaltCandidates::Mask(mask) => threads(
+                requested_threads,
+                mask.number_space()
+                    .saturating_mul((positions.len() * pairs.len()) as u64),
+            ),
+            // The size of wordlists is not known in advance
+            SaltCandidates::Wordlist(_) => threads(requested_threads, u64::MAX),
+        };
+
+        Ok(Self {
+            pairs: pairs.into_iter().collect(),
+            candidates,
+            positions,
+            threads,
+        })
+    }
+
+    pub fn candidates(&self) -> &SaltCandidates {
+        &self.candidates
+    }
+
+    /// Hashes along with the values they were generated from
+    pub fn pairs(&self) -> &[(H, String)] {
+        &self.pairs
+    }
+
+    pub fn positions(&self) -> &[SaltPosition] {
+        &self.positions
+    }
+
+    pub fn threads(&self) -> u8 {
+        self.threads
+    }
+}
+
+/// Known values along with their masked form, from which the XOR mask is recovered
+pub enum XorKnown<H: hash::Hash> {
+    /// Hashes of the masked values once base64 encoded and salted
+    Hashes(Vec<(H, String)>),
+    /// Masked values, decoded from base64
+    Encoded(Vec<(Vec<u8>, String)>),
+}
+
+impl<H: hash::Hash> XorKnown<H> {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Hashes(pairs) => pairs.len(),
+            Self::Encoded(pairs) => pairs.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct XorFind<H: hash::Hash> {
+    known: XorKnown<H>,
+    salt: String,
+    salt_position: SaltPosition,
+    threads: u8,
+}
+
+impl<H: hash::Hash> XorFind<H> {
+    pub fn new(
+        known: XorKnown<H>,
+        salt: String,
+        salt_position: SaltPosition,
+        requested_threads: impl Into<Option<u8>>,
+    ) -> Result<Self, error::Error> {
+        if known.is_empty() {
+            bail!("No valid input provided");
+        }
+
+        Ok(Self {
+            known,
+            salt,
+            salt_position,
+            // The number of unknown bytes of the mask is not known in advance
+            threads: threads(requested_threads.into(), u64::MAX),
+        })
+    }
+
+    pub fn known(&self) -> &XorKnown<H> {
+        &self.known
+    }
+
+    pub fn salt(&self) -> &str {
+        &self.salt
+    }
+
+    pub fn salt_position(&self) -> SaltPosition {
+        self.salt_position
+    }
+
+    /// The salt hashed before the values, according to its position
+    pub fn salt_prefix(&self) -> &str {
+        self.salt_position.prefix(&self.salt)
+    }
+
+    /// The salt hashed after the values, according to its position
+    pub fn salt_suffix(&self) -> &str {
+        self.salt_position.suffix(&self.salt)
+    }
+
+    pub fn threads(&self) -> u8 {
+        self.threads
+    }
+}
+
+// Allowed because a single mode is created per run
+#[allow(clippy::large_enum_variant)]
+pub enum Mode<H: hash::Hash> {
+    Encrypt(Encrypt<H>),
+    Decrypt(Decrypt<H>),
+    SaltFind(SaltFind<H>),
+    XorFind(XorFind<H>),
+}
+
+impl<H: hash::Hash> Mode<H> {
+    pub fn input_len(&self) -> usize {
+        match &self {
+            Self::Encrypt(mode) => mode.shared.input.len(),
+            Self::Decrypt(mode) => mode.shared.input.len(),
+            Self::SaltFind(mode) => mode.pairs.len(),
+            Self::XorFind(mode) => mode.known.len(),
+        }
+    }
+}
+
+// Every prefix is combined with every length and mask, in the order they were given.
+// Dates and words alone are searched without any suffix. Words are inserted after the prefix,
+// so lengths do not include them
+fn segments(
+    lengths: &[u8],
+    masks: &[Mask],
+    prefixes: Option<&[String]>,
+    dates: Option<&Dates>,
+    checksum: Option<Checksum>,
+    words: bool,
+) -> Result<Vec<Segment>, error::Error> {
+    let no_suffix = [Mask::decimal(0)?];
+
+    let masks = match masks {
+        [] if lengths.is_empty() && (dates.is_some() || words) => &no_suffix,
+        [] if lengths.is_empty() => bail!("No length or mask provided"),
+        masks => masks,
+    };
+
+    let empty = [String::new()];
+    let prefixes = match prefixes {
+        Some(prefixes) if !prefixes.is_empty() => prefixes,
+        _ => &empty,
+    };
+
+    let mut segments =
+        Vec::<Segment>::with_capacity(prefixes.len() * (lengths.len() + masks.len()));
+    for prefix in prefixes {
+        let decimals = lengths
+            .iter()
+            .map(|&length| Segment::decimal(prefix.clone(), length, dates, checksum))
+            .collect::<Result<Vec<_>, _>>()?;
+        let masks = masks
+            .iter()
+            .map(|mask| {
+                Segment::new(prefix.clone(), mask.clone())
+                    .with_dates(dates.cloned())?
+                    .with_checksum(checksum)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for segment in decimals.into_iter().chain(masks) {
+            if !segments
+                .iter()
+                .any(|s| s.prefix == segment.prefix && s.mask == segment.mask)
+            {
+                segments.push(segment);
+            }
+        }
+    }
+
+    Ok(segments)
+}
+
+// The number of candidates of every segment together
+fn number_space(segments: &[Segment]) -> Result<u64, error::Error> {
+    segments
+        .iter()
+        .try_fold(0_u64, |space, segment| {
+            space.checked_add(segment.number_space())
+        })
+        .ok_or_else(|| error!("Keyspace is too large"))
+}
+
+// The transforms given, or those of the XOR mask, which must cover the longest candidate
+fn pipeline(
+    transforms: Option<Vec<Transform>>,
+    xor: Option<Vec<u8>>,
+    segments: &[Segment],
+) -> Result<Pipeline, error::Error> {
+    let max_length = segments
+        .iter()
+        .map(|segment| usize::from(segment.length()) + segment.prefix.len())
+        .max()
+        .unwrap_or_default();
+    let transforms = match (transforms, xor) {
+        (Some(_), Some(_)) => bail!("XOR mask cannot be given along with transforms"),
+        (Some(transforms), None) => Pipeline::new(transforms)?,
+        (None, Some(xor)) => Pipeline::xor(xor),
+        (None, None) => Pipeline::default(),
+    };
+    if transforms.length(max_length).is_none() {
+        bail!("XOR mask is not long enough");
+    }
+    Ok(transforms)
+}
+
+// Candidates derived from a word by every rule, or the word alone without rules
+fn candidates(rules: &[Rule], word: &str) -> Vec<String> {
+    if rules.is_empty() {
+        vec![String::from(word)]
+    } else {
+        rules.iter().filter_map(|rule| rule.apply(word)).collect()
+    }
+}
+
+// Allowed because the count was checked for overflow
+#[allow(clippy::cast_possible_truncation)]
+fn threads(requested_count: Option<u8>, number_space: u64) -> u8 {
+    let threads = std::cmp::min(
+        number_space / decrypt::OPTIMAL_HASHES_PER_THREAD + 1,
+        requested_count.map_or_else(
+            || {
+                let cores = num_cpus::get();
+                if cores > usize::from(u8::max_value()) {
+                    u64::from(u8::max_value())
+                } else {
+                    cores as u64
+                }
+            },
+            u64::from,
+        ),
+    );
+
+    // Due to `min`, it will always be less than u8::MAX (255)
+    threads as u8
+}
@@ -0,0 +1,444 @@
+use crate::error;
+
+/// Single function of a rule
+///
+/// Positions and counts are given as `0` to `9` then `A` to `Z` (10 to 35). Functions given a
+/// position out of the word leave it unchanged
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Function {
+    /// `:`
+    Noop,
+    /// `l`
+    Lowercase,
+    /// `u`
+    Uppercase,
+    /// `c`
+    Capitalize,
+    /// `C`
+    InvertCapitalize,
+    /// `t`
+    ToggleCase,
+    /// `TN`
+    ToggleAt(usize),
+    /// `r`
+    Reverse,
+    /// `d`
+    Duplicate,
+    /// `pN`
+    DuplicateTimes(usize),
+    /// `f`
+    Reflect,
+    /// `{`
+    RotateLeft,
+    /// `}`
+    RotateRight,
+    /// `$X`
+    Append(u8),
+    /// `^X`
+    Prepend(u8),
+    /// `[`
+    DeleteFirst,
+    /// `]`
+    DeleteLast,
+    /// `DN`
+    DeleteAt(usize),
+    /// `xNM`
+    Extract(usize, usize),
+    /// `ONM`
+    Omit(usize, usize),
+    /// `iNX`
+    Insert(usize, u8),
+    /// `oNX`
+    Overwrite(usize, u8),
+    /// `'N`
+    Truncate(usize),
+    /// `sXY`
+    Replace(u8, u8),
+    /// `@X`
+    Purge(u8),
+    /// `zN`
+    DuplicateFirst(usize),
+    /// `ZN`
+    DuplicateLast(usize),
+    /// `q`
+    DuplicateEach,
+    /// `k`
+    SwapFront,
+    /// `K`
+    SwapBack,
+    /// `*NM`
+    Swap(usize, usize),
+    /// `<N`, rejects words longer than N
+    RejectLonger(usize),
+    /// `>N`, rejects words shorter than N
+    RejectShorter(usize),
+    /// `_N`, rejects words not exactly N long
+    RejectLength(usize),
+    /// `!X`, rejects words containing X
+    RejectContaining(u8),
+    /// `/X`, rejects words not containing X
+    RejectMissing(u8),
+    /// `(X`, rejects words not starting with X
+    RejectFirst(u8),
+    /// `)X`, rejects words not ending with X
+    RejectLast(u8),
+}
+
+impl Function {
+    // Mangles the word, returning false if it is rejected
+    // Allowed because every function is a single arm of the match
+    #[allow(clippy::too_many_lines)]
+    fn apply(&self, word: &mut Vec<u8>) -> bool {
+        let length = word.len();
+        match *self {
+            Self::Noop => {}
+            Self::Lowercase => word.make_ascii_lowercase(),
+            Self::Uppercase => word.make_ascii_uppercase(),
+            Self::Capitalize => {
+                word.make_ascii_lowercase();
+                if let Some(first) = word.first_mut() {
+                    first.make_ascii_uppercase();
+                }
+            }
+            Self::InvertCapitalize => {
+                word.make_ascii_uppercase();
+                if let Some(first) = word.first_mut() {
+                    first.make_ascii_lowercase();
+                }
+            }
+            Self::ToggleCase => word.iter_mut().for_each(toggle),
+            Self::ToggleAt(n) => {
+                if let Some(c) = word.get_mut(n) {
+                    toggle(c);
+                }
+            }
+            Self::Reverse => word.reverse(),
+            Self::Duplicate => word.extend_from_within(..),
+            Self::DuplicateTimes(n) => {
+                for _ in 0..n {
+                    word.extend_from_within(..length);
+                }
+            }
+            Self::Reflect => {
+                word.extend_from_within(..);
+                word[length..].reverse();
+            }
+            Self::RotateLeft => {
+                if length > 0 {
+                    word.rotate_left(1);
+                }
+            }
+            Self::RotateRight => {
+                if length > 0 {
+                    word.rotate_right(1);
+                }
+            }
+            Self::Append(c) => word.push(c),
+            Self::Prepend(c) => word.insert(0, c),
+            Self::DeleteFirst => {
+                if length > 0 {
+                    word.remove(0);
+                }
+            }
+            Self::DeleteLast => {
+                word.pop();
+            }
+            Self::DeleteAt(n) => {
+                if n < length {
+                    word.remove(n);
+                }
+            }
+            Self::Extract(n, m) => {
+                if n + m <= length {
+                    word.truncate(n + m);
+                    word.drain(..n);
+                }
+            }
+            Self::Omit(n, m) => {
+                if n + m <= length {
+                    word.drain(n..n + m);
+                }
+            }
+            Self::Insert(n, c) => {
+                if n <= length {
+                    word.insert(n, c);
+                }
+            }
+            Self::Overwrite(n, c) => {
+                if let Some(current) = word.get_mut(n) {
+                    *current = c;
+                }
+            }
+            Self::Truncate(n) => word.truncate(n),
+            Self::Replace(from, to) => word
+                .iter_mut()
+                .filter(|c| **c == from)
+                .for_each(|c| *c = to),
+            Self::Purge(c) => word.retain(|&current| current != c),
+            Self::DuplicateFirst(n) => {
+                if let Some(&first) = word.first() {
+                    word.splice(0..0, std::iter::repeat(first).take(n));
+                }
+            }
+            Self::DuplicateLast(n) => {
+                if let Some(&last) = word.last() {
+                    word.extend(std::iter::repeat(last).take(n));
+                }
+            }
+            Self::DuplicateEach => {
+                *word = word
+                    .iter()
+                    .flat_map(|&c| std::iter::repeat(c).take(2))
+                    .collect();
+            }
+            Self::SwapFront => {
+                if length > 1 {
+                    word.swap(0, 1);
+                }
+            }
+            Self::SwapBack => {
+                if length > 1 {
+                    word.swap(length - 2, length - 1);
+                }
+            }
+            Self::Swap(n, m) => {
+                if n < length && m < length {
+                    word.swap(n, m);
+                }
+            }
+            Self::RejectLonger(n) => return length <= n,
+            Self::RejectShorter(n) => return length >= n,
+            Self::RejectLength(n) => return length == n,
+            Self::RejectContaining(c) => return !word.contains(&c),
+            Self::RejectMissing(c) => return word.contains(&c),
+            Self::RejectFirst(c) => return word.first() == Some(&c),
+            Self::RejectLast(c) => return word.last() == Some(&c),
+        }
+        true
+    }
+}
+
+/// Sequence of functions mangling a word, following the hashcat and John the Ripper syntax
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    functions: Vec<Function>,
+}
+
+impl Rule {
+    /// Parses a rule, with functions optionally separated by spaces
+    pub fn parse(rule: &str) -> Result<Self, error::Error> {
+        if !rule.is_ascii() {
+            bail!("Rule '{}' must be ASCII", rule);
+        }
+
+        let mut bytes = rule.bytes();
+        let mut functions = Vec::new();
+        while let Some(name) = bytes.next() {
+            let bytes = &mut bytes;
+            functions.push(match name {
+                b' ' => continue,
+                b':' => Function::Noop,
+                b'l' => Function::Lowercase,
+                b'u' => Function::Uppercase,
+                b'c' => Function::Capitalize,
+                b'C' => Function::InvertCapitalize,
+                b't' => Function::ToggleCase,
+                b'T' => Function::ToggleAt(position(bytes, rule)?),
+                b'r' => Function::Reverse,
+                b'd' => Function::Duplicate,
+                b'p' => Function::DuplicateTimes(position(bytes, rule)?),
+                b'f' => Function::Reflect,
+                b'{' => Function::RotateLeft,
+                b'}' => Function::RotateRight,
+                b'$' => Function::Append(character(bytes, rule)?),
+                b'^' => Function::Prepend(character(bytes, rule)?),
+                b'[' => Function::DeleteFirst,
+                b']' => Function::DeleteLast,
+                b'D' => Function::DeleteAt(position(bytes, rule)?),
+                b'x' => Function::Extract(position(bytes, rule)?, position(bytes, rule)?),
+                b'O' => Function::Omit(position(bytes, rule)?, position(bytes, rule)?),
+                b'i' => Function::Insert(position(bytes, rule)?, character(bytes, rule)?),
+                b'o' => Function::Overwrite(position(bytes, rule)?, character(bytes, rule)?),
+                b'\'' => Function::Truncate(position(bytes, rule)?),
+                b's' => Function::Replace(character(bytes, rule)?, character(bytes, rule)?),
+                b'@' => Function::Purge(character(bytes, rule)?),
+                b'z' => Function::DuplicateFirst(position(bytes, rule)?),
+                b'Z' => Function::DuplicateLast(position(bytes, rule)?),
+                b'q' => Function::DuplicateEach,
+                b'k' => Function::SwapFront,
+                b'K' => Function::SwapBack,
+                b'*' => Function::Swap(position(bytes, rule)?, position(bytes, rule)?),
+                b'<' => Function::RejectLonger(position(bytes, rule)?),
+                b'>' => Function::RejectShorter(position(bytes, rule)?),
+                b'_' => Function::RejectLength(position(bytes, rule)?),
+                b'!' => Function::RejectContaining(character(bytes, rule)?),
+                b'/' => Function::RejectMissing(character(bytes, rule)?),
+                b'(' => Function::RejectFirst(character(bytes, rule)?),
+                b')' => Function::RejectLast(character(bytes, rule)?),
+                c => bail!(
+                    "Rule '{}' has an unknown function '{}'",
+                    rule,
+                    char::from(c)
+                ),
+            });
+        }
+
+        if functions.is_empty() {
+            bail!("Rule is empty");
+        }
+
+        Ok(Self { functions })
+    }
+
+    /// Mangles a word, returning `None` if it is rejected
+    pub fn apply(&self, word: &str) -> Option<String> {
+        let mut word = word.as_bytes().to_vec();
+        for function in &self.functions {
+            if !function.apply(&mut word) {
+                return None;
+            }
+        }
+        String::from_utf8(word).ok()
+    }
+}
+
+fn character(bytes: &mut impl Iterator<Item = u8>, rule: &str) -> Result<u8, error::Error> {
+    bytes
+        .next()
+        .ok_or_else(|| error!("Rule '{}' ends with an incomplete function", rule))
+}
+
+fn position(bytes: &mut impl Iterator<Item = u8>, rule: &str) -> Result<usize, error::Error> {
+    match character(bytes, rule)? {
+        c @ b'0'..=b'9' => Ok(usize::from(c - b'0')),
+        c @ b'A'..=b'Z' => Ok(usize::from(c - b'A') + 10),
+        c => bail!(
+            "Rule '{}' has an invalid position '{}'",
+            rule,
+            char::from(c)
+        ),
+    }
+}
+
+fn toggle(c: &mut u8) {
+    if c.is_ascii_lowercase() {
+        c.make_ascii_uppercase();
+    } else {
+        c.make_ascii_lowercase();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Rule;
+
+    fn apply(rule: &str, word: &str) -> Option<String> {
+        Rule::parse(rule).unwrap().apply(word)
+    }
+
+    fn mangle(rule: &str) -> String {
+        apply(rule, "p@ssW0rd").unwrap()
+    }
+
+    #[test]
+    fn case() {
+        assert_eq!(mangle("l"), "p@ssw0rd");
+        assert_eq!(mangle("u"), "P@SSW0RD");
+        assert_eq!(mangle("c"), "P@ssw0rd");
+        assert_eq!(mangle("C"), "p@SSW0RD");
+        assert_eq!(mangle("t"), "P@SSw0RD");
+        assert_eq!(mangle("T3"), "p@sSW0rd");
+        assert_eq!(mangle("TZ"), "p@ssW0rd");
+    }
+
+    #[test]
+    fn order() {
+        assert_eq!(mangle("r"), "dr0Wss@p");
+        assert_eq!(mangle("d"), "p@ssW0rdp@ssW0rd");
+        assert_eq!(mangle("p2"), "p@ssW0rdp@ssW0rdp@ssW0rd");
+        assert_eq!(mangle("f"), "p@ssW0rddr0Wss@p");
+        assert_eq!(mangle("{"), "@ssW0rdp");
+        assert_eq!(mangle("}"), "dp@ssW0r");
+        assert_eq!(mangle("k"), "@pssW0rd");
+        assert_eq!(mangle("K"), "p@ssW0dr");
+        assert_eq!(mangle("*34"), "p@sWs0rd");
+        assert_eq!(mangle("*3Z"), "p@ssW0rd");
+    }
+
+    #[test]
+    fn insertion() {
+        assert_eq!(mangle("$1"), "p@ssW0rd1");
+        assert_eq!(mangle("^1"), "1p@ssW0rd");
+        assert_eq!(mangle("$1 $2 $3"), "p@ssW0rd123");
+        assert_eq!(mangle("$ "), "p@ssW0rd ");
+        assert_eq!(mangle("i4!"), "p@ss!W0rd");
+        assert_eq!(mangle("i8!"), "p@ssW0rd!");
+        assert_eq!(mangle("i9!"), "p@ssW0rd");
+        assert_eq!(mangle("o3$"), "p@s$W0rd");
+        assert_eq!(mangle("o8$"), "p@ssW0rd");
+        assert_eq!(mangle("z2"), "ppp@ssW0rd");
+        assert_eq!(mangle("Z2"), "p@ssW0rddd");
+        assert_eq!(mangle("q"), "pp@@ssssWW00rrdd");
+    }
+
+    #[test]
+    fn deletion() {
+        assert_eq!(mangle("["), "@ssW0rd");
+        assert_eq!(mangle("]"), "p@ssW0r");
+        assert_eq!(mangle("D3"), "p@sW0rd");
+        assert_eq!(mangle("D8"), "p@ssW0rd");
+        assert_eq!(mangle("x04"), "p@ss");
+        assert_eq!(mangle("x46"), "p@ssW0rd");
+        assert_eq!(mangle("O12"), "psW0rd");
+        assert_eq!(mangle("'6"), "p@ssW0");
+        assert_eq!(mangle("'9"), "p@ssW0rd");
+        assert_eq!(mangle("@s"), "p@W0rd");
+        assert_eq!(apply("[", ""), Some(String::new()));
+        assert_eq!(apply("]", ""), Some(String::new()));
+    }
+
+    #[test]
+    fn substitution() {
+        assert_eq!(mangle("ss$"), "p@$$W0rd");
+        assert_eq!(mangle("sa4"), "p@ssW0rd");
+        assert_eq!(
+            apply("sa4 se3 so0", "awesome"),
+            Some(String::from("4w3s0m3"))
+        );
+    }
+
+    #[test]
+    fn rejection() {
+        assert_eq!(apply("<8", "p@ssW0rd"), Some(String::from("p@ssW0rd")));
+        assert_eq!(apply("<7", "p@ssW0rd"), None);
+        assert_eq!(apply(">8", "p@ssW0rd"), Some(String::from("p@ssW0rd")));
+        assert_eq!(apply(">9", "p@ssW0rd"), None);
+        assert_eq!(apply("_8", "p@ssW0rd"), Some(String::from("p@ssW0rd")));
+        assert_eq!(apply("_7", "p@ssW0rd"), None);
+        assert_eq!(apply("!@", "p@ssW0rd"), None);
+        assert_eq!(apply("!x", "p@ssW0rd"), Some(String::from("p@ssW0rd")));
+        assert_eq!(apply("/@", "p@ssW0rd"), Some(String::from("p@ssW0rd")));
+        assert_eq!(apply("/x", "p@ssW0rd"), None);
+        assert_eq!(apply("(p", "p@ssW0rd"), Some(String::from("p@ssW0rd")));
+        assert_eq!(apply("(d", "p@ssW0rd"), None);
+        assert_eq!(apply(")d", "p@ssW0rd"), Some(String::from("p@ssW0rd")));
+        assert_eq!(apply(")p", "p@ssW0rd"), None);
+        // Rejections apply to the word mangled so far
+        assert_eq!(apply("$1 <8", "p@ssW0rd"), None);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(mangle(":"), "p@ssW0rd");
+        assert_eq!(mangle("c$1$2"), mangle("c $1 $2"));
+        assert_eq!(mangle("'A"), "p@ssW0rd");
+        assert!(Rule::parse("").is_err());
+        assert!(Rule::parse("  ").is_err());
+        assert!(Rule::parse("$").is_err());
+        assert!(Rule::parse("sa").is_err());
+        assert!(Rule::parse("T").is_err());
+        assert!(Rule::parse("Ta").is_err());
+        assert!(Rule::parse("w").is_err());
+        assert!(Rule::parse("$é").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error;
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// A transform of plain values, applied before hashing
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transform {
+    Xor(Vec<u8>),
+    Base64,
+    Base64Url,
+    Hex,
+    Reverse,
+    Upper,
+    Lower,
+    /// Left padding up to a width, with a fill character
+    Pad(u8, u8),
+}
+
+impl Transform {
+    /// Parses a transform by its name, or `pad:<width>[:<fill>]` which fills with '0' by default
+    ///
+    /// The mask of `xor` must be given
+    pub fn parse(spec: &str, xor: Option<&[u8]>) -> Result<Self, error::Error> {
+        let mut parts = spec.splitn(3, ':');
+        let name = parts.next().unwrap_or_default();
+        Ok(match name.to_lowercase().as_str() {
+            "xor" => Self::Xor(
+                xor.map(<[u8]>::to_vec)
+                    .ok_or_else(|| error!("No XOR mask given for the xor transform"))?,
+            ),
+            "base64" => Self::Base64,
+            "base64url" => Self::Base64Url,
+            "hex" => Self::Hex,
+            "reverse" => Self::Reverse,
+            "upper" => Self::Upper,
+            "lower" => Self::Lower,
+            "pad" => {
+                let width = parts
+                    .next()
+                    .and_then(|width| width.parse::<u8>().ok())
+                    .ok_or_else(|| error!("Invalid padding width in '{}'", spec))?;
+                let fill = match parts.next().map(str::as_bytes) {
+                    None => b'0',
+                    Some(&[fill]) if fill.is_ascii() => fill,
+                    Some(_) => bail!(
+                        "Padding fill of '{}' must be a single ASCII character",
+                        spec
+                    ),
+                };
+                Self::Pad(width, fill)
+            }
+            _ => bail!(
+                "Unknown transform '{}', expected one of xor, base64, base64url, hex, reverse, \
+                 upper, lower or pad:<width>[:<fill>]",
+                spec
+            ),
+        })
+    }
+
+    /// Length of values of `length` once transformed, if they can be
+    pub fn length(&self, length: usize) -> Option<usize> {
+        match self {
+            Self::Xor(mask) if length > mask.len() => None,
+            Self::Base64 | Self::Base64Url => Some((length + 2) / 3 * 4),
+            Self::Hex => Some(length * 2),
+            Self::Pad(width, _) => Some(std::cmp::max(length, usize::from(*width))),
+            Self::Xor(_) | Self::Reverse | Self::Upper | Self::Lower => Some(length),
+        }
+    }
+
+    /// Values longer than the XOR mask are not transformed
+    pub fn apply(&self, mut value: Vec<u8>) -> Option<Vec<u8>> {
+        match self {
+            Self::Xor(mask) => {
+                if value.len() > mask.len() {
+                    return None;
+                }
+                value.iter_mut().zip(mask).for_each(|(b, m)| *b ^= m);
+            }
+            Self::Base64 => value = base64::encode(&value).into_bytes(),
+            Self::Base64Url => value = base64::encode_config(&value, base64::URL_SAFE).into_bytes(),
+            Self::Hex => {
+                let mut hex = Vec::with_capacity(value.len() * 2);
+                for byte in value {
+                    hex.push(HEX[usize::from(byte >> 4)]);
+                    hex.push(HEX[usize::from(byte & 0xf)]);
+                }
+                value = hex;
+            }
+            Self::Reverse => value.reverse(),
+            Self::Upper => value.make_ascii_uppercase(),
+            Self::Lower => value.make_ascii_lowercase(),
+            Self::Pad(width, fill) => {
+                let width = usize::from(*width);
+                if value.len() < width {
+                    value.splice(0..0, vec![*fill; width - value.len()]);
+                }
+            }
+        }
+        Some(value)
+    }
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Xor(_) => write!(fmt, "xor"),
+            Self::Base64 => write!(fmt, "base64"),
+            Self::Base64Url => write!(fmt, "base64url"),
+            Self::Hex => write!(fmt, "hex"),
+            Self::Reverse => write!(fmt, "reverse"),
+            Self::Upper => write!(fmt, "upper"),
+            Self::Lower => write!(fmt, "lower"),
+            Self::Pad(width, fill) => write!(fmt, "pad:{}:{}", width, char::from(*fill)),
+        }
+    }
+}
+
+/// Transforms applied in order to plain values before hashing
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pipeline(Vec<Transform>);
+
+impl Pipeline {
+    pub fn new(transforms: Vec<Transform>) -> Result<Self, error::Error> {
+        // Masked bytes are only text once encoded
+        let mut masked = false;
+        for transform in &transforms {
+            match transform {
+                Transform::Xor(_) => masked = true,
+                Transform::Base64 | Transform::Base64Url | Transform::Hex => masked = false,
+                _ => {}
+            }
+        }
+        if masked {
+            bail!("An xor transform must be followed by base64, base64url or hex");
+        }
+
+        Ok(Self(transforms))
+    }
+
+    /// XOR with the mask, then base64 encoding
+    pub fn xor(mask: Vec<u8>) -> Self {
+        Self(vec![Transform::Xor(mask), Transform::Base64])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn transforms(&self) -> &[Transform] {
+        &self.0
+    }
+
+    /// Length of values of `length` once transformed, if they can be
+    pub fn length(&self, length: usize) -> Option<usize> {
+        self.0
+            .iter()
+            .try_fold(length, |length, transform| transform.length(length))
+    }
+
+    /// Longest length of values of `length` while they are transformed, if they can be
+    pub fn max_length(&self, length: usize) -> Option<usize> {
+        self.0
+            .iter()
+            .try_fold((length, length), |(length, max), transform| {
+                transform
+                    .length(length)
+                    .map(|length| (length, std::cmp::max(length, max)))
+            })
+            .map(|(_, max)| max)
+    }
+
+    /// Values that cannot be transformed or that are not text once transformed are skipped
+    pub fn apply(&self, value: &str) -> Option<String> {
+        if self.0.is_empty() {
+            return Some(String::from(value));
+        }
+
+        let bytes = self
+            .0
+            .iter()
+            .try_fold(value.as_bytes().to_vec(), |bytes, transform| {
+                transform.apply(bytes)
+            })?;
+        String::from_utf8(bytes).ok()
+    }
+}
+
+impl std::fmt::Display for Pipeline {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let transforms = self.0.iter().map(ToString::to_string).collect::<Vec<_>>();
+        write!(fmt, "{}", transforms.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Pipeline, Transform};
+
+    fn pipeline(specs: &[&str]) -> Pipeline {
+        Pipeline::new(
+            specs
+                .iter()
+                .map(|spec| Transform::parse(spec, Some(&[1, 2, 3, 4])))
+                .collect::<Result<_, _>>()
+                .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn transforms() {
+        assert_eq!(pipeline(&[]).apply("abc").unwrap(), "abc");
+        assert_eq!(
+            pipeline(&["reverse", "upper"]).apply("abc1").unwrap(),
+            "1CBA"
+        );
+        assert_eq!(pipeline(&["lower", "hex"]).apply("AB").unwrap(), "6162");
+        assert_eq!(pipeline(&["pad:5"]).apply("12").unwrap(), "00012");
+        assert_eq!(pipeline(&["pad:4:x"]).apply("12345").unwrap(), "12345");
+        assert_eq!(
+            pipeline(&["base64"]).apply("\u{fb}\u{ff}").unwrap(),
+            "w7vDvw=="
+        );
+        assert_eq!(pipeline(&["base64"]).apply("??>").unwrap(), "Pz8+");
+        assert_eq!(pipeline(&["base64url"]).apply("??>").unwrap(), "Pz8-");
+        assert_eq!(pipeline(&["base64"]).apply("???").unwrap(), "Pz8/");
+        assert_eq!(pipeline(&["base64url"]).apply("???").unwrap(), "Pz8_");
+        assert_eq!(
+            pipeline(&["xor", "base64"]).apply("1234").unwrap(),
+            base64::encode([0x30, 0x30, 0x30, 0x30])
+        );
+        assert_eq!(pipeline(&["xor", "base64"]).apply("12345"), None);
+    }
+
+    #[test]
+    fn lengths() {
+        let pipeline = pipeline(&["pad:6", "hex", "base64"]);
+        assert_eq!(pipeline.length(2), Some(16));
+        assert_eq!(pipeline.max_length(2), Some(16));
+        assert_eq!(pipeline.apply("12").unwrap().len(), 16);
+
+        let pipeline = super::Pipeline::xor(vec![0; 4]);
+        assert_eq!(pipeline.length(4), Some(8));
+        assert_eq!(pipeline.length(5), None);
+    }
+
+    #[test]
+    fn parse() {
+        assert!(Transform::parse("xor", None).is_err());
+        assert!(Transform::parse("pad", None).is_err());
+        assert!(Transform::parse("pad:4:ab", None).is_err());
+        assert!(Transform::parse("rot13", None).is_err());
+        assert_eq!(
+            Transform::parse("PAD:4: ", None).unwrap(),
+            Transform::Pad(4, b' ')
+        );
+        assert!(Pipeline::new(vec![Transform::Xor(vec![1])]).is_err());
+        assert!(Pipeline::new(vec![Transform::Xor(vec![1]), Transform::Reverse]).is_err());
+        assert!(Pipeline::new(vec![Transform::Xor(vec![1]), Transform::Hex]).is_ok());
+    }
+}
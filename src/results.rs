@@ -1,3 +1,5 @@
+use crate::options;
+
 pub struct Summary {
     pub total_count: usize,
     pub duration: std::time::Duration,
@@ -9,11 +11,42 @@ pub struct Summary {
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Pair {
     pub hash: String,
+    /// The value which was hashed
     pub plain: String,
+    /// The value before it was transformed for hashing, if it differs
+    pub original: Option<String>,
 }
 
 impl Pair {
     pub fn new(hash: String, plain: String) -> Self {
-        Self { hash, plain }
+        Self {
+            hash,
+            plain,
+            original: None,
+        }
+    }
+
+    /// A pair whose hashed value was transformed from the original one
+    pub fn transformed(hash: String, plain: String, original: String) -> Self {
+        if plain == original {
+            Self::new(hash, plain)
+        } else {
+            Self {
+                hash,
+                plain,
+                original: Some(original),
+            }
+        }
+    }
+
+    pub fn original(&self) -> &str {
+        self.original.as_deref().unwrap_or(&self.plain)
+    }
+
+    pub fn emitted(&self, emit: options::Emit) -> &str {
+        match emit {
+            options::Emit::Original => self.original(),
+            options::Emit::Hashed => &self.plain,
+        }
     }
 }
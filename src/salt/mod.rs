@@ -0,0 +1,281 @@
+use crate::channel;
+use crate::decrypt;
+use crate::error;
+use crate::files;
+use crate::hash;
+use crate::options;
+use crate::results;
+
+use decrypt::{Sender, OPTIMAL_HASHES_PER_THREAD};
+
+/// Hashes the value of every known pair with every candidate salt, in every salt position
+pub fn execute<H: hash::Hash>(
+    options: &options::SaltFind<H>,
+    channel: &impl channel::Channel,
+) -> Result<results::Summary, error::Error> {
+    let time = std::time::Instant::now();
+
+    let count = std::sync::atomic::AtomicUsize::new(options.pairs().len());
+    let found = std::sync::Mutex::new(std::collections::HashSet::new());
+    let mut candidates = Candidates::open(options.candidates())?;
+
+    // Every salt is hashed with the value of every pair, in every position
+    // Allowed because the batch size is far below usize::MAX
+    #[allow(clippy::cast_possible_truncation)]
+    let batch_size = std::cmp::max(
+        u64::from(options.threads()) * OPTIMAL_HASHES_PER_THREAD
+            / ((options.positions().len() * options.pairs().len()) as u64),
+        1,
+    ) as usize;
+
+    let mut hash_count = 0;
+    let mut results = Vec::with_capacity(options.pairs().len());
+
+    channel.progress(0);
+    while !channel.should_terminate() && count.load(std::sync::atomic::Ordering::Acquire) > 0 {
+        let batch = candidates.next_batch(batch_size)?;
+        if batch.is_empty() {
+            break;
+        }
+
+        let (batch_count, batch_results) = execute_salts(options, &batch, &count, &found, channel)?;
+        hash_count += batch_count;
+        results.extend(batch_results);
+        channel.progress(candidates.progress());
+    }
+
+    Ok(results::Summary {
+        total_count: options.pairs().len(),
+        duration: time.elapsed(),
+        hash_count,
+        threads: u32::from(options.threads()),
+        results,
+    })
+}
+
+/// Candidate salts, read in batches along with how many were read
+enum Candidates<'a> {
+    List(&'a [String], usize),
+    Mask(&'a options::Mask, u64),
+    Words(files::Words),
+}
+
+impl<'a> Candidates<'a> {
+    fn open(candidates: &'a options::SaltCandidates) -> Result<Self, error::Error> {
+        Ok(match candidates {
+            options::SaltCandidates::List(salts) => Self::List(salts, 0),
+            options::SaltCandidates::Mask(mask) => Self::Mask(mask, 0),
+            options::SaltCandidates::Wordlist(wordlist) => {
+                Self::Words(files::Words::open(wordlist)?)
+            }
+        })
+    }
+
+    fn next_batch(&mut self, count: usize) -> Result<Vec<String>, error::Error> {
+        match self {
+            Self::List(salts, read) => {
+                let end = std::cmp::min(*read + count, salts.len());
+                let batch = salts[*read..end].to_vec();
+                *read = end;
+                Ok(batch)
+            }
+            Self::Mask(mask, read) => {
+                let end = std::cmp::min(*read + count as u64, mask.number_space());
+                let batch = (*read..end).map(|n| mask.candidate(n)).collect();
+                *read = end;
+                Ok(batch)
+            }
+            Self::Words(words) => words.next_batch(count),
+        }
+    }
+
+    // Allowed because of division; value will stay in bound
+    #[allow(clippy::cast_possible_truncation)]
+    fn progress(&self) -> u8 {
+        match self {
+            Self::List(salts, read) => (read * 100 / std::cmp::max(salts.len(), 1)) as u8,
+            Self::Mask(mask, read) => (read * 100 / std::cmp::max(mask.number_space(), 1)) as u8,
+            Self::Words(words) => words.progress(),
+        }
+    }
+}
+
+// Every salt is tried in every position, and these combinations are split between the threads
+// Allowed because the `Sender` needs a sized type to point to
+#[allow(clippy::ptr_arg)]
+fn execute_salts<H: hash::Hash>(
+    options: &options::SaltFind<H>,
+    salts: &Vec<String>,
+    count: &std::sync::atomic::AtomicUsize,
+    found: &std::sync::Mutex<std::collections::HashSet<H>>,
+    channel: &impl channel::Channel,
+) -> Result<(u64, Vec<results::Pair>), error::Error> {
+    let space = salts.len() * options.positions().len();
+    let thread_count = usize::from(options.threads());
+    let thread_space = (space + thread_count - 1) / thread_count;
+    let mut threads = Vec::<_>::with_capacity(thread_count);
+
+    for t in 0..thread_count {
+        let options_sender = Sender(options);
+        let salts_sender = Sender(salts);
+        let count_sender = Sender(count);
+        let found_sender = Sender(found);
+        let channel_sender = Sender(channel);
+
+        let first = std::cmp::min(t * thread_space, space);
+        let last = std::cmp::min(first + thread_space, space);
+
+        threads.push(std::thread::spawn(move || {
+            let options = options_sender;
+            let salts = salts_sender;
+            let count = count_sender;
+            let found = found_sender;
+            let channel = channel_sender;
+            let positions = options.positions();
+            let mut hash_count = 0;
+            let mut discovered = Vec::new();
+
+            for unit in first..last {
+                let salt = &salts[unit / positions.len()];
+                let position = positions[unit % positions.len()];
+                let prefix = position.prefix(salt);
+                let suffix = position.suffix(salt);
+
+                for (hash, value) in options.pairs() {
+                    if hash_count & (OPTIMAL_HASHES_PER_THREAD - 1) == OPTIMAL_HASHES_PER_THREAD - 1
+                        && (channel.should_terminate()
+                            || count.load(std::sync::atomic::Ordering::Relaxed) == 0)
+                    {
+                        return (hash_count, discovered);
+                    }
+
+                    hash_count += 1;
+                    if H::digest(prefix, value, suffix) != *hash {
+                        continue;
+                    }
+
+                    // Several salts or positions may reproduce a hash, each is only reported once
+                    if !found.lock().map_or(false, |mut found| found.insert(*hash)) {
+                        continue;
+                    }
+
+                    let remaining = count.fetch_sub(1, std::sync::atomic::Ordering::Release);
+                    let result = format!("{}:{}", position, salt);
+                    channel.result(&format!("{:x}", hash), &result);
+                    discovered.push(results::Pair::new(hash.to_string(), result));
+                    if remaining == 1 {
+                        return (hash_count, discovered);
+                    }
+                }
+            }
+            (hash_count, discovered)
+        }));
+    }
+
+    threads
+        .into_iter()
+        .map(|t| t.join().map_err(error::on_join))
+        .fold(Ok((0, Vec::new())), |acc, curr| {
+            if let Ok(mut acc) = acc {
+                curr.map(|(count, results)| {
+                    (acc.0 + count, {
+                        acc.1.extend(results);
+                        acc.1
+                    })
+                })
+            } else {
+                acc
+            }
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{channel, execute, hash, options, results};
+    use crate::hash::Hash;
+
+    #[derive(Copy, Clone)]
+    struct Channel;
+
+    impl channel::Channel for Channel {
+        fn progress(&self, _: u8) {}
+        fn result(&self, _: &str, _: &str) {}
+        fn should_terminate(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_salt_list() {
+        let prepended = hash::sha256::Hash::digest("abc", "1234", "");
+        let appended = hash::sha256::Hash::digest("", "5678", "abc");
+
+        let mut expected = vec![
+            results::Pair::new(prepended.to_string(), String::from("prepend:abc")),
+            results::Pair::new(appended.to_string(), String::from("append:abc")),
+        ];
+        expected.sort();
+
+        let options = options::SaltFind::new(
+            vec![
+                (prepended, String::from("1234")),
+                (appended, String::from("5678")),
+            ]
+            .into_iter()
+            .collect(),
+            options::SaltCandidates::List(vec![
+                String::from("old"),
+                String::from("abc"),
+                String::from("new"),
+            ]),
+            None,
+            4,
+        )
+        .unwrap();
+
+        let summary = execute(&options, &Channel).unwrap();
+        let mut results = summary.results;
+        results.sort();
+
+        assert_eq!(summary.total_count, 2);
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_salt_mask() {
+        let wrapped = hash::md5::Hash::digest("42", "value", "42");
+
+        let options = options::SaltFind::new(
+            std::iter::once((wrapped, String::from("value"))).collect(),
+            options::SaltCandidates::Mask(options::Mask::parse("?d?d", &[]).unwrap()),
+            options::SaltPosition::Wrap,
+            4,
+        )
+        .unwrap();
+
+        let results = execute(&options, &Channel).unwrap().results;
+
+        assert_eq!(
+            results,
+            vec![results::Pair::new(
+                wrapped.to_string(),
+                String::from("wrap:42")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_salt_not_found() {
+        let hash = hash::sha256::Hash::digest("abc", "1234", "");
+
+        let options = options::SaltFind::new(
+            std::iter::once((hash, String::from("1234"))).collect(),
+            options::SaltCandidates::List(vec![String::from("xyz")]),
+            options::SaltPosition::Prepend,
+            1,
+        )
+        .unwrap();
+
+        assert!(execute(&options, &Channel).unwrap().results.is_empty());
+    }
+}
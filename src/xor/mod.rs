@@ -0,0 +1,274 @@
+use crate::channel;
+use crate::decrypt;
+use crate::error;
+use crate::hash;
+use crate::options;
+use crate::results;
+
+use decrypt::{Sender, OPTIMAL_HASHES_PER_THREAD};
+
+/// Most bytes of the mask searched at once, as every byte multiplies the search by 256
+const MAX_SEARCHED_BYTES: usize = 3;
+
+/// Recovers the XOR mask reproducing the known values, reporting it base64 encoded
+pub fn execute<H: hash::Hash>(
+    options: &options::XorFind<H>,
+    channel: &impl channel::Channel,
+) -> Result<results::Summary, error::Error> {
+    let time = std::time::Instant::now();
+
+    channel.progress(0);
+    let (mask, hash_count, results) = match options.known() {
+        options::XorKnown::Encoded(pairs) => {
+            let (mask, results) = derive(pairs)?;
+            (mask, 0, results)
+        }
+        options::XorKnown::Hashes(pairs) => search(options, pairs, channel)?,
+    };
+
+    if !mask.is_empty() {
+        channel.result("xor", &base64::encode(&mask));
+    }
+
+    Ok(results::Summary {
+        total_count: options.known().len(),
+        duration: time.elapsed(),
+        hash_count,
+        threads: u32::from(options.threads()),
+        results,
+    })
+}
+
+// Every byte of a masked value is the byte of the value XOR the byte of the mask
+fn derive(pairs: &[(Vec<u8>, String)]) -> Result<(Vec<u8>, Vec<results::Pair>), error::Error> {
+    let mut mask = Vec::new();
+    for (encoded, value) in pairs {
+        if encoded.len() != value.len() {
+            bail!("Masked value of '{}' does not have the same length", value);
+        }
+
+        for (index, byte) in encoded
+            .iter()
+            .zip(value.bytes())
+            .map(|(e, v)| e ^ v)
+            .enumerate()
+        {
+            match mask.get(index) {
+                Some(&known) if known != byte => {
+                    bail!("Byte {} of the XOR mask differs for '{}'", index, value)
+                }
+                Some(_) => {}
+                None => mask.push(byte),
+            }
+        }
+    }
+
+    let results = pairs
+        .iter()
+        .map(|(encoded, value)| results::Pair::new(base64::encode(encoded), value.clone()))
+        .collect();
+    Ok((mask, results))
+}
+
+// Values are searched from the shortest, each one recovering the bytes of the mask past the
+// previous ones
+fn search<H: hash::Hash>(
+    options: &options::XorFind<H>,
+    pairs: &[(H, String)],
+    channel: &impl channel::Channel,
+) -> Result<(Vec<u8>, u64, Vec<results::Pair>), error::Error> {
+    let mut pairs = pairs.iter().collect::<Vec<_>>();
+    pairs.sort_by_key(|(_, value)| value.len());
+
+    let mut mask = Vec::new();
+    let mut hash_count = 0;
+    let mut results = Vec::with_capacity(pairs.len());
+
+    for (index, (hash, value)) in pairs.iter().enumerate() {
+        if channel.should_terminate() {
+            break;
+        }
+
+        let missing = value.len().saturating_sub(mask.len());
+        if missing > MAX_SEARCHED_BYTES {
+            bail!(
+                "'{}' is {} bytes longer than the recovered mask, but at most {} can be searched \
+                 at once: known values of the lengths in between are needed",
+                value,
+                missing,
+                MAX_SEARCHED_BYTES
+            );
+        }
+
+        let (count, bytes) = search_bytes(options, &mask, value, *hash, missing, channel)?;
+        hash_count += count;
+        if let Some(bytes) = bytes {
+            mask.extend(bytes);
+            results.push(results::Pair::new(hash.to_string(), String::clone(value)));
+        }
+
+        // Allowed because of division; value will stay in bound
+        #[allow(clippy::cast_possible_truncation)]
+        channel.progress(((index + 1) * 100 / pairs.len()) as u8);
+    }
+
+    Ok((mask, hash_count, results))
+}
+
+// Every combination of the missing bytes is split between the threads
+// Allowed because the `Sender` needs a sized type to point to
+#[allow(clippy::ptr_arg)]
+fn search_bytes<H: hash::Hash>(
+    options: &options::XorFind<H>,
+    mask: &Vec<u8>,
+    value: &String,
+    hash: H,
+    missing: usize,
+    channel: &impl channel::Channel,
+) -> Result<(u64, Option<Vec<u8>>), error::Error> {
+    let space = 1_u64 << (8 * missing);
+    let thread_count = u64::from(options.threads());
+    let thread_space = (space + thread_count - 1) / thread_count;
+    let bytes = std::sync::Mutex::new(None);
+    let found = &bytes;
+    let mut threads = Vec::<_>::with_capacity(options.threads() as usize);
+
+    for t in 0..thread_count {
+        let options_sender = Sender(options);
+        let mask_sender = Sender(mask);
+        let value_sender = Sender(value);
+        let found_sender = Sender(found);
+        let channel_sender = Sender(channel);
+
+        let first = std::cmp::min(t * thread_space, space);
+        let last = std::cmp::min(first + thread_space, space);
+
+        threads.push(std::thread::spawn(move || {
+            let options = options_sender;
+            let value = value_sender;
+            let found = found_sender;
+            let channel = channel_sender;
+
+            let known = value.len() - missing;
+            let mut candidate = mask_sender[..known].to_vec();
+            candidate.resize(value.len(), 0);
+
+            for n in first..last {
+                if n & (OPTIMAL_HASHES_PER_THREAD - 1) == OPTIMAL_HASHES_PER_THREAD - 1
+                    && (channel.should_terminate()
+                        || found.lock().map_or(true, |found| found.is_some()))
+                {
+                    return n - first;
+                }
+
+                candidate[known..].copy_from_slice(&n.to_le_bytes()[..missing]);
+                let xored = value
+                    .bytes()
+                    .zip(&candidate)
+                    .map(|(v, m)| v ^ m)
+                    .collect::<Vec<_>>();
+                let digest = H::digest(
+                    options.salt_prefix(),
+                    &base64::encode(xored),
+                    options.salt_suffix(),
+                );
+                if digest == hash {
+                    if let Ok(mut found) = found.lock() {
+                        *found = Some(candidate[known..].to_vec());
+                    }
+                    return n - first + 1;
+                }
+            }
+            last - first
+        }));
+    }
+
+    let hash_count = threads
+        .into_iter()
+        .map(|t| t.join().map_err(error::on_join))
+        .sum::<Result<u64, _>>()?;
+    let bytes = bytes
+        .into_inner()
+        .map_err(|_| error!("Could not collect the XOR mask"))?;
+    Ok((hash_count, bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{channel, execute, hash, options};
+    use crate::hash::Hash;
+
+    // Keeps the reported mask
+    struct Channel(std::sync::Mutex<String>);
+
+    impl channel::Channel for Channel {
+        fn progress(&self, _: u8) {}
+        fn result(&self, _: &str, output: &str) {
+            if let Ok(mut mask) = self.0.lock() {
+                *mask = String::from(output);
+            }
+        }
+        fn should_terminate(&self) -> bool {
+            false
+        }
+    }
+
+    const MASK: [u8; 5] = [0x3c, 0xa1, 0x07, 0xff, 0x5e];
+
+    fn xored(value: &str) -> Vec<u8> {
+        value.bytes().zip(&MASK).map(|(v, m)| v ^ m).collect()
+    }
+
+    fn recover<H: hash::Hash>(known: options::XorKnown<H>, salt: &str) -> Option<String> {
+        let options =
+            options::XorFind::new(known, String::from(salt), options::SaltPosition::Prepend, 4)
+                .unwrap();
+
+        let channel = Channel(std::sync::Mutex::new(String::new()));
+        let summary = execute(&options, &channel).ok()?;
+        assert_eq!(summary.results.len(), summary.total_count);
+        channel.0.into_inner().ok()
+    }
+
+    #[test]
+    fn test_derivation() {
+        let mask = recover::<hash::sha256::Hash>(
+            options::XorKnown::Encoded(vec![
+                (xored("123"), String::from("123")),
+                (xored("98765"), String::from("98765")),
+            ]),
+            "",
+        );
+        assert_eq!(mask, Some(base64::encode(MASK)));
+
+        let mut inconsistent = xored("98765");
+        inconsistent[1] ^= 1;
+        let mask = recover::<hash::sha256::Hash>(
+            options::XorKnown::Encoded(vec![
+                (xored("123"), String::from("123")),
+                (inconsistent, String::from("98765")),
+            ]),
+            "",
+        );
+        assert_eq!(mask, None);
+    }
+
+    #[test]
+    fn test_search() {
+        let hashed = |value: &str| {
+            (
+                hash::md5::Hash::digest("abc", &base64::encode(xored(value)), ""),
+                String::from(value),
+            )
+        };
+
+        let mask = recover(
+            options::XorKnown::Hashes(vec![hashed("12345"), hashed("123"), hashed("9")]),
+            "abc",
+        );
+        assert_eq!(mask, Some(base64::encode(MASK)));
+
+        let mask = recover(options::XorKnown::Hashes(vec![hashed("12345")]), "abc");
+        assert_eq!(mask, None);
+    }
+}